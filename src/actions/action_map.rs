@@ -0,0 +1,228 @@
+use crate::actions::game_control::GameControl;
+use crate::actions::gamepad::GamepadInput;
+use crate::actions::key_bindings::KeyBindings;
+use crate::actions::touch::{TouchControlSettings, TouchControls, TouchMovement};
+use crate::actions::FOLLOW_EPSILON;
+use crate::camera::{GameCamera, GameViewport};
+use crate::player::Player;
+use bevy::ecs::system::SystemParam;
+use bevy::math::Vec3Swizzles;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+/// Everything the player can do, independent of the device it's done with
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    Move,
+    /// Opens and closes the game menu
+    Menu,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ActionKind {
+    /// Has a direction and strength, see [`ActionState::axis`]
+    Axis,
+    /// Is either pressed or not
+    Button,
+}
+
+impl Action {
+    pub const ALL: [Action; 2] = [Action::Move, Action::Menu];
+
+    pub fn kind(self) -> ActionKind {
+        match self {
+            Action::Move => ActionKind::Axis,
+            Action::Menu => ActionKind::Button,
+        }
+    }
+}
+
+/// One way to trigger an action
+/// Button sources have the value `Vec2::X` while held, so they can also drive axis actions
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum InputSource {
+    /// The keys bound to the four direction controls
+    DirectionControls,
+    Key(KeyCode),
+    GamepadButton(GamepadButton),
+    /// The left stick with the dead zone and response curve applied, or the d-pad
+    GamepadMovement,
+    /// From the player towards the finger touching the screen, if touch movement follows the finger
+    /// Ignored while more fingers touch the game, e.g. when pinching to zoom
+    TouchFollow,
+    /// The on-screen joystick, if touch movement uses one
+    TouchJoystick,
+    /// The on-screen button for the action
+    OnScreenButton(Action),
+}
+
+/// Which input sources trigger which action
+#[derive(Resource, Clone, Debug)]
+pub struct ActionMap(pub HashMap<Action, Vec<InputSource>>);
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        Self(HashMap::from([
+            (
+                Action::Move,
+                vec![
+                    InputSource::DirectionControls,
+                    InputSource::GamepadMovement,
+                    InputSource::TouchFollow,
+                    InputSource::TouchJoystick,
+                ],
+            ),
+            (
+                Action::Menu,
+                vec![
+                    InputSource::Key(KeyCode::Escape),
+                    InputSource::GamepadButton(GamepadButton::Start),
+                    InputSource::GamepadButton(GamepadButton::Select),
+                    InputSource::OnScreenButton(Action::Menu),
+                ],
+            ),
+        ]))
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+struct ActionValue {
+    axis: Vec2,
+    previous_axis: Vec2,
+}
+
+/// The state of every [`Action`] in the current frame
+#[derive(Resource, Default, Debug)]
+pub struct ActionState(HashMap<Action, ActionValue>);
+
+impl ActionState {
+    fn value(&self, action: Action) -> ActionValue {
+        self.0.get(&action).copied().unwrap_or_default()
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        self.value(action).axis != Vec2::ZERO
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        let value = self.value(action);
+        value.axis != Vec2::ZERO && value.previous_axis == Vec2::ZERO
+    }
+
+    #[cfg(test)]
+    pub fn just_released(&self, action: Action) -> bool {
+        let value = self.value(action);
+        value.axis == Vec2::ZERO && value.previous_axis != Vec2::ZERO
+    }
+
+    /// Direction and strength of the action, with a length of at most `1.0`
+    pub fn axis(&self, action: Action) -> Vec2 {
+        self.value(action).axis
+    }
+}
+
+/// Everything input sources are read from
+#[derive(SystemParam)]
+pub struct InputSources<'w, 's> {
+    keyboard_input: Res<'w, ButtonInput<KeyCode>>,
+    key_bindings: Res<'w, KeyBindings>,
+    gamepad_input: GamepadInput<'w, 's>,
+    touch_input: Res<'w, Touches>,
+    touch_controls: Res<'w, TouchControls>,
+    touch_settings: Res<'w, TouchControlSettings>,
+    player: Query<'w, 's, &'static Transform, With<Player>>,
+    camera: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<GameCamera>>,
+    viewport: Option<Res<'w, GameViewport>>,
+}
+
+impl InputSources<'_, '_> {
+    fn button(pressed: bool) -> Vec2 {
+        if pressed {
+            Vec2::X
+        } else {
+            Vec2::ZERO
+        }
+    }
+
+    pub fn value(&self, source: InputSource) -> Vec2 {
+        match source {
+            InputSource::DirectionControls => {
+                let pressed = |control: GameControl| {
+                    if control.pressed(&self.key_bindings, &self.keyboard_input) {
+                        1.0
+                    } else {
+                        0.0
+                    }
+                };
+                Vec2::new(
+                    pressed(GameControl::Right) - pressed(GameControl::Left),
+                    pressed(GameControl::Up) - pressed(GameControl::Down),
+                )
+            }
+            InputSource::Key(key) => Self::button(self.keyboard_input.pressed(key)),
+            InputSource::GamepadButton(button) => Self::button(self.gamepad_input.pressed(button)),
+            InputSource::GamepadMovement => self.gamepad_input.movement(),
+            InputSource::TouchFollow => self.touch_follow(),
+            InputSource::TouchJoystick => self.touch_controls.movement(),
+            InputSource::OnScreenButton(action) => {
+                Self::button(self.touch_controls.pressed(action))
+            }
+        }
+    }
+
+    fn touch_follow(&self) -> Vec2 {
+        if self.touch_settings.movement != TouchMovement::FollowFinger {
+            return Vec2::ZERO;
+        }
+        // Fingers on the on-screen buttons don't count
+        let mut touches = self
+            .touch_input
+            .iter()
+            .filter(|touch| !self.touch_controls.captures(touch.id()));
+        let (Some(touch), None) = (touches.next(), touches.next()) else {
+            return Vec2::ZERO;
+        };
+        let touch_position = touch.position();
+        let (Ok((camera, camera_transform)), Ok(player)) =
+            (self.camera.get_single(), self.player.get_single())
+        else {
+            return Vec2::ZERO;
+        };
+        let viewport = self.viewport.as_deref().copied().unwrap_or_default();
+        let Some(touch_position) =
+            viewport.window_to_world(camera, camera_transform, touch_position)
+        else {
+            return Vec2::ZERO;
+        };
+        let diff = touch_position - player.translation.xy();
+        if diff.length() > FOLLOW_EPSILON {
+            diff.normalize()
+        } else {
+            Vec2::ZERO
+        }
+    }
+}
+
+pub fn update_action_state(
+    action_map: Res<ActionMap>,
+    input_sources: InputSources,
+    mut action_state: ResMut<ActionState>,
+) {
+    for action in Action::ALL {
+        let axis: Vec2 = action_map
+            .0
+            .get(&action)
+            .into_iter()
+            .flatten()
+            .map(|source| input_sources.value(*source))
+            .sum();
+        let value = action_state.0.entry(action).or_default();
+        value.previous_axis = value.axis;
+        value.axis = match action.kind() {
+            // Clamp instead of normalizing, so partial stick deflections result in slower movement
+            ActionKind::Axis => axis.clamp_length_max(1.0),
+            ActionKind::Button if axis != Vec2::ZERO => Vec2::X,
+            ActionKind::Button => Vec2::ZERO,
+        };
+    }
+}
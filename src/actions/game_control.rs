@@ -1,5 +1,7 @@
+use crate::actions::key_bindings::KeyBindings;
 use bevy::prelude::{ButtonInput, KeyCode, Res};
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum GameControl {
     Up,
     Down,
@@ -8,28 +10,27 @@ pub enum GameControl {
 }
 
 impl GameControl {
-    pub fn pressed(&self, keyboard_input: &Res<ButtonInput<KeyCode>>) -> bool {
+    pub const ALL: [GameControl; 4] = [
+        GameControl::Up,
+        GameControl::Down,
+        GameControl::Left,
+        GameControl::Right,
+    ];
+
+    pub fn label(&self) -> &'static str {
         match self {
-            GameControl::Up => {
-                keyboard_input.pressed(KeyCode::KeyW) || keyboard_input.pressed(KeyCode::ArrowUp)
-            }
-            GameControl::Down => {
-                keyboard_input.pressed(KeyCode::KeyS) || keyboard_input.pressed(KeyCode::ArrowDown)
-            }
-            GameControl::Left => {
-                keyboard_input.pressed(KeyCode::KeyA) || keyboard_input.pressed(KeyCode::ArrowLeft)
-            }
-            GameControl::Right => {
-                keyboard_input.pressed(KeyCode::KeyD) || keyboard_input.pressed(KeyCode::ArrowRight)
-            }
+            GameControl::Up => "Up",
+            GameControl::Down => "Down",
+            GameControl::Left => "Left",
+            GameControl::Right => "Right",
         }
     }
-}
 
-pub fn get_movement(control: GameControl, input: &Res<ButtonInput<KeyCode>>) -> f32 {
-    if control.pressed(input) {
-        1.0
-    } else {
-        0.0
+    pub fn pressed(
+        &self,
+        key_bindings: &KeyBindings,
+        keyboard_input: &Res<ButtonInput<KeyCode>>,
+    ) -> bool {
+        keyboard_input.any_pressed(key_bindings.get(*self).keys())
     }
 }
@@ -0,0 +1,149 @@
+use bevy::ecs::system::SystemParam;
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How the stick deflection outside of the dead zone is mapped to movement
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum ResponseCurve {
+    #[default]
+    Linear,
+    /// Finer control for small deflections
+    Quadratic,
+    /// Even finer control for small deflections, full speed only close to the edge
+    Cubic,
+}
+
+impl ResponseCurve {
+    fn apply(self, value: f32) -> f32 {
+        match self {
+            ResponseCurve::Linear => value,
+            ResponseCurve::Quadratic => value.powi(2),
+            ResponseCurve::Cubic => value.powi(3),
+        }
+    }
+}
+
+#[derive(Resource, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GamepadInputSettings {
+    /// Stick deflections below this are ignored, between `0.0` and `1.0`
+    pub dead_zone: f32,
+    pub response_curve: ResponseCurve,
+}
+
+impl Default for GamepadInputSettings {
+    fn default() -> Self {
+        Self {
+            dead_zone: 0.2,
+            response_curve: ResponseCurve::Linear,
+        }
+    }
+}
+
+impl GamepadInputSettings {
+    /// Applies the radial dead zone and response curve to a raw stick position
+    pub fn shape_stick(&self, stick: Vec2) -> Vec2 {
+        shape_stick(stick, self.dead_zone, self.response_curve)
+    }
+
+    /// Movement from the left stick, or the d-pad if the stick is at rest
+    pub fn movement(&self, gamepad: &Gamepad) -> Vec2 {
+        let stick = self.shape_stick(gamepad.left_stick());
+        if stick != Vec2::ZERO {
+            stick
+        } else {
+            gamepad.dpad()
+        }
+    }
+}
+
+/// Applies a radial dead zone and response curve to a stick position with a length of up to one
+/// The result is rescaled, so it still covers the full range right outside of the dead zone
+pub fn shape_stick(stick: Vec2, dead_zone: f32, response_curve: ResponseCurve) -> Vec2 {
+    let length = stick.length().min(1.0);
+    if length <= dead_zone {
+        return Vec2::ZERO;
+    }
+    let scaled = (length - dead_zone) / (1.0 - dead_zone);
+    stick.normalize() * response_curve.apply(scaled)
+}
+
+pub struct ConnectedGamepad {
+    pub entity: Entity,
+    pub name: String,
+}
+
+/// All currently connected gamepads, in the order they were connected
+#[derive(Resource, Default)]
+pub struct ConnectedGamepads(pub Vec<ConnectedGamepad>);
+
+pub fn track_gamepad_connections(
+    mut connection_events: EventReader<GamepadConnectionEvent>,
+    mut connected_gamepads: ResMut<ConnectedGamepads>,
+) {
+    for event in connection_events.read() {
+        match &event.connection {
+            GamepadConnection::Connected { name, .. } => {
+                if !connected_gamepads
+                    .0
+                    .iter()
+                    .any(|gamepad| gamepad.entity == event.gamepad)
+                {
+                    info!("Gamepad {name} connected");
+                    connected_gamepads.0.push(ConnectedGamepad {
+                        entity: event.gamepad,
+                        name: name.clone(),
+                    });
+                }
+            }
+            GamepadConnection::Disconnected => {
+                connected_gamepads.0.retain(|gamepad| {
+                    if gamepad.entity == event.gamepad {
+                        info!("Gamepad {} disconnected", gamepad.name);
+                        false
+                    } else {
+                        true
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Combined input of all connected gamepads
+#[derive(SystemParam)]
+pub struct GamepadInput<'w, 's> {
+    settings: Res<'w, GamepadInputSettings>,
+    connected_gamepads: Res<'w, ConnectedGamepads>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl GamepadInput<'_, '_> {
+    fn iter(&self) -> impl Iterator<Item = &Gamepad> {
+        self.connected_gamepads
+            .0
+            .iter()
+            .filter_map(|connected| self.gamepads.get(connected.entity).ok())
+    }
+
+    /// Sum of the movement of all gamepads, not clamped
+    pub fn movement(&self) -> Vec2 {
+        self.iter()
+            .map(|gamepad| self.settings.movement(gamepad))
+            .sum()
+    }
+
+    /// Whether the button is held on any gamepad
+    pub fn pressed(&self, button: GamepadButton) -> bool {
+        self.iter().any(|gamepad| gamepad.pressed(button))
+    }
+
+    /// How far the button is pushed on the gamepad pushing it furthest, between `0.0` and `1.0`
+    /// Only triggers are analog, other buttons are either `0.0` or `1.0`
+    pub fn value(&self, button: GamepadButton) -> f32 {
+        self.iter()
+            .filter_map(|gamepad| gamepad.get(button))
+            .fold(0.0, f32::max)
+    }
+}
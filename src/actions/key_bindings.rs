@@ -0,0 +1,127 @@
+use crate::actions::game_control::GameControl;
+use bevy::prelude::{KeyCode, Resource};
+use serde::{Deserialize, Serialize};
+
+/// Which of the two keys of a [`ControlBinding`] is meant
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BindingSlot {
+    Primary,
+    Secondary,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub struct ControlBinding {
+    pub primary: Option<KeyCode>,
+    pub secondary: Option<KeyCode>,
+}
+
+impl ControlBinding {
+    fn new(primary: KeyCode, secondary: Option<KeyCode>) -> Self {
+        Self {
+            primary: Some(primary),
+            secondary,
+        }
+    }
+
+    pub fn get(&self, slot: BindingSlot) -> Option<KeyCode> {
+        match slot {
+            BindingSlot::Primary => self.primary,
+            BindingSlot::Secondary => self.secondary,
+        }
+    }
+
+    pub fn set(&mut self, slot: BindingSlot, key: Option<KeyCode>) {
+        match slot {
+            BindingSlot::Primary => self.primary = key,
+            BindingSlot::Secondary => self.secondary = key,
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = KeyCode> {
+        self.primary.into_iter().chain(self.secondary)
+    }
+}
+
+/// The keys bound to each [`GameControl`]
+/// Every control has a primary and a secondary key, either of them can be unbound
+#[derive(Resource, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub up: ControlBinding,
+    pub down: ControlBinding,
+    pub left: ControlBinding,
+    pub right: ControlBinding,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            up: ControlBinding::new(KeyCode::KeyW, Some(KeyCode::ArrowUp)),
+            down: ControlBinding::new(KeyCode::KeyS, Some(KeyCode::ArrowDown)),
+            left: ControlBinding::new(KeyCode::KeyA, Some(KeyCode::ArrowLeft)),
+            right: ControlBinding::new(KeyCode::KeyD, Some(KeyCode::ArrowRight)),
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn get(&self, control: GameControl) -> &ControlBinding {
+        match control {
+            GameControl::Up => &self.up,
+            GameControl::Down => &self.down,
+            GameControl::Left => &self.left,
+            GameControl::Right => &self.right,
+        }
+    }
+
+    pub fn get_mut(&mut self, control: GameControl) -> &mut ControlBinding {
+        match control {
+            GameControl::Up => &mut self.up,
+            GameControl::Down => &mut self.down,
+            GameControl::Left => &mut self.left,
+            GameControl::Right => &mut self.right,
+        }
+    }
+
+    /// Finds the control and slot a key is currently bound to
+    pub fn find(&self, key: KeyCode) -> Option<(GameControl, BindingSlot)> {
+        GameControl::ALL.into_iter().find_map(|control| {
+            let binding = self.get(control);
+            if binding.primary == Some(key) {
+                Some((control, BindingSlot::Primary))
+            } else if binding.secondary == Some(key) {
+                Some((control, BindingSlot::Secondary))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Binds `key` to the given slot and returns where it was bound before, if that was somewhere else
+    /// A key can only ever trigger one control, so the previous binding gets removed
+    pub fn bind(
+        &mut self,
+        control: GameControl,
+        slot: BindingSlot,
+        key: KeyCode,
+    ) -> Option<(GameControl, BindingSlot)> {
+        let conflict = self
+            .find(key)
+            .filter(|&existing| existing != (control, slot));
+        if let Some((conflicting_control, conflicting_slot)) = conflict {
+            self.get_mut(conflicting_control)
+                .set(conflicting_slot, None);
+        }
+        self.get_mut(control).set(slot, Some(key));
+        conflict
+    }
+}
+
+/// A short, readable name for a key, e.g. "W" instead of "KeyW"
+pub fn key_label(key: KeyCode) -> String {
+    let name = format!("{key:?}");
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_string()
+}
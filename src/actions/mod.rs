@@ -1,43 +1,78 @@
-use bevy::math::Vec3Swizzles;
+use bevy::input::InputSystem;
 use bevy::prelude::*;
 
-use crate::actions::game_control::{get_movement, GameControl};
+use crate::actions::action_map::update_action_state;
+use crate::actions::gamepad::track_gamepad_connections;
+use crate::actions::touch::{update_touch_controls, TouchControlsPlugin};
 use crate::overlay_state::GameOverlayState;
-use crate::player::Player;
+use crate::settings_file::SettingsFile;
 use crate::AppState;
 
+mod action_map;
 mod game_control;
+mod gamepad;
+mod key_bindings;
+mod touch;
+
+#[cfg(test)]
+pub use action_map::InputSource;
+pub use action_map::{Action, ActionMap, ActionState};
+pub use game_control::GameControl;
+pub use gamepad::{ConnectedGamepads, GamepadInput, GamepadInputSettings};
+pub use key_bindings::{key_label, BindingSlot, KeyBindings};
+pub use touch::{TouchControlSettings, TouchControls, TouchMovement};
 
 pub const FOLLOW_EPSILON: f32 = 5.;
 
 pub struct ActionsPlugin;
 
-// This plugin listens for keyboard input and converts the input into Actions.
-// Actions can then be used as a resource in other systems to act on the player input.
+// This plugin listens for keyboard, mouse, gamepad and touch input and converts the input into actions.
+// Touch input also comes from the on-screen joystick and buttons of the `TouchControlsPlugin`.
+// The `ActionMap` decides which input triggers which `Action`, their state can then be read
+// from the `ActionState` resource in other systems to act on the player input.
 impl Plugin for ActionsPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<Actions>().add_systems(
-            Update,
-            (set_movement_actions, handle_settings_action).run_if(in_state(AppState::InGame)),
-        );
+        app.add_plugins(TouchControlsPlugin)
+            .init_resource::<ActionMap>()
+            .init_resource::<ActionState>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<GamepadInputSettings>()
+            .init_resource::<ConnectedGamepads>()
+            .add_systems(Startup, initialize_input_settings)
+            .add_systems(
+                PreUpdate,
+                (
+                    track_gamepad_connections,
+                    update_touch_controls,
+                    update_action_state.in_set(ActionSystem),
+                )
+                    .chain()
+                    .after(InputSystem),
+            )
+            .add_systems(
+                Update,
+                handle_menu_action.run_if(in_state(AppState::InGame)),
+            );
     }
 }
 
-#[derive(Default, Resource)]
-pub struct Actions {
-    pub player_movement: Option<Vec2>,
-    pub settings: bool,
+/// Updates the [`ActionState`]
+/// Anything consuming input before it becomes an action (e.g. rebinding a key) should run before this set
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ActionSystem;
+
+fn initialize_input_settings(mut commands: Commands, settings_file: Res<SettingsFile>) {
+    commands.insert_resource(settings_file.controls.clone());
+    commands.insert_resource(settings_file.gamepad.clone());
+    commands.insert_resource(settings_file.touch.clone());
 }
 
-fn handle_settings_action(
-    mut actions: ResMut<Actions>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+fn handle_menu_action(
+    action_state: Res<ActionState>,
     mut next_overlay_state: ResMut<NextState<GameOverlayState>>,
     overlay_state: Res<State<GameOverlayState>>,
 ) {
-    actions.settings = keyboard_input.just_pressed(KeyCode::Escape);
-
-    if actions.settings {
+    if action_state.just_pressed(Action::Menu) {
         match overlay_state.get() {
             GameOverlayState::None => {
                 next_overlay_state.set(GameOverlayState::GameMenu);
@@ -45,40 +80,11 @@ fn handle_settings_action(
             GameOverlayState::GameMenu => {
                 next_overlay_state.set(GameOverlayState::None);
             }
-            GameOverlayState::SettingsMenu => {
+            GameOverlayState::SettingsMenu
+            | GameOverlayState::SaveMenu
+            | GameOverlayState::LoadMenu => {
                 next_overlay_state.set(GameOverlayState::GameMenu);
             }
         }
     }
 }
-
-pub fn set_movement_actions(
-    mut actions: ResMut<Actions>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    touch_input: Res<Touches>,
-    player: Query<&Transform, With<Player>>,
-    camera: Query<(&Camera, &GlobalTransform), With<Camera2d>>,
-) {
-    let mut player_movement = Vec2::new(
-        get_movement(GameControl::Right, &keyboard_input)
-            - get_movement(GameControl::Left, &keyboard_input),
-        get_movement(GameControl::Up, &keyboard_input)
-            - get_movement(GameControl::Down, &keyboard_input),
-    );
-
-    if let Some(touch_position) = touch_input.first_pressed_position() {
-        let (camera, camera_transform) = camera.single();
-        if let Ok(touch_position) = camera.viewport_to_world_2d(camera_transform, touch_position) {
-            let diff = touch_position - player.single().translation.xy();
-            if diff.length() > FOLLOW_EPSILON {
-                player_movement = diff.normalize();
-            }
-        }
-    }
-
-    if player_movement != Vec2::ZERO {
-        actions.player_movement = Some(player_movement.normalize());
-    } else {
-        actions.player_movement = None;
-    }
-}
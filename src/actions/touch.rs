@@ -0,0 +1,406 @@
+use crate::actions::gamepad::{shape_stick, ResponseCurve};
+use crate::actions::Action;
+use crate::pause::GameplayState;
+use crate::AppState;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy::window::PrimaryWindow;
+use serde::{Deserialize, Serialize};
+
+/// Distance between the fixed joystick's edge and the window's corner
+const JOYSTICK_MARGIN: f32 = 60.0;
+/// The fixed joystick also reacts to touches a bit outside of its base
+const JOYSTICK_CATCH_RADIUS: f32 = 1.5;
+
+pub struct TouchControlsPlugin;
+
+/// This plugin shows the on-screen joystick and buttons during the game, once the screen was touched
+/// Which touch is on which control is tracked in [`TouchControls`], before the actions are updated
+impl Plugin for TouchControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TouchControls>()
+            .init_resource::<TouchControlSettings>()
+            .add_systems(OnEnter(AppState::InGame), spawn_touch_controls)
+            .add_systems(OnExit(AppState::InGame), despawn_touch_controls)
+            .add_systems(
+                Update,
+                (show_touch_controls, update_touch_control_nodes)
+                    .run_if(in_state(AppState::InGame)),
+            );
+    }
+}
+
+/// How touching the screen moves the player
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum TouchMovement {
+    /// Towards the finger touching the game
+    #[default]
+    FollowFinger,
+    /// With a joystick in the bottom left corner
+    FixedJoystick,
+    /// With a joystick appearing wherever the left half of the screen is touched
+    FloatingJoystick,
+}
+
+impl TouchMovement {
+    pub const ALL: [TouchMovement; 3] = [
+        TouchMovement::FollowFinger,
+        TouchMovement::FixedJoystick,
+        TouchMovement::FloatingJoystick,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TouchMovement::FollowFinger => "Follow Finger",
+            TouchMovement::FixedJoystick => "Fixed Joystick",
+            TouchMovement::FloatingJoystick => "Floating Joystick",
+        }
+    }
+}
+
+#[derive(Resource, Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TouchControlSettings {
+    pub movement: TouchMovement,
+    /// How far the joystick can be pushed, in logical pixels
+    pub joystick_radius: f32,
+    /// Joystick deflections below this are ignored, between `0.0` and `1.0`
+    pub dead_zone: f32,
+}
+
+impl Default for TouchControlSettings {
+    fn default() -> Self {
+        Self {
+            movement: TouchMovement::default(),
+            joystick_radius: 80.0,
+            dead_zone: 0.15,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Corner {
+    TopRight,
+    BottomLeft,
+}
+
+/// A round control placed relative to one of the window's corners
+#[derive(Clone, Copy)]
+struct OnScreenControl {
+    corner: Corner,
+    /// From the corner to the control's center, in logical pixels
+    offset: Vec2,
+    radius: f32,
+}
+
+impl OnScreenControl {
+    /// In window coordinates, like touch positions
+    fn center(&self, window_size: Vec2) -> Vec2 {
+        match self.corner {
+            Corner::TopRight => Vec2::new(window_size.x - self.offset.x, self.offset.y),
+            Corner::BottomLeft => Vec2::new(self.offset.x, window_size.y - self.offset.y),
+        }
+    }
+
+    fn contains(&self, window_size: Vec2, position: Vec2) -> bool {
+        self.center(window_size).distance(position) <= self.radius
+    }
+
+    fn node(&self) -> Node {
+        let edge = Val::Px(self.offset.x - self.radius);
+        let vertical_edge = Val::Px(self.offset.y - self.radius);
+        let mut node = Node {
+            position_type: PositionType::Absolute,
+            width: Val::Px(self.radius * 2.0),
+            height: Val::Px(self.radius * 2.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        };
+        match self.corner {
+            Corner::TopRight => {
+                node.right = edge;
+                node.top = vertical_edge;
+            }
+            Corner::BottomLeft => {
+                node.left = edge;
+                node.bottom = vertical_edge;
+            }
+        }
+        node
+    }
+}
+
+const ON_SCREEN_BUTTONS: [(Action, &str, OnScreenControl); 1] = [(
+    Action::Menu,
+    "Menu",
+    OnScreenControl {
+        corner: Corner::TopRight,
+        offset: Vec2::new(60.0, 60.0),
+        radius: 40.0,
+    },
+)];
+
+fn fixed_joystick(settings: &TouchControlSettings) -> OnScreenControl {
+    OnScreenControl {
+        corner: Corner::BottomLeft,
+        offset: Vec2::splat(settings.joystick_radius + JOYSTICK_MARGIN),
+        radius: settings.joystick_radius,
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct JoystickTouch {
+    id: u64,
+    /// Where the joystick is centered, in window coordinates
+    origin: Vec2,
+    position: Vec2,
+}
+
+/// Which finger is on which on-screen control
+/// Every finger is tracked on its own, so the player can move and press buttons at the same time
+#[derive(Resource, Default, Debug)]
+pub struct TouchControls {
+    /// The controls are only shown once the screen was touched, so they don't get in the way on desktops
+    pub shown: bool,
+    joystick: Option<JoystickTouch>,
+    buttons: HashMap<u64, Action>,
+    movement: Vec2,
+}
+
+impl TouchControls {
+    /// Direction and strength of the joystick, with a length of at most `1.0`
+    pub fn movement(&self) -> Vec2 {
+        self.movement
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        self.buttons.values().any(|pressed| *pressed == action)
+    }
+
+    /// Whether the finger is on one of the controls, so it shouldn't do anything else
+    pub fn captures(&self, id: u64) -> bool {
+        self.buttons.contains_key(&id) || self.joystick.is_some_and(|joystick| joystick.id == id)
+    }
+
+    fn release_all(&mut self) {
+        self.joystick = None;
+        self.buttons.clear();
+        self.movement = Vec2::ZERO;
+    }
+}
+
+pub fn update_touch_controls(
+    touch_input: Res<Touches>,
+    settings: Res<TouchControlSettings>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    ui_scale: Res<UiScale>,
+    gameplay_state: Option<Res<State<GameplayState>>>,
+    mut touch_controls: ResMut<TouchControls>,
+) {
+    if touch_input.any_just_pressed() {
+        touch_controls.shown = true;
+    }
+    // The controls are hidden in the menus, where touches belong to the menu buttons
+    let running = gameplay_state.is_some_and(|state| *state.get() == GameplayState::Running);
+    let (true, Ok(window)) = (running, window.get_single()) else {
+        touch_controls.release_all();
+        return;
+    };
+    // Touches are handled in UI units, so they line up with the controls' nodes at every UI scale
+    let window_size = window.size() / ui_scale.0;
+
+    for touch in touch_input.iter_just_pressed() {
+        let position = touch.position() / ui_scale.0;
+        if let Some((action, _, _)) = ON_SCREEN_BUTTONS
+            .iter()
+            .find(|(_, _, control)| control.contains(window_size, position))
+        {
+            touch_controls.buttons.insert(touch.id(), *action);
+            continue;
+        }
+        if touch_controls.joystick.is_some() {
+            continue;
+        }
+        let base = fixed_joystick(&settings);
+        let origin = match settings.movement {
+            TouchMovement::FixedJoystick
+                if base.center(window_size).distance(position)
+                    <= base.radius * JOYSTICK_CATCH_RADIUS =>
+            {
+                base.center(window_size)
+            }
+            TouchMovement::FloatingJoystick if position.x < window_size.x / 2.0 => position,
+            _ => continue,
+        };
+        touch_controls.joystick = Some(JoystickTouch {
+            id: touch.id(),
+            origin,
+            position,
+        });
+    }
+
+    for touch in touch_input
+        .iter_just_released()
+        .chain(touch_input.iter_just_canceled())
+    {
+        touch_controls.buttons.remove(&touch.id());
+        if touch_controls
+            .joystick
+            .is_some_and(|joystick| joystick.id == touch.id())
+        {
+            touch_controls.joystick = None;
+        }
+    }
+
+    touch_controls.movement = Vec2::ZERO;
+    if let Some(mut joystick) = touch_controls.joystick {
+        if let Some(touch) = touch_input.get_pressed(joystick.id) {
+            joystick.position = touch.position() / ui_scale.0;
+        }
+        touch_controls.joystick = Some(joystick);
+        // Window coordinates point down, the world up
+        let offset = joystick.position - joystick.origin;
+        let stick = Vec2::new(offset.x, -offset.y) / settings.joystick_radius.max(1.0);
+        touch_controls.movement = shape_stick(stick, settings.dead_zone, ResponseCurve::Linear);
+    }
+}
+
+#[derive(Component)]
+struct TouchControlsRoot;
+
+#[derive(Component)]
+struct JoystickBase;
+
+#[derive(Component)]
+struct JoystickKnob;
+
+#[derive(Component)]
+struct OnScreenButton(Action);
+
+fn control_color(pressed: bool) -> Color {
+    if pressed {
+        Color::linear_rgba(0.9, 0.9, 0.9, 0.4)
+    } else {
+        Color::linear_rgba(0.9, 0.9, 0.9, 0.15)
+    }
+}
+
+fn spawn_touch_controls(mut commands: Commands, settings: Res<TouchControlSettings>) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            Visibility::Hidden,
+            TouchControlsRoot,
+        ))
+        .with_children(|parent| {
+            let base = fixed_joystick(&settings);
+            parent
+                .spawn((
+                    base.node(),
+                    BackgroundColor(control_color(false)),
+                    BorderRadius::MAX,
+                    JoystickBase,
+                ))
+                .with_child((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        width: Val::Px(base.radius),
+                        height: Val::Px(base.radius),
+                        ..default()
+                    },
+                    BackgroundColor(control_color(true)),
+                    BorderRadius::MAX,
+                    JoystickKnob,
+                ));
+
+            for (action, label, control) in ON_SCREEN_BUTTONS {
+                parent
+                    .spawn((
+                        control.node(),
+                        BackgroundColor(control_color(false)),
+                        BorderRadius::MAX,
+                        OnScreenButton(action),
+                    ))
+                    .with_child((
+                        Text::new(label),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
+                    ));
+            }
+        });
+}
+
+fn despawn_touch_controls(mut commands: Commands, root: Query<Entity, With<TouchControlsRoot>>) {
+    for entity in &root {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn show_touch_controls(
+    touch_controls: Res<TouchControls>,
+    gameplay_state: Res<State<GameplayState>>,
+    mut root: Query<&mut Visibility, With<TouchControlsRoot>>,
+) {
+    let shown = touch_controls.shown && *gameplay_state.get() == GameplayState::Running;
+    for mut visibility in &mut root {
+        visibility.set_if_neq(if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+fn update_touch_control_nodes(
+    touch_controls: Res<TouchControls>,
+    settings: Res<TouchControlSettings>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    ui_scale: Res<UiScale>,
+    mut joystick_base: Query<(&mut Node, &mut Visibility), With<JoystickBase>>,
+    mut joystick_knob: Query<&mut Node, (With<JoystickKnob>, Without<JoystickBase>)>,
+    mut buttons: Query<(&OnScreenButton, &mut BackgroundColor)>,
+) {
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+
+    let base = fixed_joystick(&settings);
+    // An idle floating joystick waits where the fixed one would be
+    let center = touch_controls
+        .joystick
+        .map(|joystick| joystick.origin)
+        .unwrap_or_else(|| base.center(window.size() / ui_scale.0));
+    if let Ok((mut node, mut visibility)) = joystick_base.get_single_mut() {
+        node.left = Val::Px(center.x - base.radius);
+        node.top = Val::Px(center.y - base.radius);
+        node.right = Val::Auto;
+        node.bottom = Val::Auto;
+        visibility.set_if_neq(if settings.movement == TouchMovement::FollowFinger {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        });
+    }
+    if let Ok(mut node) = joystick_knob.get_single_mut() {
+        let movement = touch_controls.movement();
+        let knob_offset = base.radius / 2.0 + Vec2::new(movement.x, -movement.y) * base.radius;
+        node.left = Val::Px(knob_offset.x);
+        node.top = Val::Px(knob_offset.y);
+    }
+
+    for (button, mut color) in &mut buttons {
+        let wanted = control_color(touch_controls.pressed(button.0));
+        if color.0 != wanted {
+            color.0 = wanted;
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::{fmt, io};
+
+/// Keeps clips with zero-length frames from advancing forever within one frame
+const MIN_FRAME_DURATION: f32 = 0.001;
+
+pub struct SpriteAnimationPlugin;
+
+/// This plugin plays clips of texture atlas frames on sprites with a [`SpriteAnimator`]
+/// The clips are loaded from `.anim.ron` files as [`SpriteAnimations`]
+/// Animations follow virtual time, so they stop while the game is paused
+impl Plugin for SpriteAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<SpriteAnimations>()
+            .init_asset_loader::<SpriteAnimationsLoader>()
+            .add_event::<SpriteAnimationEvent>()
+            .add_systems(Update, animate_sprites.in_set(SpriteAnimationSystem));
+    }
+}
+
+/// Advances the animations and sets the sprites' atlas indices
+/// Systems picking clips should run before it, so the new clip is shown in the same frame
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct SpriteAnimationSystem;
+
+/// The named clips of one sprite sheet
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct SpriteAnimations {
+    pub clips: HashMap<String, SpriteClip>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct SpriteClip {
+    /// Indices into the texture atlas, in the order they are shown
+    pub frames: Vec<usize>,
+    /// Seconds every frame is shown for
+    pub frame_duration: f32,
+    /// Overrides `frame_duration` for single frames, by their position in `frames`
+    #[serde(default)]
+    pub durations: HashMap<usize, f32>,
+    /// Starts over after the last frame instead of holding it
+    #[serde(default)]
+    pub looping: bool,
+    #[serde(default)]
+    pub events: Vec<ClipEvent>,
+}
+
+impl SpriteClip {
+    fn duration(&self, frame: usize) -> f32 {
+        self.durations
+            .get(&frame)
+            .copied()
+            .unwrap_or(self.frame_duration)
+            .max(MIN_FRAME_DURATION)
+    }
+}
+
+/// Sends a [`SpriteAnimationEvent`] whenever the frame at the position in `frames` is shown
+#[derive(Deserialize, Clone, Debug)]
+pub struct ClipEvent {
+    pub frame: usize,
+    pub name: String,
+}
+
+/// A [`ClipEvent`] of a clip played by the entity, e.g. to play footstep sounds
+#[derive(Event, Clone, PartialEq, Debug)]
+pub struct SpriteAnimationEvent {
+    pub entity: Entity,
+    pub name: String,
+}
+
+/// Plays one clip of the [`SpriteAnimations`] at a time on the entity's [`Sprite`]
+/// The sprite needs a texture atlas, whose index is set to the clip's current frame
+#[derive(Component, Debug)]
+#[require(Sprite)]
+pub struct SpriteAnimator {
+    pub animations: Handle<SpriteAnimations>,
+    clip: String,
+    /// Position in the clip's frames
+    frame: usize,
+    /// Time spent on the current frame
+    elapsed: f32,
+    /// The current frame wasn't shown yet, so its events are still due
+    entering: bool,
+    finished: bool,
+}
+
+impl SpriteAnimator {
+    pub fn new(animations: Handle<SpriteAnimations>, clip: &str) -> Self {
+        Self {
+            animations,
+            clip: clip.to_string(),
+            frame: 0,
+            elapsed: 0.0,
+            entering: true,
+            finished: false,
+        }
+    }
+
+    /// Starts the clip from its first frame, unless it is already playing
+    pub fn play(&mut self, clip: &str) {
+        if self.clip != clip {
+            self.restart(clip);
+        }
+    }
+
+    /// Starts the clip from its first frame, even if it is already playing
+    pub fn restart(&mut self, clip: &str) {
+        *self = Self::new(self.animations.clone(), clip);
+    }
+
+    /// Whether a clip that doesn't loop reached the end of its last frame
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Moves on by `delta` seconds and returns the positions of the frames that were entered
+    fn advance(&mut self, clip: &SpriteClip, delta: f32) -> Vec<usize> {
+        let mut entered = Vec::new();
+        if self.entering {
+            self.entering = false;
+            entered.push(self.frame);
+        }
+        if self.finished {
+            return entered;
+        }
+        self.elapsed += delta;
+        while self.elapsed >= clip.duration(self.frame) {
+            let last = self.frame + 1 >= clip.frames.len();
+            if last && !clip.looping {
+                self.finished = true;
+                break;
+            }
+            self.elapsed -= clip.duration(self.frame);
+            self.frame = if last { 0 } else { self.frame + 1 };
+            entered.push(self.frame);
+        }
+        entered
+    }
+}
+
+fn animate_sprites(
+    time: Res<Time>,
+    animations: Res<Assets<SpriteAnimations>>,
+    mut animation_events: EventWriter<SpriteAnimationEvent>,
+    mut animators: Query<(Entity, &mut SpriteAnimator, &mut Sprite)>,
+) {
+    for (entity, mut animator, mut sprite) in &mut animators {
+        let Some(clip) = animations
+            .get(&animator.animations)
+            .and_then(|animations| animations.clips.get(&animator.clip))
+        else {
+            continue;
+        };
+        if clip.frames.is_empty() {
+            continue;
+        }
+        for frame in animator.advance(clip, time.delta_secs()) {
+            for event in clip.events.iter().filter(|event| event.frame == frame) {
+                animation_events.send(SpriteAnimationEvent {
+                    entity,
+                    name: event.name.clone(),
+                });
+            }
+        }
+        if let Some(atlas) = &mut sprite.texture_atlas {
+            let index = clip.frames[animator.frame.min(clip.frames.len() - 1)];
+            if atlas.index != index {
+                atlas.index = index;
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct SpriteAnimationsLoader;
+
+#[derive(Debug)]
+pub enum SpriteAnimationsLoaderError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+}
+
+impl fmt::Display for SpriteAnimationsLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpriteAnimationsLoaderError::Io(error) => write!(f, "could not read the file: {error}"),
+            SpriteAnimationsLoaderError::Parse(error) => write!(f, "invalid animations: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SpriteAnimationsLoaderError {}
+
+impl From<io::Error> for SpriteAnimationsLoaderError {
+    fn from(error: io::Error) -> Self {
+        SpriteAnimationsLoaderError::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for SpriteAnimationsLoaderError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        SpriteAnimationsLoaderError::Parse(error)
+    }
+}
+
+impl AssetLoader for SpriteAnimationsLoader {
+    type Asset = SpriteAnimations;
+    type Settings = ();
+    type Error = SpriteAnimationsLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["anim.ron"]
+    }
+}
@@ -1,54 +1,190 @@
-use crate::actions::{set_movement_actions, Actions};
+use crate::actions::{Action, ActionState};
 use crate::loading::AudioAssets;
+use crate::pause::GameplayState;
+use crate::settings_file::SettingsFile;
 use crate::AppState;
 use bevy::prelude::*;
 use bevy_kira_audio::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How long sounds take to fade out when the game is paused, and to fade back in afterwards
+const PAUSE_FADE: Duration = Duration::from_millis(250);
+/// The flying loop plays quieter than the other sound effects
+const FLYING_VOLUME: f64 = 0.3;
 
 pub struct InternalAudioPlugin;
 
 // This plugin is responsible to control the game audio
+// Every sound is played on one of the channels below, their volume follows the `AudioSettings`
+// Sound effects get their volume when they start instead of from their channel, so the flying loop can be quieter
 impl Plugin for InternalAudioPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(AudioPlugin)
+            .add_audio_channel::<Music>()
+            .add_audio_channel::<Sfx>()
+            .init_resource::<AudioSettings>()
+            .init_resource::<PausedAudio>()
+            .add_event::<PlaySfx>()
+            .add_systems(Startup, initialize_audio_settings)
+            .add_systems(
+                Update,
+                apply_audio_settings.run_if(resource_changed::<AudioSettings>),
+            )
             .add_systems(OnEnter(AppState::InGame), start_audio)
+            .add_systems(OnExit(AppState::InGame), stop_audio)
+            .add_systems(OnEnter(GameplayState::Paused), pause_audio)
+            .add_systems(OnExit(GameplayState::Paused), resume_audio)
             .add_systems(
                 Update,
-                control_flying_sound
-                    .after(set_movement_actions)
-                    .run_if(in_state(AppState::InGame)),
-            );
+                control_flying_sound.run_if(in_state(GameplayState::Running)),
+            )
+            .add_systems(PostUpdate, play_sfx);
+    }
+}
+
+/// Audio channel for background music
+#[derive(Resource)]
+pub struct Music;
+
+/// Audio channel for sound effects
+#[derive(Resource)]
+pub struct Sfx;
+
+/// Plays a sound effect once on the [`Sfx`] channel
+#[derive(Event, Clone, PartialEq, Debug)]
+pub struct PlaySfx(pub Handle<AudioSource>);
+
+/// Volume levels between `0.0` and `1.0`
+/// The music and sound effect levels are scaled by the master level
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            music: 0.8,
+            sfx: 0.9,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn music_volume(&self) -> f64 {
+        (self.master * self.music) as f64
+    }
+
+    pub fn sfx_volume(&self) -> f64 {
+        (self.master * self.sfx) as f64
+    }
+}
+
+fn initialize_audio_settings(mut commands: Commands, settings_file: Res<SettingsFile>) {
+    commands.insert_resource(settings_file.audio.clone());
+}
+
+// Setting the volume of the sfx channel would replace the flying loop's lower volume
+fn apply_audio_settings(
+    audio_settings: Res<AudioSettings>,
+    music: Res<AudioChannel<Music>>,
+    flying_audio: Option<ResMut<FlyingAudio>>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    music.set_volume(audio_settings.music_volume());
+    if let Some(mut flying_audio) = flying_audio {
+        flying_audio.volume = FLYING_VOLUME * audio_settings.sfx_volume();
+        if let Some(instance) = audio_instances.get_mut(&flying_audio.instance) {
+            instance.set_volume(flying_audio.volume, AudioTween::default());
+        }
     }
 }
 
+/// The looping sound played while the player moves
 #[derive(Resource)]
-struct FlyingAudio(Handle<AudioInstance>);
+pub struct FlyingAudio {
+    pub instance: Handle<AudioInstance>,
+    /// Follows the sound effects volume, but quieter
+    pub volume: f64,
+}
 
-fn start_audio(mut commands: Commands, audio_assets: Res<AudioAssets>, audio: Res<Audio>) {
-    audio.pause();
-    let handle = audio
+fn start_audio(
+    mut commands: Commands,
+    audio_assets: Res<AudioAssets>,
+    audio_settings: Res<AudioSettings>,
+    sfx: Res<AudioChannel<Sfx>>,
+) {
+    let volume = FLYING_VOLUME * audio_settings.sfx_volume();
+    // Only this sound starts paused, the channel keeps playing the other sound effects
+    let instance = sfx
         .play(audio_assets.flying.clone())
         .looped()
-        .with_volume(0.3)
+        .with_volume(volume)
+        .paused()
         .handle();
-    commands.insert_resource(FlyingAudio(handle));
+    commands.insert_resource(FlyingAudio { instance, volume });
+}
+
+fn play_sfx(
+    mut sfx_events: EventReader<PlaySfx>,
+    audio_settings: Res<AudioSettings>,
+    sfx: Res<AudioChannel<Sfx>>,
+) {
+    for PlaySfx(source) in sfx_events.read() {
+        sfx.play(source.clone())
+            .with_volume(audio_settings.sfx_volume());
+    }
+}
+
+fn stop_audio(mut commands: Commands, sfx: Res<AudioChannel<Sfx>>) {
+    sfx.stop();
+    commands.remove_resource::<FlyingAudio>();
+}
+
+/// Sounds that were playing when the game got paused and are resumed with it
+#[derive(Resource, Default)]
+struct PausedAudio(Vec<AssetId<AudioInstance>>);
+
+fn pause_audio(
+    mut paused_audio: ResMut<PausedAudio>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    for (id, instance) in audio_instances.iter_mut() {
+        if let PlaybackState::Playing { .. } = instance.state() {
+            instance.pause(AudioTween::linear(PAUSE_FADE));
+            paused_audio.0.push(id);
+        }
+    }
+}
+
+fn resume_audio(
+    mut paused_audio: ResMut<PausedAudio>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    for id in paused_audio.0.drain(..) {
+        if let Some(instance) = audio_instances.get_mut(id) {
+            instance.resume(AudioTween::linear(PAUSE_FADE));
+        }
+    }
 }
 
 fn control_flying_sound(
-    actions: Res<Actions>,
+    action_state: Res<ActionState>,
     audio: Res<FlyingAudio>,
     mut audio_instances: ResMut<Assets<AudioInstance>>,
 ) {
-    if let Some(instance) = audio_instances.get_mut(&audio.0) {
+    if let Some(instance) = audio_instances.get_mut(&audio.instance) {
         match instance.state() {
-            PlaybackState::Paused { .. } => {
-                if actions.player_movement.is_some() {
-                    instance.resume(AudioTween::default());
-                }
+            PlaybackState::Paused { .. } if action_state.pressed(Action::Move) => {
+                instance.resume(AudioTween::default());
             }
-            PlaybackState::Playing { .. } => {
-                if actions.player_movement.is_none() {
-                    instance.pause(AudioTween::default());
-                }
+            PlaybackState::Playing { .. } if !action_state.pressed(Action::Move) => {
+                instance.pause(AudioTween::default());
             }
             _ => {}
         }
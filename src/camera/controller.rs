@@ -0,0 +1,335 @@
+use crate::camera::zoom::{apply_camera_zoom, zoom_camera};
+use crate::camera::GameRenderResolution;
+use crate::pause::GameplayState;
+use crate::physics::Velocity;
+use crate::AppState;
+use bevy::math::Vec3Swizzles;
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+pub struct CameraControllerPlugin;
+
+/// This plugin moves the game camera along with the entities it should keep in view,
+/// and lets the player zoom with the mouse wheel, by pinching or with the gamepad triggers
+/// Like other gameplay, the camera stands still while the game is paused
+impl Plugin for CameraControllerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CameraShake>()
+            .add_systems(
+                PostUpdate,
+                (
+                    add_camera_trauma,
+                    zoom_camera,
+                    follow_camera_targets,
+                    apply_camera_zoom,
+                )
+                    .chain()
+                    .run_if(in_state(GameplayState::Running))
+                    // After gameplay moved the targets, before the camera's `GlobalTransform` is updated
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .add_systems(OnExit(AppState::InGame), reset_camera);
+    }
+}
+
+/// Entities the game camera keeps in view
+/// Their `Transform` is used, so they shouldn't have a parent
+#[derive(Component, Default)]
+pub struct CameraTarget;
+
+/// Shakes the game camera, e.g. when something hits the player
+/// Trauma from several events adds up, see [`CameraController::trauma`]
+#[derive(Event, Clone, Copy, Debug)]
+pub struct CameraShake {
+    /// Between `0.0` and `1.0`
+    pub trauma: f32,
+}
+
+/// How the camera catches up with its targets
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum CameraSmoothing {
+    /// Always centered on the targets
+    None,
+    /// Covers most of the remaining distance within `1 / speed` seconds, and starts moving abruptly
+    Lerp { speed: f32 },
+    /// A critically damped spring, which speeds up and slows down smoothly without overshooting
+    /// Higher frequencies follow more tightly
+    Spring { frequency: f32 },
+}
+
+/// Makes the game camera follow every [`CameraTarget`]
+#[derive(Component, Clone, Debug)]
+pub struct CameraController {
+    pub smoothing: CameraSmoothing,
+    /// How many seconds of the targets' movement the camera looks ahead
+    pub look_ahead_time: f32,
+    /// Limits the look-ahead in world units
+    pub max_look_ahead: f32,
+    /// Half the size of the area around the camera's center the targets can move in without moving the camera
+    pub dead_zone: Vec2,
+    /// The camera never shows anything outside of these bounds, if set
+    pub bounds: Option<Rect>,
+    /// Largest distance in world units the camera is moved by shaking
+    pub max_shake_offset: Vec2,
+    /// Largest rotation in radians caused by shaking
+    pub max_shake_angle: f32,
+    /// How much trauma is lost per second
+    pub trauma_decay: f32,
+    /// Current amount of shaking between `0.0` and `1.0`
+    pub trauma: f32,
+    /// How much larger things appear than at the render resolution, which is fully visible at `1.0`
+    /// The camera eases towards it, see [`CameraController::current_zoom`]
+    pub zoom: f32,
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+    /// How quickly the camera eases towards a new zoom
+    pub zoom_speed: f32,
+    /// Zoom and move so all targets are visible, instead of the player deciding the zoom
+    /// The camera stays centered on the targets, without look-ahead or dead zone
+    pub frame_targets: bool,
+    /// Space in world units kept between the framed targets and the edge of the view
+    pub framing_margin: f32,
+    /// Where the camera looks without shaking
+    position: Vec2,
+    current_zoom: f32,
+    /// Used by the spring
+    velocity: Vec2,
+    /// Where the targets were in the last frame, `None` to jump to them right away
+    previous_focus: Option<Vec2>,
+}
+
+impl Default for CameraController {
+    fn default() -> Self {
+        Self {
+            smoothing: CameraSmoothing::Spring { frequency: 1.5 },
+            look_ahead_time: 0.5,
+            max_look_ahead: 150.0,
+            dead_zone: Vec2::new(60.0, 40.0),
+            bounds: None,
+            max_shake_offset: Vec2::splat(30.0),
+            max_shake_angle: 0.05,
+            trauma_decay: 1.0,
+            trauma: 0.0,
+            zoom: 1.0,
+            min_zoom: 0.5,
+            max_zoom: 3.0,
+            zoom_speed: 8.0,
+            frame_targets: false,
+            framing_margin: 100.0,
+            position: Vec2::ZERO,
+            current_zoom: 1.0,
+            velocity: Vec2::ZERO,
+            previous_focus: None,
+        }
+    }
+}
+
+impl CameraController {
+    /// Where the camera looks, without shaking
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    /// The zoom the camera shows right now
+    pub fn current_zoom(&self) -> f32 {
+        self.current_zoom
+    }
+
+    /// Moves the camera to the targets without smoothing in the next frame, e.g. after a teleport
+    pub fn snap(&mut self) {
+        self.previous_focus = None;
+    }
+
+    fn reset(&mut self) {
+        self.position = Vec2::ZERO;
+        self.velocity = Vec2::ZERO;
+        self.previous_focus = None;
+        self.trauma = 0.0;
+        self.zoom = 1.0;
+        self.current_zoom = 1.0;
+    }
+
+    /// Moves the camera's position towards `goal`
+    fn smooth_towards(&mut self, goal: Vec2, delta_secs: f32) {
+        match self.smoothing {
+            CameraSmoothing::None => {
+                self.position = goal;
+                self.velocity = Vec2::ZERO;
+            }
+            CameraSmoothing::Lerp { speed } => {
+                // Framerate independent, unlike lerping by `speed * delta`
+                let factor = 1.0 - (-speed * delta_secs).exp();
+                self.position = self.position.lerp(goal, factor);
+                self.velocity = Vec2::ZERO;
+            }
+            CameraSmoothing::Spring { frequency } => {
+                // Exact solution of the spring for this time step, so it's stable at any framerate
+                let omega = frequency * std::f32::consts::TAU;
+                let offset = self.position - goal;
+                let impulse = (self.velocity + omega * offset) * delta_secs;
+                let decay = (-omega * delta_secs).exp();
+                self.velocity = (self.velocity - omega * impulse) * decay;
+                self.position = goal + (offset + impulse) * decay;
+            }
+        }
+    }
+
+    /// Eases the current zoom towards the wanted one
+    /// Interpolates the logarithm, so zooming in and out feel equally fast
+    fn smooth_zoom(&mut self, delta_secs: f32) {
+        let factor = 1.0 - (-self.zoom_speed * delta_secs).exp();
+        let zoom = self.current_zoom.ln().lerp(self.zoom.ln(), factor).exp();
+        self.current_zoom = if (zoom - self.zoom).abs() < 1e-4 {
+            self.zoom
+        } else {
+            zoom
+        };
+    }
+
+    /// Keeps a view of the given half size inside the bounds
+    fn clamp_to_bounds(&self, position: Vec2, half_view: Vec2) -> Vec2 {
+        let Some(bounds) = self.bounds else {
+            return position;
+        };
+        let min = bounds.min + half_view;
+        let max = bounds.max - half_view;
+        // Bounds smaller than the view are centered instead
+        Vec2::new(
+            if min.x <= max.x {
+                position.x.clamp(min.x, max.x)
+            } else {
+                bounds.center().x
+            },
+            if min.y <= max.y {
+                position.y.clamp(min.y, max.y)
+            } else {
+                bounds.center().y
+            },
+        )
+    }
+}
+
+fn add_camera_trauma(
+    mut shake_events: EventReader<CameraShake>,
+    mut controllers: Query<&mut CameraController>,
+) {
+    for event in shake_events.read() {
+        for mut controller in &mut controllers {
+            controller.trauma = (controller.trauma + event.trauma).clamp(0.0, 1.0);
+        }
+    }
+}
+
+fn follow_camera_targets(
+    time: Res<Time>,
+    render_resolution: Res<GameRenderResolution>,
+    targets: Query<
+        (&Transform, Option<&Velocity>),
+        (With<CameraTarget>, Without<CameraController>),
+    >,
+    mut cameras: Query<(
+        &mut CameraController,
+        &mut Transform,
+        &OrthographicProjection,
+    )>,
+) {
+    let delta_secs = time.delta_secs();
+    // The smallest area containing all targets
+    let target_area = targets
+        .iter()
+        .map(|(transform, _)| transform.translation.xy())
+        .fold(None, |area: Option<Rect>, position| {
+            Some(
+                area.map_or(Rect::from_center_size(position, Vec2::ZERO), |area| {
+                    area.union_point(position)
+                }),
+            )
+        });
+    // Physics moves targets in fixed steps, so their velocity is steadier than how far they moved this frame
+    let target_velocity = targets
+        .iter()
+        .map(|(_, velocity)| velocity.map(|velocity| velocity.0))
+        .sum::<Option<Vec2>>()
+        .map(|velocity| velocity / targets.iter().len() as f32);
+    // At least the render resolution is visible at a zoom of `1.0`, whatever the scaling mode
+    let resolution = Vec2::new(render_resolution.width, render_resolution.height).max(Vec2::ONE);
+
+    for (mut controller, mut transform, projection) in &mut cameras {
+        let half_view = projection.area.half_size();
+        if let Some(target_area) = target_area {
+            let focus = target_area.center();
+            if controller.frame_targets {
+                let framed_size = target_area.size() + 2.0 * controller.framing_margin;
+                let zoom = (resolution / framed_size.max(Vec2::ONE)).min_element();
+                controller.zoom = zoom.clamp(controller.min_zoom, controller.max_zoom);
+            }
+            match controller.previous_focus {
+                Some(_) if controller.frame_targets => {
+                    let goal = controller.clamp_to_bounds(focus, half_view);
+                    controller.smooth_towards(goal, delta_secs);
+                }
+                Some(previous_focus) => {
+                    let velocity = match target_velocity {
+                        Some(velocity) => velocity,
+                        None if delta_secs > 0.0 => (focus - previous_focus) / delta_secs,
+                        None => Vec2::ZERO,
+                    };
+                    let look_ahead = (velocity * controller.look_ahead_time)
+                        .clamp_length_max(controller.max_look_ahead);
+                    // Only the part of the distance that leaves the dead zone moves the camera
+                    let distance = focus + look_ahead - controller.position;
+                    let outside_dead_zone =
+                        distance - distance.clamp(-controller.dead_zone, controller.dead_zone);
+                    let goal = controller
+                        .clamp_to_bounds(controller.position + outside_dead_zone, half_view);
+                    controller.smooth_towards(goal, delta_secs);
+                }
+                None => {
+                    controller.position = focus;
+                    controller.velocity = Vec2::ZERO;
+                    controller.current_zoom = controller.zoom;
+                }
+            }
+            controller.previous_focus = Some(focus);
+        }
+        controller.smooth_zoom(delta_secs);
+        let position = controller.clamp_to_bounds(controller.position, half_view);
+        controller.position = position;
+
+        // Trauma is squared, so small hits barely shake while big ones feel violent
+        let shake = controller.trauma * controller.trauma;
+        let seconds = time.elapsed_secs();
+        let offset = controller.max_shake_offset
+            * shake
+            * Vec2::new(shake_noise(seconds, 0.0), shake_noise(seconds, 1.0));
+        let angle = controller.max_shake_angle * shake * shake_noise(seconds, 2.0);
+        controller.trauma = (controller.trauma - controller.trauma_decay * delta_secs).max(0.0);
+
+        transform.translation.x = position.x + offset.x;
+        transform.translation.y = position.y + offset.y;
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+}
+
+/// Smooth pseudo random value between `-1.0` and `1.0`, different for every seed
+fn shake_noise(seconds: f32, seed: f32) -> f32 {
+    let t = seconds * 25.0 + seed * 17.0;
+    (t.sin() + (t * 2.3 + seed).sin() * 0.5 + (t * 5.7 + seed * 3.0).sin() * 0.25) / 1.75
+}
+
+// The next game starts with a calm camera at the origin
+fn reset_camera(
+    mut cameras: Query<(
+        &mut CameraController,
+        &mut Transform,
+        &mut OrthographicProjection,
+    )>,
+) {
+    for (mut controller, mut transform, mut projection) in &mut cameras {
+        controller.reset();
+        projection.scale = 1.0;
+        transform.translation.x = 0.0;
+        transform.translation.y = 0.0;
+        transform.rotation = Quat::IDENTITY;
+    }
+}
@@ -0,0 +1,262 @@
+use crate::camera::controller::CameraControllerPlugin;
+use bevy::image::ImageSampler;
+use bevy::prelude::*;
+use bevy::render::camera::{RenderTarget, ScalingMode};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages};
+use bevy::render::view::RenderLayers;
+use bevy::window::{PrimaryWindow, WindowResized};
+use serde::{Deserialize, Serialize};
+
+mod controller;
+mod zoom;
+
+pub use controller::{CameraController, CameraShake, CameraSmoothing, CameraTarget};
+
+/// Render layer of the image the game is rendered to, when it isn't rendered to the window directly
+const PRESENTATION_LAYER: usize = 1;
+
+pub struct CameraPlugin;
+
+#[derive(Resource)]
+pub struct GameRenderResolution {
+    pub width: f32,
+    pub height: f32,
+    pub scaling: RenderScaling,
+    /// Fills the parts of the window the game doesn't cover
+    pub bar_color: Color,
+}
+
+impl Default for GameRenderResolution {
+    fn default() -> Self {
+        Self {
+            width: 1920.0,
+            height: 1080.0,
+            scaling: RenderScaling::default(),
+            bar_color: Color::BLACK,
+        }
+    }
+}
+
+/// How the render resolution is fit into the window
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum RenderScaling {
+    /// At least the render resolution is visible, the window's longer side shows more of the world
+    #[default]
+    AutoMin,
+    /// Exactly the render resolution is visible, with bars where the window has a different aspect ratio
+    Letterbox,
+    /// Rendered at the render resolution and only scaled up by whole numbers, so pixels stay sharp
+    PixelPerfect,
+    /// Rendered at the render resolution and smoothly scaled up to fit the window
+    Upscale,
+}
+
+impl RenderScaling {
+    pub const ALL: [RenderScaling; 4] = [
+        RenderScaling::AutoMin,
+        RenderScaling::Letterbox,
+        RenderScaling::PixelPerfect,
+        RenderScaling::Upscale,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RenderScaling::AutoMin => "Expand",
+            RenderScaling::Letterbox => "Letterbox",
+            RenderScaling::PixelPerfect => "Pixel Perfect",
+            RenderScaling::Upscale => "Upscale",
+        }
+    }
+}
+
+/// The camera looking at the game world
+/// Other cameras only exist to show its image in the window
+#[derive(Component)]
+pub struct GameCamera;
+
+/// Shows the image the game camera renders to, and all UI
+#[derive(Component)]
+struct PresentationCamera;
+
+#[derive(Component)]
+struct PresentationSprite;
+
+/// Where the game is shown in the window
+/// A window position `p` is at `(p - offset) * scale` on the game camera's render target
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct GameViewport {
+    pub offset: Vec2,
+    pub scale: Vec2,
+}
+
+impl Default for GameViewport {
+    fn default() -> Self {
+        Self {
+            offset: Vec2::ZERO,
+            scale: Vec2::ONE,
+        }
+    }
+}
+
+impl GameViewport {
+    /// The world position shown at the given window position
+    pub fn window_to_world(
+        &self,
+        camera: &Camera,
+        camera_transform: &GlobalTransform,
+        window_position: Vec2,
+    ) -> Option<Vec2> {
+        camera
+            .viewport_to_world_2d(
+                camera_transform,
+                (window_position - self.offset) * self.scale,
+            )
+            .ok()
+    }
+}
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(CameraControllerPlugin)
+            .init_resource::<GameRenderResolution>()
+            .init_resource::<GameViewport>()
+            .add_systems(Startup, setup_camera)
+            .add_systems(
+                Update,
+                update_camera_scaling
+                    .run_if(resource_changed::<GameRenderResolution>.or(on_event::<WindowResized>)),
+            );
+    }
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn((Camera2d, GameCamera, CameraController::default()));
+    commands.spawn((
+        Camera2d,
+        Camera {
+            // After the game camera, so the UI is drawn on top of the game
+            order: 1,
+            clear_color: ClearColorConfig::None,
+            ..default()
+        },
+        RenderLayers::layer(PRESENTATION_LAYER),
+        IsDefaultUiCamera,
+        PresentationCamera,
+    ));
+    commands.spawn((
+        Sprite::default(),
+        Visibility::Hidden,
+        RenderLayers::layer(PRESENTATION_LAYER),
+        PresentationSprite,
+    ));
+}
+
+/// The largest size with the aspect ratio of `size` that fits into `available`
+fn fit(size: Vec2, available: Vec2) -> Vec2 {
+    size * (available / size).min_element()
+}
+
+// Only runs when something changed, since recreating the render target is expensive
+fn update_camera_scaling(
+    render_resolution: Res<GameRenderResolution>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut game_camera: Query<(&mut Camera, &mut OrthographicProjection), With<GameCamera>>,
+    mut presentation_camera: Query<&mut Camera, (With<PresentationCamera>, Without<GameCamera>)>,
+    mut sprite: Query<(&mut Sprite, &mut Visibility), With<PresentationSprite>>,
+    mut images: ResMut<Assets<Image>>,
+    mut viewport: ResMut<GameViewport>,
+) {
+    let (Ok(window), Ok((mut camera, mut projection))) =
+        (window.get_single(), game_camera.get_single_mut())
+    else {
+        return;
+    };
+    let resolution = Vec2::new(render_resolution.width, render_resolution.height).max(Vec2::ONE);
+    let window_size = window.size().max(Vec2::ONE);
+    let scale_factor = window.scale_factor();
+
+    // Size of the image the game is rendered to, and its size in the window in logical pixels
+    let (target_size, shown_size) = match render_resolution.scaling {
+        RenderScaling::AutoMin => {
+            camera.target = RenderTarget::default();
+            projection.scaling_mode = ScalingMode::AutoMin {
+                min_width: resolution.x,
+                min_height: resolution.y,
+            };
+            *viewport = GameViewport::default();
+            if let Ok(mut presentation_camera) = presentation_camera.get_single_mut() {
+                presentation_camera.clear_color = ClearColorConfig::None;
+            }
+            if let Ok((_, mut visibility)) = sprite.get_single_mut() {
+                *visibility = Visibility::Hidden;
+            }
+            return;
+        }
+        // Rendered at the window's resolution, so it's as sharp as rendering to the window directly
+        RenderScaling::Letterbox => {
+            let shown_size = fit(resolution, window_size);
+            ((shown_size * scale_factor).round(), shown_size)
+        }
+        RenderScaling::PixelPerfect => {
+            let physical_scale = (window_size * scale_factor / resolution)
+                .min_element()
+                .floor()
+                .max(1.0);
+            (resolution, resolution * physical_scale / scale_factor)
+        }
+        RenderScaling::Upscale => (resolution, fit(resolution, window_size)),
+    };
+    projection.scaling_mode = ScalingMode::Fixed {
+        width: resolution.x,
+        height: resolution.y,
+    };
+    *viewport = GameViewport {
+        offset: (window_size - shown_size) / 2.0,
+        scale: target_size / shown_size,
+    };
+
+    // Reuse the current render target if there is one
+    let size = Extent3d {
+        width: target_size.x as u32,
+        height: target_size.y as u32,
+        ..default()
+    };
+    let handle = match &camera.target {
+        RenderTarget::Image(handle) if images.contains(handle) => handle.clone(),
+        _ => images.add(render_target_image(size)),
+    };
+    if let Some(image) = images.get_mut(&handle) {
+        if image.texture_descriptor.size != size {
+            image.resize(size);
+        }
+        image.sampler = if render_resolution.scaling == RenderScaling::PixelPerfect {
+            ImageSampler::nearest()
+        } else {
+            ImageSampler::linear()
+        };
+    }
+    camera.target = RenderTarget::Image(handle.clone());
+
+    if let Ok(mut presentation_camera) = presentation_camera.get_single_mut() {
+        presentation_camera.clear_color = ClearColorConfig::Custom(render_resolution.bar_color);
+    }
+    if let Ok((mut sprite, mut visibility)) = sprite.get_single_mut() {
+        sprite.image = handle;
+        sprite.custom_size = Some(shown_size);
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn render_target_image(size: Extent3d) -> Image {
+    let mut image = Image::new_fill(
+        size,
+        TextureDimension::D2,
+        &[0, 0, 0, 255],
+        TextureFormat::Bgra8UnormSrgb,
+        RenderAssetUsages::default(),
+    );
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    image
+}
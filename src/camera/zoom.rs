@@ -0,0 +1,106 @@
+use crate::actions::{GamepadInput, TouchControls};
+use crate::camera::controller::CameraController;
+use crate::camera::{GameRenderResolution, RenderScaling};
+use bevy::ecs::system::SystemParam;
+use bevy::input::mouse::{AccumulatedMouseScroll, MouseScrollUnit};
+use bevy::prelude::*;
+
+/// Zoom factor per line the mouse wheel is scrolled
+const WHEEL_ZOOM_STEP: f32 = 1.1;
+/// Touchpads scroll by pixels, this many of them count as one line
+const PIXELS_PER_LINE: f32 = 50.0;
+/// Zoom factor per second with a trigger fully pushed
+const TRIGGER_ZOOM_SPEED: f32 = 2.0;
+
+/// Everything the player can zoom the game camera with
+#[derive(SystemParam)]
+pub struct ZoomInput<'w, 's> {
+    mouse_scroll: Res<'w, AccumulatedMouseScroll>,
+    touch_input: Res<'w, Touches>,
+    touch_controls: Res<'w, TouchControls>,
+    gamepad_input: GamepadInput<'w, 's>,
+}
+
+impl ZoomInput<'_, '_> {
+    /// How much the player zoomed in this frame, below `1.0` for zooming out
+    pub fn factor(&self, delta_secs: f32) -> f32 {
+        self.wheel_factor() * self.pinch_factor() * self.trigger_factor(delta_secs)
+    }
+
+    fn wheel_factor(&self) -> f32 {
+        let lines = match self.mouse_scroll.unit {
+            MouseScrollUnit::Line => self.mouse_scroll.delta.y,
+            MouseScrollUnit::Pixel => self.mouse_scroll.delta.y / PIXELS_PER_LINE,
+        };
+        WHEEL_ZOOM_STEP.powf(lines)
+    }
+
+    /// How much further apart two fingers on the screen moved since the last frame
+    /// Fingers on the on-screen controls don't count
+    fn pinch_factor(&self) -> f32 {
+        let mut touches = self
+            .touch_input
+            .iter()
+            .filter(|touch| !self.touch_controls.captures(touch.id()));
+        let (Some(first), Some(second), None) = (touches.next(), touches.next(), touches.next())
+        else {
+            return 1.0;
+        };
+        let previous_distance = first
+            .previous_position()
+            .distance(second.previous_position());
+        let distance = first.position().distance(second.position());
+        if previous_distance > 0.0 && distance > 0.0 {
+            distance / previous_distance
+        } else {
+            1.0
+        }
+    }
+
+    /// The right trigger zooms in, the left one out
+    fn trigger_factor(&self, delta_secs: f32) -> f32 {
+        let direction = self.gamepad_input.value(GamepadButton::RightTrigger2)
+            - self.gamepad_input.value(GamepadButton::LeftTrigger2);
+        TRIGGER_ZOOM_SPEED.powf(direction * delta_secs)
+    }
+}
+
+pub(super) fn zoom_camera(
+    time: Res<Time>,
+    zoom_input: ZoomInput,
+    mut cameras: Query<&mut CameraController>,
+) {
+    let factor = zoom_input.factor(time.delta_secs());
+    if factor == 1.0 {
+        return;
+    }
+    for mut controller in &mut cameras {
+        // Framing decides the zoom by itself
+        if !controller.frame_targets {
+            let zoom = controller.zoom * factor;
+            controller.zoom = zoom.clamp(controller.min_zoom, controller.max_zoom);
+        }
+    }
+}
+
+// The scaling modes only set the projection's scaling mode, so zooming works the same in all of them
+pub(super) fn apply_camera_zoom(
+    render_resolution: Res<GameRenderResolution>,
+    mut cameras: Query<(&CameraController, &mut OrthographicProjection)>,
+) {
+    for (controller, mut projection) in &mut cameras {
+        let mut zoom = controller.current_zoom();
+        if render_resolution.scaling == RenderScaling::PixelPerfect {
+            // Every world pixel covers the same number of rendered pixels, so they stay sharp
+            zoom = if zoom >= 1.0 {
+                zoom.round()
+            } else {
+                1.0 / (1.0 / zoom).round()
+            };
+        }
+        let scale = 1.0 / zoom;
+        if projection.scale != scale {
+            projection.scale = scale;
+        }
+    }
+}
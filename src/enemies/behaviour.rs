@@ -0,0 +1,173 @@
+use crate::enemies::{Enemy, EnemyArchetypes};
+use crate::loading::EnemyAssets;
+use crate::physics::Velocity;
+use crate::player::{MovementStats, Player};
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+/// Close enough to a point the enemy is walking to
+const ARRIVAL_DISTANCE: f32 = 4.0;
+/// Wandering enemies rest up to this long at every point they reach
+const MAX_WANDER_REST_SECONDS: f32 = 2.0;
+/// Wandering enemies pick another point if they can't reach theirs in time, e.g. because of a wall
+const WANDER_GIVE_UP_SECONDS: f32 = 5.0;
+
+/// Decides where an enemy goes
+/// Chasing and fleeing only apply while the player is within their radius
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub enum Behaviour {
+    /// Walks to random points around where it was spawned, resting in between
+    Wander { radius: f32 },
+    /// Runs towards the player
+    Chase { radius: f32 },
+    /// Runs away from the player
+    Flee { radius: f32 },
+    /// Walks from point to point and starts over after the last one
+    /// The points are relative to where the enemy was spawned
+    Patrol { path: Vec<Vec2> },
+}
+
+/// Where an enemy is at with its behaviours
+#[derive(Component, Debug)]
+pub(super) struct EnemyBrain {
+    /// See [`Enemy::home`]
+    home: Vec2,
+    wander_target: Option<Vec2>,
+    rest: Timer,
+    give_up: Timer,
+    patrol_point: usize,
+}
+
+impl EnemyBrain {
+    pub(super) fn new(home: Vec2) -> Self {
+        Self {
+            home,
+            wander_target: None,
+            rest: Timer::from_seconds(0.0, TimerMode::Once),
+            give_up: Timer::from_seconds(WANDER_GIVE_UP_SECONDS, TimerMode::Once),
+            patrol_point: 0,
+        }
+    }
+
+    /// The direction to move in, with a length of at most one
+    fn steer(&mut self, behaviours: &[Behaviour], position: Vec2, player: Option<Vec2>) -> Vec2 {
+        for behaviour in behaviours {
+            match behaviour {
+                Behaviour::Chase { radius } => {
+                    if let Some(player) =
+                        player.filter(|player| player.distance(position) < *radius)
+                    {
+                        return (player - position).normalize_or_zero();
+                    }
+                }
+                Behaviour::Flee { radius } => {
+                    if let Some(player) =
+                        player.filter(|player| player.distance(position) < *radius)
+                    {
+                        return (position - player).normalize_or_zero();
+                    }
+                }
+                Behaviour::Wander { radius } => {
+                    if !self.rest.finished() {
+                        return Vec2::ZERO;
+                    }
+                    let mut rng = rand::thread_rng();
+                    let target = match self.wander_target {
+                        Some(target) => target,
+                        None => {
+                            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+                            let target = self.home
+                                + Vec2::from_angle(angle) * rng.gen_range(0.0..=radius.max(0.0));
+                            self.wander_target = Some(target);
+                            self.give_up.reset();
+                            target
+                        }
+                    };
+                    if target.distance(position) < ARRIVAL_DISTANCE || self.give_up.finished() {
+                        self.wander_target = None;
+                        self.rest = Timer::from_seconds(
+                            rng.gen_range(0.0..MAX_WANDER_REST_SECONDS),
+                            TimerMode::Once,
+                        );
+                        return Vec2::ZERO;
+                    }
+                    return (target - position).normalize_or_zero();
+                }
+                Behaviour::Patrol { path } => {
+                    if path.is_empty() {
+                        continue;
+                    }
+                    let mut target = self.home + path[self.patrol_point % path.len()];
+                    if target.distance(position) < ARRIVAL_DISTANCE {
+                        self.patrol_point = (self.patrol_point + 1) % path.len();
+                        target = self.home + path[self.patrol_point];
+                    }
+                    return (target - position).normalize_or_zero();
+                }
+            }
+        }
+        Vec2::ZERO
+    }
+}
+
+// Enemies speed up and slow down like the player does
+pub(super) fn steer_enemies(
+    time: Res<Time>,
+    enemy_assets: Res<EnemyAssets>,
+    archetypes: Res<Assets<EnemyArchetypes>>,
+    player_query: Query<&Transform, With<Player>>,
+    mut enemy_query: Query<
+        (
+            &Enemy,
+            &Transform,
+            &MovementStats,
+            &mut EnemyBrain,
+            &mut Velocity,
+        ),
+        Without<Player>,
+    >,
+) {
+    let Some(archetypes) = archetypes.get(&enemy_assets.archetypes) else {
+        return;
+    };
+    let player = player_query
+        .iter()
+        .next()
+        .map(|transform| transform.translation.truncate());
+    for (enemy, transform, stats, mut brain, mut velocity) in &mut enemy_query {
+        let Some(archetype) = archetypes.archetypes.get(&enemy.archetype) else {
+            continue;
+        };
+        brain.rest.tick(time.delta());
+        brain.give_up.tick(time.delta());
+        let direction = brain.steer(
+            &archetype.behaviours,
+            transform.translation.truncate(),
+            player,
+        );
+        let (target, rate) = if direction == Vec2::ZERO {
+            (Vec2::ZERO, stats.deceleration)
+        } else {
+            (direction * stats.max_speed, stats.acceleration)
+        };
+        velocity.0 = velocity.0.move_towards(target, rate * time.delta_secs());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn wandering_with_a_negative_radius_stays_home() {
+        let home = Vec2::new(100.0, 50.0);
+        let mut brain = EnemyBrain::new(home);
+        // Done resting, like after the first frame
+        brain.rest.tick(Duration::ZERO);
+        brain.steer(&[Behaviour::Wander { radius: -20.0 }], Vec2::ZERO, None);
+
+        assert_eq!(brain.wander_target, Some(home));
+    }
+}
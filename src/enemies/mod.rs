@@ -0,0 +1,199 @@
+use crate::gameplay::GameplayEntity;
+use crate::health::{Damage, Died, Health, HealthSystem, Team};
+use crate::loading::EnemyAssets;
+use crate::pause::GameplayState;
+use crate::physics::{Collider, PhysicsSystem, Velocity};
+use crate::player::MovementStats;
+use crate::AppState;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::{fmt, io};
+
+mod behaviour;
+mod spawner;
+
+pub use behaviour::Behaviour;
+pub use spawner::{spawn_enemy_spawner, EnemySpawner, SpawnRules, SpawnedBy};
+
+pub struct EnemyPlugin;
+
+/// This plugin sets up enemies from their [`EnemyArchetype`] and moves them by their [`Behaviour`]s
+/// Enemies are spawned by [`EnemySpawner`]s placed in the level
+impl Plugin for EnemyPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Enemy>()
+            .register_type::<EnemySpawner>()
+            .register_type::<SpawnedBy>()
+            .init_asset::<EnemyArchetypes>()
+            .init_asset_loader::<EnemyArchetypesLoader>()
+            .add_systems(Update, setup_enemies.run_if(in_state(AppState::InGame)))
+            .add_systems(
+                Update,
+                (
+                    spawner::run_spawners,
+                    despawn_dead_enemies.after(HealthSystem),
+                )
+                    .run_if(in_state(GameplayState::Running)),
+            )
+            .add_systems(
+                FixedUpdate,
+                behaviour::steer_enemies
+                    .before(PhysicsSystem)
+                    .run_if(in_state(GameplayState::Running)),
+            );
+    }
+}
+
+/// The enemy archetypes by name
+#[derive(Asset, TypePath, Deserialize, Debug)]
+pub struct EnemyArchetypes {
+    pub archetypes: HashMap<String, EnemyArchetype>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct EnemyArchetype {
+    pub health: u32,
+    /// Dealt to the player on contact
+    pub damage: u32,
+    pub radius: f32,
+    /// Color of the enemy's sprite, as sRGB
+    pub color: [f32; 3],
+    #[serde(default)]
+    pub movement: MovementStats,
+    /// The first behaviour that applies decides where the enemy goes
+    pub behaviours: Vec<Behaviour>,
+}
+
+/// An enemy of the given archetype
+/// Everything else is added from the archetype, which also covers enemies restored from a save
+#[derive(Component, Reflect, Default, Debug)]
+#[reflect(Component, Default)]
+#[require(Velocity, Health)]
+pub struct Enemy {
+    pub archetype: String,
+    /// Where the enemy was spawned, wandering and patrols are around it
+    pub home: Vec2,
+}
+
+/// Spawns an enemy of the archetype at the position
+pub fn spawn_enemy(
+    commands: &mut Commands,
+    archetype_name: &str,
+    archetype: &EnemyArchetype,
+    position: Vec2,
+) -> Entity {
+    commands
+        .spawn((
+            Enemy {
+                archetype: archetype_name.to_string(),
+                home: position,
+            },
+            Health::new(archetype.health),
+            Transform::from_translation(position.extend(0.5)),
+            GameplayEntity,
+        ))
+        .id()
+}
+
+fn setup_enemies(
+    mut commands: Commands,
+    enemy_assets: Res<EnemyAssets>,
+    archetypes: Res<Assets<EnemyArchetypes>>,
+    enemy_query: Query<(Entity, &Enemy), Without<behaviour::EnemyBrain>>,
+) {
+    let Some(archetypes) = archetypes.get(&enemy_assets.archetypes) else {
+        return;
+    };
+    for (entity, enemy) in &enemy_query {
+        let Some(archetype) = archetypes.archetypes.get(&enemy.archetype) else {
+            warn!("Unknown enemy archetype {:?}", enemy.archetype);
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+        let [red, green, blue] = archetype.color;
+        // A save loaded in the same frame may have replaced the enemy already
+        commands.entity(entity).try_insert((
+            Sprite::from_color(
+                Color::srgb(red, green, blue),
+                Vec2::splat(archetype.radius * 2.0),
+            ),
+            Collider::Circle {
+                radius: archetype.radius,
+            },
+            archetype.movement,
+            Damage {
+                amount: archetype.damage,
+            },
+            Team::Enemy,
+            behaviour::EnemyBrain::new(enemy.home),
+        ));
+    }
+}
+
+fn despawn_dead_enemies(
+    mut commands: Commands,
+    mut died_events: EventReader<Died>,
+    enemy_query: Query<(), With<Enemy>>,
+) {
+    for died in died_events.read() {
+        if enemy_query.contains(died.entity) {
+            commands.entity(died.entity).despawn_recursive();
+        }
+    }
+}
+
+#[derive(Default)]
+struct EnemyArchetypesLoader;
+
+#[derive(Debug)]
+pub enum EnemyArchetypesLoaderError {
+    Io(io::Error),
+    Parse(ron::error::SpannedError),
+}
+
+impl fmt::Display for EnemyArchetypesLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnemyArchetypesLoaderError::Io(error) => write!(f, "could not read the file: {error}"),
+            EnemyArchetypesLoaderError::Parse(error) => write!(f, "invalid enemies: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for EnemyArchetypesLoaderError {}
+
+impl From<io::Error> for EnemyArchetypesLoaderError {
+    fn from(error: io::Error) -> Self {
+        EnemyArchetypesLoaderError::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for EnemyArchetypesLoaderError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        EnemyArchetypesLoaderError::Parse(error)
+    }
+}
+
+impl AssetLoader for EnemyArchetypesLoader {
+    type Asset = EnemyArchetypes;
+    type Settings = ();
+    type Error = EnemyArchetypesLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["enemies.ron"]
+    }
+}
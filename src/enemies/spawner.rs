@@ -0,0 +1,238 @@
+use crate::enemies::{spawn_enemy, Enemy, EnemyArchetypes};
+use crate::gameplay::GameplayEntity;
+use crate::loading::EnemyAssets;
+use bevy::ecs::entity::{EntityMapper, MapEntities};
+use bevy::ecs::reflect::ReflectMapEntities;
+use bevy::prelude::*;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// No spawner adds enemies while there are this many, however many each of them allows
+const MAX_ENEMIES: usize = 32;
+
+/// When and how many enemies a spawner brings into the level
+#[derive(Reflect, Clone, PartialEq, Debug)]
+pub struct SpawnRules {
+    /// Name of the [`EnemyArchetype`](super::EnemyArchetype)
+    pub archetype: String,
+    /// Seconds before the first and between the following enemies of a wave
+    pub interval: f32,
+    pub wave_size: u32,
+    /// Seconds between the last enemy of a wave and the first of the next one
+    pub wave_delay: f32,
+    /// The spawner stops after this many waves, `None` keeps it going for the whole level
+    pub waves: Option<u32>,
+    /// Enemies of this spawner alive at the same time, further spawns wait until some are gone
+    pub max_alive: usize,
+    /// Enemies appear at a random point within this distance of the spawner
+    pub radius: f32,
+}
+
+impl Default for SpawnRules {
+    fn default() -> Self {
+        Self {
+            archetype: String::new(),
+            interval: 1.0,
+            wave_size: 1,
+            wave_delay: 10.0,
+            waves: None,
+            max_alive: 1,
+            radius: 0.0,
+        }
+    }
+}
+
+impl SpawnRules {
+    /// Reads the rules from a level object's properties, named like the fields
+    /// Missing or invalid properties keep their default
+    pub fn from_properties(properties: &HashMap<String, String>) -> Self {
+        fn parse<T: std::str::FromStr>(
+            properties: &HashMap<String, String>,
+            name: &str,
+            default: T,
+        ) -> T {
+            match properties.get(name).map(|value| value.parse()) {
+                Some(Ok(value)) => value,
+                Some(Err(_)) => {
+                    warn!("Invalid spawner property {name}: {:?}", properties[name]);
+                    default
+                }
+                None => default,
+            }
+        }
+        // Durations and distances, timers even panic when given negative ones
+        fn non_negative(properties: &HashMap<String, String>, name: &str, default: f32) -> f32 {
+            let value = parse(properties, name, default);
+            if value.is_finite() && value >= 0.0 {
+                return value;
+            }
+            warn!("Spawner property {name} can't be negative: {value}");
+            default
+        }
+        let default = Self::default();
+        Self {
+            archetype: properties.get("archetype").cloned().unwrap_or_default(),
+            interval: non_negative(properties, "interval", default.interval),
+            wave_size: parse(properties, "wave_size", default.wave_size),
+            wave_delay: non_negative(properties, "wave_delay", default.wave_delay),
+            waves: properties
+                .contains_key("waves")
+                .then(|| parse(properties, "waves", 1)),
+            max_alive: parse(properties, "max_alive", default.max_alive),
+            radius: non_negative(properties, "radius", default.radius),
+        }
+    }
+}
+
+/// Spawns enemies following its [`SpawnRules`] while the game runs
+/// Spawners keep track of their waves, so they are saved instead of being spawned from the map again
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component, Default)]
+#[require(Transform)]
+pub struct EnemySpawner {
+    pub rules: SpawnRules,
+    waves_done: u32,
+    spawned_in_wave: u32,
+    /// Until the next enemy is due
+    timer: Timer,
+    /// Set when the archetype doesn't exist, not saved so a fixed archetype list is picked up on load
+    #[reflect(ignore)]
+    stopped: bool,
+}
+
+impl EnemySpawner {
+    pub fn new(rules: SpawnRules) -> Self {
+        Self {
+            timer: Timer::from_seconds(rules.interval, TimerMode::Once),
+            rules,
+            waves_done: 0,
+            spawned_in_wave: 0,
+            stopped: false,
+        }
+    }
+
+    /// Whether all waves have been spawned
+    pub fn is_done(&self) -> bool {
+        self.rules
+            .waves
+            .is_some_and(|waves| self.waves_done >= waves)
+    }
+}
+
+impl Default for EnemySpawner {
+    fn default() -> Self {
+        Self::new(SpawnRules::default())
+    }
+}
+
+/// Spawns a spawner at the position
+/// Spawners are [`GameplayEntity`]s of their own instead of level entities, like pickups
+pub fn spawn_enemy_spawner(
+    commands: &mut Commands,
+    name: &str,
+    rules: SpawnRules,
+    position: Vec2,
+) -> Entity {
+    commands
+        .spawn((
+            Name::new(name.to_string()),
+            EnemySpawner::new(rules),
+            Transform::from_translation(position.extend(0.0)),
+            GameplayEntity,
+        ))
+        .id()
+}
+
+/// The spawner an enemy came from, for the spawner's cap
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Debug)]
+#[reflect(Component, MapEntities)]
+pub struct SpawnedBy(pub Entity);
+
+impl MapEntities for SpawnedBy {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
+
+pub(super) fn run_spawners(
+    mut commands: Commands,
+    time: Res<Time>,
+    enemy_assets: Res<EnemyAssets>,
+    archetypes: Res<Assets<EnemyArchetypes>>,
+    mut spawner_query: Query<(Entity, &mut EnemySpawner, &Transform)>,
+    enemy_query: Query<Option<&SpawnedBy>, With<Enemy>>,
+) {
+    let Some(archetypes) = archetypes.get(&enemy_assets.archetypes) else {
+        return;
+    };
+    let mut enemies = enemy_query.iter().count();
+    for (entity, mut spawner, transform) in &mut spawner_query {
+        if spawner.is_done() || spawner.stopped {
+            continue;
+        }
+        // A finished timer waits for room below the caps
+        spawner.timer.tick(time.delta());
+        if !spawner.timer.finished() || enemies >= MAX_ENEMIES {
+            continue;
+        }
+        let alive = enemy_query
+            .iter()
+            .filter(|spawned_by| spawned_by.is_some_and(|spawned_by| spawned_by.0 == entity))
+            .count();
+        if alive >= spawner.rules.max_alive {
+            continue;
+        }
+        let Some(archetype) = archetypes.archetypes.get(&spawner.rules.archetype) else {
+            warn!(
+                "Spawner has an unknown enemy archetype {:?}",
+                spawner.rules.archetype
+            );
+            // Stop the spawner instead of warning every frame
+            spawner.stopped = true;
+            continue;
+        };
+
+        let mut rng = rand::thread_rng();
+        let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+        let distance = rng.gen_range(0.0..=spawner.rules.radius);
+        let position = transform.translation.truncate() + Vec2::from_angle(angle) * distance;
+        let enemy = spawn_enemy(&mut commands, &spawner.rules.archetype, archetype, position);
+        commands.entity(enemy).insert(SpawnedBy(entity));
+        enemies += 1;
+
+        spawner.spawned_in_wave += 1;
+        let delay = if spawner.spawned_in_wave >= spawner.rules.wave_size {
+            spawner.spawned_in_wave = 0;
+            spawner.waves_done += 1;
+            spawner.rules.wave_delay
+        } else {
+            spawner.rules.interval
+        };
+        spawner.timer = Timer::from_seconds(delay, TimerMode::Once);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negative_spawn_properties_keep_their_defaults() {
+        let properties = HashMap::from([
+            ("archetype".to_string(), "slime".to_string()),
+            ("interval".to_string(), "-1".to_string()),
+            ("wave_delay".to_string(), "-2.5".to_string()),
+            ("radius".to_string(), "-50".to_string()),
+            ("wave_size".to_string(), "4".to_string()),
+        ]);
+        let rules = SpawnRules::from_properties(&properties);
+        let default = SpawnRules::default();
+
+        assert_eq!(rules.interval, default.interval);
+        assert_eq!(rules.wave_delay, default.wave_delay);
+        assert_eq!(rules.radius, default.radius);
+        assert_eq!(rules.wave_size, 4);
+        // The timers of a spawner built from them don't panic
+        EnemySpawner::new(rules);
+    }
+}
@@ -3,12 +3,17 @@ use bevy::prelude::*;
 
 pub struct GameplayPlugin;
 
-#[derive(Component)]
+/// Marks entities that belong to the running game session
+/// They are despawned when leaving the game and are what save games are made of
+/// Entering the game again, e.g. to retry a level, starts over with a new session
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
 pub struct GameplayEntity;
 
 impl Plugin for GameplayPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnExit(AppState::InGame), cleanup_gameplay_entities);
+        app.register_type::<GameplayEntity>()
+            .add_systems(OnExit(AppState::InGame), cleanup_gameplay_entities);
     }
 }
 
@@ -16,7 +21,8 @@ fn cleanup_gameplay_entities(
     mut commands: Commands,
     gameplay_query: Query<Entity, With<GameplayEntity>>,
 ) {
+    // Children are often gameplay entities too and may already be gone with their parent
     for entity in gameplay_query.iter() {
-        commands.entity(entity).despawn_recursive();
+        commands.entity(entity).try_despawn_recursive();
     }
 }
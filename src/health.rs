@@ -0,0 +1,182 @@
+use crate::pause::GameplayState;
+use crate::physics::Collider;
+use bevy::prelude::*;
+
+/// How long an entity can't be hurt again after taking damage
+const INVULNERABILITY_SECONDS: f32 = 1.0;
+/// Invulnerable sprites are hidden and shown again at this interval
+const FLASH_INTERVAL_SECONDS: f32 = 0.1;
+
+pub struct HealthPlugin;
+
+/// This plugin hurts entities with [`Health`] that touch entities dealing [`Damage`] and aren't on the same [`Team`]
+/// After every hit the entity is [`Invulnerable`] for a moment, which is shown by flashing its sprite
+/// Every hit sends a [`Damaged`] event, running out of health also sends a [`Died`] event, what happens then is up to the plugin owning the entity
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Health>()
+            .add_event::<Damaged>()
+            .add_event::<Died>()
+            .add_systems(
+                Update,
+                (apply_contact_damage, flash_invulnerable)
+                    .chain()
+                    .in_set(HealthSystem)
+                    .run_if(in_state(GameplayState::Running)),
+            )
+            .add_systems(OnEnter(GameplayState::Paused), show_invulnerable);
+    }
+}
+
+/// Applies damage and sends [`Damaged`] and [`Died`] events
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct HealthSystem;
+
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Debug)]
+#[reflect(Component, Default)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current == 0
+    }
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+/// Hurts entities with [`Health`] that touch this entity's [`Collider`]
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+#[require(Collider)]
+pub struct Damage {
+    pub amount: u32,
+}
+
+impl Default for Damage {
+    fn default() -> Self {
+        Self { amount: 1 }
+    }
+}
+
+/// The side an entity is on, [`Damage`] only hurts entities of other teams
+/// Entities without a team, like hazards, hurt and are hurt by everyone
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Team {
+    Player,
+    Enemy,
+}
+
+/// Can't take damage until the timer finishes
+#[derive(Component, Debug)]
+pub struct Invulnerable {
+    pub timer: Timer,
+}
+
+impl Default for Invulnerable {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(INVULNERABILITY_SECONDS, TimerMode::Once),
+        }
+    }
+}
+
+/// Sent when an entity lost health
+#[derive(Event, Clone, Copy, PartialEq, Debug)]
+pub struct Damaged {
+    pub entity: Entity,
+    pub amount: u32,
+}
+
+/// Sent once when an entity's [`Health`] drops to zero
+#[derive(Event, Clone, Copy, PartialEq, Debug)]
+pub struct Died {
+    pub entity: Entity,
+}
+
+fn apply_contact_damage(
+    mut commands: Commands,
+    mut damaged_events: EventWriter<Damaged>,
+    mut died_events: EventWriter<Died>,
+    mut targets: Query<
+        (Entity, &mut Health, &Transform, &Collider, Option<&Team>),
+        Without<Invulnerable>,
+    >,
+    sources: Query<(Entity, &Damage, &Transform, &Collider, Option<&Team>)>,
+) {
+    for (entity, mut health, transform, collider, team) in &mut targets {
+        if health.is_dead() {
+            continue;
+        }
+        // Only the strongest touching source counts, every hit is followed by invulnerability anyway
+        let damage = sources
+            .iter()
+            .filter(|(source, .., source_team)| {
+                *source != entity
+                    && (team.is_none() || source_team.is_none() || team != *source_team)
+            })
+            .filter(|(_, _, source_transform, source_collider, _)| {
+                collider
+                    .penetration(
+                        transform.translation.truncate(),
+                        **source_collider,
+                        source_transform.translation.truncate(),
+                    )
+                    .is_some()
+            })
+            .map(|(_, damage, ..)| damage.amount)
+            .max();
+        let Some(damage) = damage.filter(|damage| *damage > 0) else {
+            continue;
+        };
+        health.current = health.current.saturating_sub(damage);
+        damaged_events.send(Damaged {
+            entity,
+            amount: damage,
+        });
+        if health.is_dead() {
+            died_events.send(Died { entity });
+        } else {
+            commands.entity(entity).insert(Invulnerable::default());
+        }
+    }
+}
+
+fn flash_invulnerable(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Invulnerable, Option<&mut Visibility>)>,
+) {
+    for (entity, mut invulnerable, visibility) in &mut query {
+        invulnerable.timer.tick(time.delta());
+        let finished = invulnerable.timer.finished();
+        if finished {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+        if let Some(mut visibility) = visibility {
+            let flash = (invulnerable.timer.elapsed_secs() / FLASH_INTERVAL_SECONDS) as u32;
+            let shown = if finished || flash % 2 == 1 {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+            visibility.set_if_neq(shown);
+        }
+    }
+}
+
+// Pausing in the middle of a flash mustn't leave the entity hidden while the menu is open
+fn show_invulnerable(mut query: Query<&mut Visibility, With<Invulnerable>>) {
+    for mut visibility in &mut query {
+        visibility.set_if_neq(Visibility::Inherited);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::enemies::Enemy;
+use crate::level::{CurrentLevel, LevelMap};
+use crate::loading::LevelAssets;
+use crate::player::Player;
+use crate::score::Pickup;
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+/// The whole level is squeezed into this, whatever its aspect ratio
+const MINIMAP_SIZE: Vec2 = Vec2::new(160.0, 96.0);
+
+#[derive(Component)]
+pub(super) struct Minimap;
+
+/// Shows where an entity is in the level
+#[derive(Component)]
+pub struct MinimapDot {
+    target: Entity,
+}
+
+pub(super) fn spawn_minimap(parent: &mut ChildBuilder) {
+    parent.spawn((
+        Node {
+            width: Val::Px(MINIMAP_SIZE.x),
+            height: Val::Px(MINIMAP_SIZE.y),
+            border: UiRect::all(Val::Px(2.0)),
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.5)),
+        BorderColor(Color::srgba(0.9, 0.9, 0.9, 0.5)),
+        Minimap,
+    ));
+}
+
+// Dots are added for new entities and removed with theirs, e.g. for defeated enemies or collected pickups
+pub(super) fn update_minimap(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    level_assets: Res<LevelAssets>,
+    maps: Res<Assets<LevelMap>>,
+    minimap_query: Query<Entity, With<Minimap>>,
+    mut dot_query: Query<(Entity, &MinimapDot, &mut Node)>,
+    tracked_query: Query<
+        (Entity, &Transform, Has<Player>, Has<Enemy>),
+        Or<(With<Player>, With<Enemy>, With<Pickup>)>,
+    >,
+) {
+    let Ok(minimap) = minimap_query.get_single() else {
+        return;
+    };
+    let Some(map) = level_assets
+        .maps
+        .get(current_level.id.as_str())
+        .and_then(|handle| maps.get(handle))
+    else {
+        return;
+    };
+    let bounds = Rect::from_center_size(Vec2::ZERO, map.size);
+
+    let mut shown = HashSet::new();
+    for (dot, MinimapDot { target }, mut node) in &mut dot_query {
+        match tracked_query.get(*target) {
+            Ok((_, transform, ..)) => {
+                let (left, top) = dot_position(bounds, transform.translation.truncate());
+                if node.left != left || node.top != top {
+                    node.left = left;
+                    node.top = top;
+                }
+                shown.insert(*target);
+            }
+            Err(_) => commands.entity(dot).despawn_recursive(),
+        }
+    }
+
+    for (entity, transform, is_player, is_enemy) in &tracked_query {
+        if shown.contains(&entity) {
+            continue;
+        }
+        let (color, size) = if is_player {
+            (Color::srgb(0.3, 0.9, 0.3), 6.0)
+        } else if is_enemy {
+            (Color::srgb(0.9, 0.25, 0.25), 4.0)
+        } else {
+            (Color::srgb(0.95, 0.8, 0.2), 3.0)
+        };
+        let (left, top) = dot_position(bounds, transform.translation.truncate());
+        let node = Node {
+            position_type: PositionType::Absolute,
+            left,
+            top,
+            width: Val::Px(size),
+            height: Val::Px(size),
+            // Centers the dot on its position
+            margin: UiRect::all(Val::Px(-size / 2.0)),
+            ..default()
+        };
+        commands
+            .spawn((
+                node,
+                BackgroundColor(color),
+                // The player is drawn above everything else
+                ZIndex(if is_player { 1 } else { 0 }),
+                MinimapDot { target: entity },
+            ))
+            .set_parent(minimap);
+    }
+}
+
+/// Left and top of a dot, relative to the minimap
+fn dot_position(bounds: Rect, position: Vec2) -> (Val, Val) {
+    let relative = ((position - bounds.min) / bounds.size()).clamp(Vec2::ZERO, Vec2::ONE);
+    // UI coordinates grow downwards
+    (
+        Val::Percent(relative.x * 100.0),
+        Val::Percent((1.0 - relative.y) * 100.0),
+    )
+}
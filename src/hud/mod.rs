@@ -0,0 +1,164 @@
+use crate::gameplay::GameplayEntity;
+use crate::overlay_state::OverlayBackgroundState;
+use crate::AppState;
+use bevy::prelude::*;
+
+mod minimap;
+mod widgets;
+
+#[cfg(test)]
+pub use minimap::MinimapDot;
+
+/// Space between the anchors and the edges of the window
+const HUD_MARGIN: f32 = 12.0;
+
+pub struct HudPlugin;
+
+/// This plugin shows health, score, level time and a minimap over the running game
+/// Other plugins add their own widgets with [`HudAppExt::add_hud_widget`]
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HudWidgets>()
+            .add_hud_widget(HudAnchor::TopLeft, widgets::spawn_health_bar)
+            .add_hud_widget(HudAnchor::TopLeft, widgets::spawn_score)
+            .add_hud_widget(HudAnchor::TopCenter, widgets::spawn_level_timer)
+            .add_hud_widget(HudAnchor::BottomCenter, minimap::spawn_minimap)
+            .add_systems(OnEnter(AppState::InGame), spawn_hud)
+            .add_systems(
+                Update,
+                (
+                    show_hud,
+                    widgets::update_health_bar,
+                    widgets::update_score,
+                    widgets::update_level_timer,
+                    minimap::update_minimap,
+                )
+                    .run_if(in_state(AppState::InGame)),
+            );
+    }
+}
+
+/// Where widgets are placed on the screen, widgets sharing an anchor are stacked in the order they were added
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HudAnchor {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+impl HudAnchor {
+    const ALL: [HudAnchor; 6] = [
+        HudAnchor::TopLeft,
+        HudAnchor::TopCenter,
+        HudAnchor::TopRight,
+        HudAnchor::BottomLeft,
+        HudAnchor::BottomCenter,
+        HudAnchor::BottomRight,
+    ];
+
+    fn node(self) -> Node {
+        let margin = Val::Px(HUD_MARGIN);
+        let mut node = Node {
+            position_type: PositionType::Absolute,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.0),
+            ..default()
+        };
+        match self {
+            HudAnchor::TopLeft | HudAnchor::TopCenter | HudAnchor::TopRight => node.top = margin,
+            HudAnchor::BottomLeft | HudAnchor::BottomCenter | HudAnchor::BottomRight => {
+                node.bottom = margin;
+                // The first widget is the one closest to the edge
+                node.flex_direction = FlexDirection::ColumnReverse;
+            }
+        }
+        match self {
+            HudAnchor::TopLeft | HudAnchor::BottomLeft => {
+                node.left = margin;
+                node.align_items = AlignItems::FlexStart;
+            }
+            HudAnchor::TopCenter | HudAnchor::BottomCenter => {
+                // Centered by spanning the whole width
+                node.left = Val::Px(0.0);
+                node.right = Val::Px(0.0);
+                node.align_items = AlignItems::Center;
+            }
+            HudAnchor::TopRight | HudAnchor::BottomRight => {
+                node.right = margin;
+                node.align_items = AlignItems::FlexEnd;
+            }
+        }
+        node
+    }
+}
+
+/// Spawns a widget's entities, it is called every time the game is entered
+pub type HudWidget = fn(&mut ChildBuilder);
+
+/// The widgets the HUD is built from
+#[derive(Resource, Default)]
+pub struct HudWidgets(Vec<(HudAnchor, HudWidget)>);
+
+pub trait HudAppExt {
+    /// Adds a widget to the HUD, plugins can do this before or after the [`HudPlugin`] was added
+    /// Widgets update themselves with their own systems, e.g. by querying for a marker component
+    fn add_hud_widget(&mut self, anchor: HudAnchor, widget: HudWidget) -> &mut Self;
+}
+
+impl HudAppExt for App {
+    fn add_hud_widget(&mut self, anchor: HudAnchor, widget: HudWidget) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_insert_with(HudWidgets::default)
+            .0
+            .push((anchor, widget));
+        self
+    }
+}
+
+/// Covers the window and holds the anchors
+/// It is a gameplay entity, but not part of save games
+#[derive(Component)]
+pub struct HudRoot;
+
+fn spawn_hud(mut commands: Commands, widgets: Res<HudWidgets>) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            Visibility::default(),
+            Name::new("HUD"),
+            HudRoot,
+            GameplayEntity,
+        ))
+        .with_children(|parent| {
+            for anchor in HudAnchor::ALL {
+                parent
+                    .spawn((anchor.node(), anchor))
+                    .with_children(|parent| {
+                        for (_, widget) in widgets.0.iter().filter(|(at, _)| *at == anchor) {
+                            widget(parent);
+                        }
+                    });
+            }
+        });
+}
+
+fn show_hud(
+    background_state: Res<State<OverlayBackgroundState>>,
+    mut hud_query: Query<&mut Visibility, With<HudRoot>>,
+) {
+    let visibility = match background_state.get() {
+        OverlayBackgroundState::None => Visibility::Inherited,
+        OverlayBackgroundState::Darkened => Visibility::Hidden,
+    };
+    for mut hud_visibility in &mut hud_query {
+        hud_visibility.set_if_neq(visibility);
+    }
+}
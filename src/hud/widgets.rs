@@ -0,0 +1,135 @@
+use crate::health::Health;
+use crate::level::{format_time, CurrentLevel};
+use crate::menus::widgets::TEXT_COLOR;
+use crate::player::Player;
+use crate::score::Score;
+use bevy::prelude::*;
+
+const COMBO_COLOR: Color = Color::linear_rgb(0.9, 0.8, 0.2);
+
+/// Part of the health bar that shrinks with the player's health
+#[derive(Component)]
+pub(super) struct HealthBarFill;
+
+#[derive(Component)]
+pub(super) struct ScoreText;
+
+/// Shows the multiplier while a combo is going
+#[derive(Component)]
+pub(super) struct ComboText;
+
+#[derive(Component)]
+pub(super) struct LevelTimerText;
+
+pub(super) fn spawn_health_bar(parent: &mut ChildBuilder) {
+    parent
+        .spawn((
+            Node {
+                width: Val::Px(160.0),
+                height: Val::Px(16.0),
+                padding: UiRect::all(Val::Px(2.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        ))
+        .with_child((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.8, 0.15, 0.15)),
+            HealthBarFill,
+        ));
+}
+
+pub(super) fn spawn_score(parent: &mut ChildBuilder) {
+    parent
+        .spawn(Node {
+            column_gap: Val::Px(10.0),
+            align_items: AlignItems::Baseline,
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Score 0"),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(TEXT_COLOR),
+                ScoreText,
+            ));
+            parent.spawn((
+                Text::default(),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(COMBO_COLOR),
+                ComboText,
+            ));
+        });
+}
+
+pub(super) fn spawn_level_timer(parent: &mut ChildBuilder) {
+    parent.spawn((
+        Text::new(format_time(0.0)),
+        TextFont {
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(TEXT_COLOR),
+        LevelTimerText,
+    ));
+}
+
+pub(super) fn update_health_bar(
+    player_query: Query<&Health, With<Player>>,
+    mut fill_query: Query<&mut Node, With<HealthBarFill>>,
+) {
+    let Ok(health) = player_query.get_single() else {
+        return;
+    };
+    let width = Val::Percent(100.0 * health.current as f32 / health.max.max(1) as f32);
+    for mut node in &mut fill_query {
+        if node.width != width {
+            node.width = width;
+        }
+    }
+}
+
+pub(super) fn update_score(
+    score: Res<Score>,
+    mut score_query: Query<&mut Text, (With<ScoreText>, Without<ComboText>)>,
+    mut combo_query: Query<&mut Text, With<ComboText>>,
+) {
+    let points = format!("Score {}", score.points);
+    for mut text in &mut score_query {
+        if text.0 != points {
+            text.0 = points.clone();
+        }
+    }
+    let combo = if score.combo > 1 {
+        format!("x{}", score.multiplier())
+    } else {
+        String::new()
+    };
+    for mut text in &mut combo_query {
+        if text.0 != combo {
+            text.0 = combo.clone();
+        }
+    }
+}
+
+pub(super) fn update_level_timer(
+    current_level: Res<CurrentLevel>,
+    mut text_query: Query<&mut Text, With<LevelTimerText>>,
+) {
+    let time = format_time(current_level.time);
+    for mut text in &mut text_query {
+        if text.0 != time {
+            text.0 = time.clone();
+        }
+    }
+}
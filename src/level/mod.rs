@@ -0,0 +1,310 @@
+use crate::camera::CameraController;
+use crate::enemies::{spawn_enemy_spawner, SpawnRules};
+use crate::gameplay::GameplayEntity;
+use crate::health::Damage;
+use crate::loading::LevelAssets;
+use crate::pause::GameplayState;
+use crate::physics::{Collider, Obstacle};
+use crate::score::{spawn_pickup, Pickup};
+use crate::AppState;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+mod progress;
+mod registry;
+mod tiled;
+
+pub use progress::{format_time, LevelCompleted, LevelProgress, LevelResult};
+pub use registry::LevelRegistry;
+
+/// Tile layers are stacked in the order of the map, all of them below the player
+const LAYER_Z_STEP: f32 = 0.01;
+
+pub struct LevelPlugin;
+
+/// This plugin spawns the [`CurrentLevel`] from its Tiled [`LevelMap`] when the game starts
+/// and records the time in the [`LevelProgress`] once its [`LevelGoal`] is reached
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<LevelMap>()
+            .init_asset_loader::<tiled::TiledMapLoader>()
+            .register_type::<CurrentLevel>()
+            .init_resource::<LevelRegistry>()
+            .init_resource::<CurrentLevel>()
+            .insert_resource(LevelProgress::load())
+            .add_event::<LevelCompleted>()
+            .add_systems(
+                OnEnter(AppState::InGame),
+                spawn_level.in_set(LevelSpawnSystem),
+            )
+            .add_systems(OnExit(AppState::InGame), clear_camera_bounds)
+            .add_systems(
+                Update,
+                (
+                    respawn_level.in_set(LevelSpawnSystem),
+                    (progress::track_level_time, progress::check_level_goal)
+                        .chain()
+                        .run_if(in_state(GameplayState::Running)),
+                    progress::record_completed_levels,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::InGame)),
+            );
+    }
+}
+
+/// Spawns the level when entering the game, and respawns it when it changed
+/// Systems relying on the level's markers, like placing the player, should run after it
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LevelSpawnSystem;
+
+/// A level's tiles and objects, in world units and centered on the origin
+#[derive(Asset, TypePath, Debug)]
+pub struct LevelMap {
+    /// Width and height in world units
+    pub size: Vec2,
+    pub tilesets: Vec<LevelTileset>,
+    pub layers: Vec<TileLayer>,
+    pub objects: Vec<LevelObject>,
+}
+
+#[derive(Debug)]
+pub struct LevelTileset {
+    /// Id of the tileset's first tile in the map
+    pub first_id: u32,
+    pub image: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+}
+
+#[derive(Debug)]
+pub struct TileLayer {
+    pub name: String,
+    pub tiles: Vec<LevelTile>,
+}
+
+#[derive(Debug)]
+pub struct LevelTile {
+    /// Center of the tile
+    pub position: Vec2,
+    /// Index into the map's tilesets
+    pub tileset: usize,
+    /// Index into the tileset's texture atlas
+    pub index: usize,
+    pub flip_x: bool,
+    pub flip_y: bool,
+}
+
+#[derive(Debug)]
+pub struct LevelObject {
+    pub name: String,
+    /// Decides which marker the object becomes
+    pub class: String,
+    /// Points have a rect without size
+    pub rect: Rect,
+    /// Custom properties of the object, numbers and booleans as text
+    pub properties: HashMap<String, String>,
+}
+
+/// Everything spawned from the level's map
+/// Level entities aren't part of save games, loading a save keeps them as they are
+#[derive(Component)]
+pub struct LevelEntity;
+
+/// Holds the level's layers and markers
+#[derive(Component)]
+pub struct LevelRoot {
+    /// Id of the level in the [`LevelRegistry`]
+    pub level: String,
+}
+
+/// The level that is played, it is part of save games
+#[derive(Resource, Reflect, Clone, PartialEq, Debug)]
+#[reflect(Resource)]
+pub struct CurrentLevel {
+    /// Id of the level in the [`LevelRegistry`]
+    pub id: String,
+    /// Seconds played in the level so far, paused time isn't counted
+    pub time: f32,
+}
+
+impl CurrentLevel {
+    /// Plays the level from the start
+    pub fn start(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            time: 0.0,
+        }
+    }
+}
+
+impl FromWorld for CurrentLevel {
+    fn from_world(world: &mut World) -> Self {
+        let registry = world.resource::<LevelRegistry>();
+        Self::start(registry.levels.first().map_or("", |level| level.id))
+    }
+}
+
+/// Where a new game places the player
+#[derive(Component)]
+pub struct PlayerStart;
+
+/// An area of the level that other plugins react to, e.g. the level's exit
+/// Which one it is can be told by its [`Name`], the object's name in the map
+#[derive(Component)]
+pub struct LevelTrigger;
+
+fn spawn_level(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    level_assets: Res<LevelAssets>,
+    maps: Res<Assets<LevelMap>>,
+    mut cameras: Query<&mut CameraController>,
+) {
+    // The maps were loaded with the other assets before the game could be started
+    let map = level_assets
+        .maps
+        .get(current_level.id.as_str())
+        .and_then(|handle| maps.get(handle));
+    match map {
+        Some(map) => {
+            spawn_level_entities(&mut commands, &current_level.id, map);
+            set_camera_bounds(&mut cameras, map);
+            for object in &map.objects {
+                match object.class.as_str() {
+                    "Pickup" => {
+                        spawn_pickup(
+                            &mut commands,
+                            &object.name,
+                            Pickup::from_properties(&object.properties),
+                            object.rect,
+                        );
+                    }
+                    "Spawner" => {
+                        spawn_enemy_spawner(
+                            &mut commands,
+                            &object.name,
+                            SpawnRules::from_properties(&object.properties),
+                            object.rect.center(),
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+        None => error!("There is no map for level {:?}", current_level.id),
+    }
+}
+
+fn respawn_level(
+    mut commands: Commands,
+    mut map_events: EventReader<AssetEvent<LevelMap>>,
+    current_level: Res<CurrentLevel>,
+    level_assets: Res<LevelAssets>,
+    maps: Res<Assets<LevelMap>>,
+    level_roots: Query<(Entity, &LevelRoot)>,
+    mut cameras: Query<&mut CameraController>,
+) {
+    let Some(handle) = level_assets.maps.get(current_level.id.as_str()) else {
+        return;
+    };
+    let map_changed = map_events.read().any(|event| event.is_modified(handle));
+    let level_changed = level_roots
+        .iter()
+        .any(|(_, root)| root.level != current_level.id);
+    if !map_changed && !level_changed {
+        return;
+    }
+    let Some(map) = maps.get(handle) else {
+        return;
+    };
+    info!("Respawning level {}", current_level.id);
+    for (entity, _) in &level_roots {
+        commands.entity(entity).despawn_recursive();
+    }
+    spawn_level_entities(&mut commands, &current_level.id, map);
+    set_camera_bounds(&mut cameras, map);
+}
+
+// The map is centered on the origin
+fn set_camera_bounds(cameras: &mut Query<&mut CameraController>, map: &LevelMap) {
+    for mut controller in cameras.iter_mut() {
+        controller.bounds = Some(Rect::from_center_size(Vec2::ZERO, map.size));
+    }
+}
+
+fn clear_camera_bounds(mut cameras: Query<&mut CameraController>) {
+    for mut controller in &mut cameras {
+        controller.bounds = None;
+    }
+}
+
+// The root stays at the origin, so the markers' translations are world positions
+fn spawn_level_entities(commands: &mut Commands, level: &str, map: &LevelMap) {
+    commands
+        .spawn((
+            Transform::default(),
+            Visibility::default(),
+            LevelRoot {
+                level: level.to_string(),
+            },
+            LevelEntity,
+            GameplayEntity,
+        ))
+        .with_children(|parent| {
+            for (layer_index, layer) in map.layers.iter().enumerate() {
+                parent
+                    .spawn((
+                        Name::new(layer.name.clone()),
+                        Transform::from_xyz(0.0, 0.0, layer_index as f32 * LAYER_Z_STEP),
+                        Visibility::default(),
+                        LevelEntity,
+                        GameplayEntity,
+                    ))
+                    .with_children(|parent| {
+                        // Tiles sharing a tileset image are drawn in one batch
+                        for tile in &layer.tiles {
+                            let tileset = &map.tilesets[tile.tileset];
+                            parent.spawn((
+                                Sprite {
+                                    image: tileset.image.clone(),
+                                    texture_atlas: Some(TextureAtlas {
+                                        layout: tileset.layout.clone(),
+                                        index: tile.index,
+                                    }),
+                                    flip_x: tile.flip_x,
+                                    flip_y: tile.flip_y,
+                                    ..default()
+                                },
+                                Transform::from_translation(tile.position.extend(0.0)),
+                                LevelEntity,
+                                GameplayEntity,
+                            ));
+                        }
+                    });
+            }
+
+            for object in &map.objects {
+                let collider = Collider::Aabb {
+                    half_size: object.rect.half_size(),
+                };
+                let mut marker = match object.class.as_str() {
+                    "PlayerStart" => parent.spawn(PlayerStart),
+                    "Collision" => parent.spawn((Obstacle, collider)),
+                    "Hazard" => parent.spawn((Damage::default(), collider)),
+                    "Trigger" => parent.spawn((LevelTrigger, collider)),
+                    // Spawned on their own when entering the game, see `spawn_level`
+                    "Pickup" | "Spawner" => continue,
+                    class => {
+                        warn!("Unknown level object class {class:?}");
+                        continue;
+                    }
+                };
+                marker.insert((
+                    Name::new(object.name.clone()),
+                    Transform::from_translation(object.rect.center().extend(0.0)),
+                    LevelEntity,
+                    GameplayEntity,
+                ));
+            }
+        });
+}
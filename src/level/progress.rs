@@ -0,0 +1,141 @@
+use crate::level::registry::{LevelGoal, LevelRegistry};
+use crate::level::{CurrentLevel, LevelTrigger};
+use crate::physics::Collider;
+use crate::player::Player;
+use crate::storage;
+use crate::AppState;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const PROGRESS_KEY: &str = "level_progress.ron";
+
+/// Sent once the goal of the [`CurrentLevel`] is reached
+#[derive(Event, Clone, PartialEq, Debug)]
+pub struct LevelCompleted {
+    pub level: String,
+    /// Seconds it took to complete the level
+    pub time: f32,
+}
+
+/// The last completed level, shown until the next level is started
+#[derive(Resource, Clone, Debug)]
+pub struct LevelResult {
+    pub level: String,
+    pub time: f32,
+    pub best_time: f32,
+    pub new_best: bool,
+}
+
+/// Which levels the player has completed, stored on disk across sessions
+/// A level is unlocked once the level before it was completed
+#[derive(Resource, Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct LevelProgress {
+    /// Fastest completion in seconds, by level id
+    pub best_times: BTreeMap<String, f32>,
+}
+
+impl LevelProgress {
+    /// Loads the stored progress, starting over if there is none or it can't be read
+    pub fn load() -> Self {
+        storage::load_ron(PROGRESS_KEY, "level progress")
+    }
+
+    pub fn save(&self) {
+        storage::save_ron(PROGRESS_KEY, "level progress", self);
+    }
+
+    pub fn best_time(&self, level: &str) -> Option<f32> {
+        self.best_times.get(level).copied()
+    }
+
+    pub fn is_unlocked(&self, registry: &LevelRegistry, index: usize) -> bool {
+        index == 0
+            || registry
+                .levels
+                .get(index - 1)
+                .is_some_and(|previous| self.best_times.contains_key(previous.id))
+    }
+
+    /// Returns whether the time is a new best for the level
+    pub fn record(&mut self, level: &str, time: f32) -> bool {
+        let new_best = self.best_time(level).is_none_or(|best| time < best);
+        if new_best {
+            self.best_times.insert(level.to_string(), time);
+        }
+        new_best
+    }
+}
+
+/// Formats seconds like `1:05.3`
+pub fn format_time(seconds: f32) -> String {
+    let tenths = (seconds.max(0.0) * 10.0).round() as u32;
+    format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+}
+
+pub(super) fn track_level_time(time: Res<Time>, mut current_level: ResMut<CurrentLevel>) {
+    current_level.time += time.delta_secs();
+}
+
+pub(super) fn check_level_goal(
+    current_level: Res<CurrentLevel>,
+    registry: Res<LevelRegistry>,
+    mut completed_events: EventWriter<LevelCompleted>,
+    player_query: Query<(&Transform, &Collider), With<Player>>,
+    trigger_query: Query<(&Name, &Transform, &Collider), With<LevelTrigger>>,
+) {
+    let Some(level) = registry.get(&current_level.id) else {
+        return;
+    };
+    let reached = match level.goal {
+        LevelGoal::Reach { trigger } => player_query.iter().any(|(player, player_collider)| {
+            trigger_query
+                .iter()
+                .filter(|(name, ..)| name.as_str() == trigger)
+                .any(|(_, transform, collider)| {
+                    player_collider
+                        .penetration(
+                            player.translation.truncate(),
+                            *collider,
+                            transform.translation.truncate(),
+                        )
+                        .is_some()
+                })
+        }),
+        LevelGoal::Survive { seconds } => current_level.time >= seconds,
+    };
+    if reached {
+        completed_events.send(LevelCompleted {
+            level: level.id.to_string(),
+            time: current_level.time,
+        });
+    }
+}
+
+pub(super) fn record_completed_levels(
+    mut commands: Commands,
+    mut completed_events: EventReader<LevelCompleted>,
+    mut progress: ResMut<LevelProgress>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Some(completed) = completed_events.read().last() else {
+        return;
+    };
+    info!(
+        "Completed level {} in {}",
+        completed.level,
+        format_time(completed.time)
+    );
+    let new_best = progress.record(&completed.level, completed.time);
+    progress.save();
+    commands.insert_resource(LevelResult {
+        level: completed.level.clone(),
+        time: completed.time,
+        best_time: progress
+            .best_time(&completed.level)
+            .unwrap_or(completed.time),
+        new_best,
+    });
+    next_state.set(AppState::LevelComplete);
+}
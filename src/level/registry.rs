@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+
+/// All levels of the game, in the order they are played and unlocked
+#[derive(Resource, Debug)]
+pub struct LevelRegistry {
+    pub levels: Vec<LevelInfo>,
+}
+
+impl Default for LevelRegistry {
+    fn default() -> Self {
+        Self {
+            levels: vec![
+                LevelInfo {
+                    id: "meadow",
+                    name: "Meadow",
+                    goal: LevelGoal::Reach { trigger: "exit" },
+                },
+                LevelInfo {
+                    id: "ruins",
+                    name: "Ruins",
+                    goal: LevelGoal::Survive { seconds: 60.0 },
+                },
+            ],
+        }
+    }
+}
+
+impl LevelRegistry {
+    pub fn get(&self, id: &str) -> Option<&LevelInfo> {
+        self.levels.iter().find(|level| level.id == id)
+    }
+
+    pub fn index_of(&self, id: &str) -> Option<usize> {
+        self.levels.iter().position(|level| level.id == id)
+    }
+
+    /// The level unlocked by completing the given one
+    pub fn next(&self, id: &str) -> Option<&LevelInfo> {
+        self.levels.get(self.index_of(id)? + 1)
+    }
+}
+
+#[derive(Debug)]
+pub struct LevelInfo {
+    /// Also the name of the level's map, `levels/<id>.tmj`
+    pub id: &'static str,
+    /// Shown in the menus
+    pub name: &'static str,
+    pub goal: LevelGoal,
+}
+
+/// What has to be done to complete a level
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LevelGoal {
+    /// Touch the [`LevelTrigger`](super::LevelTrigger) with this name
+    Reach { trigger: &'static str },
+    /// Keep playing the level for this long
+    Survive { seconds: f32 },
+}
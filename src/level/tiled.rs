@@ -0,0 +1,269 @@
+use crate::level::{LevelMap, LevelObject, LevelTile, LevelTileset, TileLayer};
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::{fmt, io};
+
+// Tiled stores whether a tile is flipped in the highest bits of its global tile id
+const FLIPPED_HORIZONTALLY: u32 = 0x8000_0000;
+const FLIPPED_VERTICALLY: u32 = 0x4000_0000;
+/// Diagonal flips (rotating by 90°) and the rotation of hexagonal tiles, neither is supported
+const FLIPPED_DIAGONALLY_OR_ROTATED: u32 = 0x3000_0000;
+const TILE_ID_MASK: u32 = 0x0fff_ffff;
+
+/// Loads maps saved by the Tiled editor in its JSON format (`.tmj`) as [`LevelMap`]s
+/// Only orthogonal maps with embedded tilesets and uncompressed (CSV) tile layer data are supported
+#[derive(Default)]
+pub struct TiledMapLoader;
+
+#[derive(Debug)]
+pub enum TiledMapLoaderError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    Unsupported(String),
+}
+
+impl fmt::Display for TiledMapLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TiledMapLoaderError::Io(error) => write!(f, "could not read the file: {error}"),
+            TiledMapLoaderError::Parse(error) => write!(f, "invalid map: {error}"),
+            TiledMapLoaderError::Unsupported(feature) => write!(f, "unsupported map: {feature}"),
+        }
+    }
+}
+
+impl std::error::Error for TiledMapLoaderError {}
+
+impl From<io::Error> for TiledMapLoaderError {
+    fn from(error: io::Error) -> Self {
+        TiledMapLoaderError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for TiledMapLoaderError {
+    fn from(error: serde_json::Error) -> Self {
+        TiledMapLoaderError::Parse(error)
+    }
+}
+
+// The parts of Tiled's JSON map format the game uses, see <https://doc.mapeditor.org/en/stable/reference/json-map-format/>
+
+#[derive(Deserialize)]
+struct TiledMap {
+    orientation: String,
+    width: u32,
+    height: u32,
+    tilewidth: u32,
+    tileheight: u32,
+    layers: Vec<TiledLayer>,
+    tilesets: Vec<TiledTileset>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum TiledLayer {
+    TileLayer {
+        name: String,
+        width: u32,
+        data: Vec<u32>,
+        #[serde(default = "visible")]
+        visible: bool,
+    },
+    ObjectGroup {
+        objects: Vec<TiledObject>,
+    },
+    // Image layers and groups
+    #[serde(other)]
+    Other,
+}
+
+fn visible() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct TiledObject {
+    #[serde(default)]
+    name: String,
+    // Called "class" in the editor since Tiled 1.9
+    #[serde(default, rename = "type", alias = "class")]
+    class: String,
+    x: f32,
+    y: f32,
+    #[serde(default)]
+    width: f32,
+    #[serde(default)]
+    height: f32,
+    // Custom properties set in the editor
+    #[serde(default)]
+    properties: Vec<TiledProperty>,
+}
+
+#[derive(Deserialize)]
+struct TiledProperty {
+    name: String,
+    value: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct TiledTileset {
+    firstgid: u32,
+    // Only set for embedded tilesets, external ones have a `source` instead
+    image: Option<String>,
+    tilewidth: Option<u32>,
+    tileheight: Option<u32>,
+    #[serde(default)]
+    tilecount: u32,
+    #[serde(default)]
+    columns: u32,
+    #[serde(default)]
+    margin: u32,
+    #[serde(default)]
+    spacing: u32,
+}
+
+impl AssetLoader for TiledMapLoader {
+    type Asset = LevelMap;
+    type Settings = ();
+    type Error = TiledMapLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let map: TiledMap = serde_json::from_slice(&bytes)?;
+        if map.orientation != "orthogonal" {
+            return Err(TiledMapLoaderError::Unsupported(format!(
+                "{} orientation",
+                map.orientation
+            )));
+        }
+
+        let mut tilesets = Vec::new();
+        for (index, tileset) in map.tilesets.iter().enumerate() {
+            let (Some(image), Some(tile_width), Some(tile_height)) =
+                (&tileset.image, tileset.tilewidth, tileset.tileheight)
+            else {
+                return Err(TiledMapLoaderError::Unsupported(
+                    "external tilesets, embed them in the map".to_string(),
+                ));
+            };
+            // Tileset images are referenced relative to the map
+            let image_path = load_context
+                .asset_path()
+                .resolve_embed(image)
+                .map_err(|error| {
+                    TiledMapLoaderError::Unsupported(format!("tileset image {image}: {error}"))
+                })?;
+            let columns = tileset.columns.max(1);
+            let layout = TextureAtlasLayout::from_grid(
+                UVec2::new(tile_width, tile_height),
+                columns,
+                tileset.tilecount.div_ceil(columns),
+                Some(UVec2::splat(tileset.spacing)),
+                Some(UVec2::splat(tileset.margin)),
+            );
+            tilesets.push(LevelTileset {
+                first_id: tileset.firstgid,
+                image: load_context.load(image_path),
+                layout: load_context.add_labeled_asset(format!("tileset{index}"), layout),
+            });
+        }
+
+        let tile_size = Vec2::new(map.tilewidth as f32, map.tileheight as f32);
+        let size = UVec2::new(map.width, map.height).as_vec2() * tile_size;
+        // Tiled's y axis points down from the top left corner, the level is centered on the origin
+        let to_level = |position: Vec2| Vec2::new(position.x, size.y - position.y) - size / 2.0;
+
+        let mut layers = Vec::new();
+        let mut objects = Vec::new();
+        for layer in map.layers {
+            match layer {
+                TiledLayer::TileLayer {
+                    name,
+                    width,
+                    data,
+                    visible,
+                } => {
+                    if !visible {
+                        continue;
+                    }
+                    if data
+                        .iter()
+                        .any(|id| id & FLIPPED_DIAGONALLY_OR_ROTATED != 0)
+                    {
+                        return Err(TiledMapLoaderError::Unsupported(format!(
+                            "rotated tiles in layer {name}, only horizontal and vertical flips are supported"
+                        )));
+                    }
+                    let tiles = data
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(cell, id)| {
+                            let tile_id = id & TILE_ID_MASK;
+                            // The tileset with the highest first id that isn't above the tile's id
+                            let tileset = tilesets
+                                .iter()
+                                .rposition(|tileset| tileset.first_id <= tile_id)?;
+                            let column = cell as u32 % width.max(1);
+                            let row = cell as u32 / width.max(1);
+                            let corner = UVec2::new(column, row).as_vec2() * tile_size;
+                            Some(LevelTile {
+                                position: to_level(corner + Vec2::new(0.5, 0.5) * tile_size),
+                                tileset,
+                                index: (tile_id - tilesets[tileset].first_id) as usize,
+                                flip_x: id & FLIPPED_HORIZONTALLY != 0,
+                                flip_y: id & FLIPPED_VERTICALLY != 0,
+                            })
+                        })
+                        .collect();
+                    layers.push(TileLayer { name, tiles });
+                }
+                TiledLayer::ObjectGroup {
+                    objects: layer_objects,
+                } => {
+                    objects.extend(layer_objects.into_iter().map(|object| {
+                        let corner = Vec2::new(object.x, object.y);
+                        let far_corner = corner + Vec2::new(object.width, object.height);
+                        let properties: HashMap<String, String> = object
+                            .properties
+                            .into_iter()
+                            .map(|property| {
+                                let value = match property.value {
+                                    serde_json::Value::String(value) => value,
+                                    value => value.to_string(),
+                                };
+                                (property.name, value)
+                            })
+                            .collect();
+                        LevelObject {
+                            name: object.name,
+                            class: object.class,
+                            rect: Rect::from_corners(to_level(corner), to_level(far_corner)),
+                            properties,
+                        }
+                    }));
+                }
+                TiledLayer::Other => {}
+            }
+        }
+
+        Ok(LevelMap {
+            size,
+            tilesets,
+            layers,
+            objects,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tmj"]
+    }
+}
@@ -1,21 +1,49 @@
 #![allow(clippy::type_complexity)]
 
 mod actions;
+mod animation;
 mod audio;
 pub mod camera;
+mod enemies;
 mod gameplay;
+mod health;
+mod hud;
+mod level;
 mod loading;
 mod menus;
 mod overlay_state;
+mod pause;
+mod physics;
 mod player;
+mod save_game;
+mod score;
+pub mod settings_file;
+mod storage;
+#[cfg(test)]
+mod test_support;
+#[cfg(test)]
+mod tests;
 
 use crate::actions::ActionsPlugin;
+use crate::animation::SpriteAnimationPlugin;
 use crate::audio::InternalAudioPlugin;
+use crate::enemies::EnemyPlugin;
 use crate::gameplay::GameplayPlugin;
+use crate::health::HealthPlugin;
+use crate::hud::HudPlugin;
+use crate::level::LevelPlugin;
 use crate::loading::LoadingPlugin;
-use crate::menus::{GameMenuPlugin, MainMenuPlugin, SettingsMenuPlugin};
+use crate::menus::{
+    GameMenuPlugin, GameOverMenuPlugin, HighScoresMenuPlugin, LevelCompleteMenuPlugin,
+    LevelSelectMenuPlugin, MainMenuPlugin, SaveSlotsMenuPlugin, SettingsMenuPlugin, WidgetsPlugin,
+};
 use crate::overlay_state::OverlayStatePlugin;
+use crate::pause::PausePlugin;
+use crate::physics::PhysicsPlugin;
 use crate::player::PlayerPlugin;
+use crate::save_game::SaveGamePlugin;
+use crate::score::ScorePlugin;
+use crate::settings_file::SettingsFilePlugin;
 
 use bevy::app::App;
 #[cfg(debug_assertions)]
@@ -30,10 +58,20 @@ enum AppState {
     // During the loading State the LoadingPlugin will load our assets
     #[default]
     Loading,
+    // Shown instead of the menu if any asset failed to load
+    LoadingFailed,
+    // Here the level to play is picked
+    LevelSelect,
     // During this State the actual game logic is executed
     InGame,
+    // Shown after the goal of a level was reached
+    LevelComplete,
+    // Shown after the player died
+    GameOver,
     // Here the main menu is drawn and waiting for player interaction
     MainMenu,
+    // The best runs, reached from the main menu
+    HighScores,
     // Settings menu state
     SettingsMenu,
 }
@@ -42,17 +80,37 @@ pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<AppState>().add_plugins((
-            LoadingPlugin,
-            MainMenuPlugin,
-            SettingsMenuPlugin,
-            GameMenuPlugin,
-            ActionsPlugin,
-            InternalAudioPlugin,
-            PlayerPlugin,
-            OverlayStatePlugin,
-            GameplayPlugin,
-        ));
+        app.init_state::<AppState>()
+            .add_plugins((
+                SettingsFilePlugin,
+                LoadingPlugin,
+                MainMenuPlugin,
+                LevelSelectMenuPlugin,
+                LevelCompleteMenuPlugin,
+                GameOverMenuPlugin,
+                HighScoresMenuPlugin,
+                SettingsMenuPlugin,
+                GameMenuPlugin,
+                SaveSlotsMenuPlugin,
+                WidgetsPlugin,
+                ActionsPlugin,
+                InternalAudioPlugin,
+                OverlayStatePlugin,
+                PausePlugin,
+            ))
+            // Gameplay
+            .add_plugins((
+                PlayerPlugin,
+                EnemyPlugin,
+                SpriteAnimationPlugin,
+                LevelPlugin,
+                PhysicsPlugin,
+                HealthPlugin,
+                ScorePlugin,
+                HudPlugin,
+                GameplayPlugin,
+                SaveGamePlugin,
+            ));
 
         #[cfg(debug_assertions)]
         {
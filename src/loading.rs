@@ -1,37 +0,0 @@
-use crate::AppState;
-use bevy::prelude::*;
-use bevy_asset_loader::prelude::*;
-use bevy_kira_audio::AudioSource;
-
-pub struct LoadingPlugin;
-
-/// This plugin loads all assets using [`AssetLoader`] from a third party bevy plugin
-/// Alternatively you can write the logic to load assets yourself
-/// If interested, take a look at <https://bevy-cheatbook.github.io/features/assets.html>
-impl Plugin for LoadingPlugin {
-    fn build(&self, app: &mut App) {
-        app.add_loading_state(
-            LoadingState::new(AppState::Loading)
-                .continue_to_state(AppState::MainMenu)
-                .load_collection::<AudioAssets>()
-                .load_collection::<TextureAssets>(),
-        );
-    }
-}
-
-// the following asset collections will be loaded during the State `AppState::Loading`
-// when done loading, they will be inserted as resources (see <https://github.com/NiklasEi/bevy_asset_loader>)
-
-#[derive(AssetCollection, Resource)]
-pub struct AudioAssets {
-    #[asset(path = "audio/flying.ogg")]
-    pub flying: Handle<AudioSource>,
-}
-
-#[derive(AssetCollection, Resource)]
-pub struct TextureAssets {
-    #[asset(path = "textures/bevy.png")]
-    pub bevy: Handle<Image>,
-    #[asset(path = "textures/github.png")]
-    pub github: Handle<Image>,
-}
@@ -0,0 +1,188 @@
+use crate::loading::{LoadingError, LoadingProgress};
+use crate::menus::widgets::{
+    button, label, menu_container, title, ButtonStyle, BAR_COLOR, DISABLED_TEXT_COLOR,
+};
+use crate::AppState;
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// Shown one after another below the progress bar
+const TIPS: [&str; 4] = [
+    "Press Escape or Start to open the game menu",
+    "Every control can be rebound in the settings",
+    "Menus can be used with the arrow keys or a gamepad",
+    "Save often, there are three slots to choose from",
+];
+
+/// How long each tip is shown before the next one
+const TIP_DURATION: Duration = Duration::from_secs(4);
+
+pub struct LoadingScreenPlugin;
+
+/// This plugin draws the loading screen during `AppState::Loading`
+/// and the error screen during `AppState::LoadingFailed`
+impl Plugin for LoadingScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LoadingScreenSettings>()
+            .add_systems(OnEnter(AppState::Loading), setup_loading_screen)
+            .add_systems(
+                Update,
+                (update_progress_bar, rotate_tips).run_if(in_state(AppState::Loading)),
+            )
+            .add_systems(OnExit(AppState::Loading), cleanup_loading_screen)
+            .add_systems(OnEnter(AppState::LoadingFailed), setup_error_screen)
+            .add_systems(
+                Update,
+                handle_quit_button.run_if(in_state(AppState::LoadingFailed)),
+            );
+    }
+}
+
+#[derive(Resource)]
+pub struct LoadingScreenSettings {
+    /// The loading screen stays at least this long, so it doesn't just flash up when loading is fast
+    pub min_display_time: Duration,
+}
+
+impl Default for LoadingScreenSettings {
+    fn default() -> Self {
+        Self {
+            min_display_time: Duration::from_millis(500),
+        }
+    }
+}
+
+#[derive(Component)]
+struct LoadingScreen;
+
+#[derive(Component)]
+struct ProgressBarFill;
+
+#[derive(Component)]
+struct ProgressText;
+
+#[derive(Component)]
+struct TipText;
+
+#[derive(Component)]
+struct ErrorScreen;
+
+#[derive(Component)]
+struct ErrorQuitButton;
+
+fn setup_loading_screen(mut commands: Commands) {
+    commands
+        .spawn((menu_container(), LoadingScreen))
+        .with_children(|children| {
+            title(children, "Loading");
+
+            // Progress bar
+            children
+                .spawn((
+                    Node {
+                        width: Val::Px(400.0),
+                        height: Val::Px(24.0),
+                        ..default()
+                    },
+                    BackgroundColor(BAR_COLOR),
+                ))
+                .with_child((
+                    Node {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::linear_rgb(0.9, 0.9, 0.9)),
+                    ProgressBarFill,
+                ));
+
+            label(children, "0%", 20.0).insert((
+                Node {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                },
+                ProgressText,
+            ));
+
+            // Tip
+            label(children, TIPS[0], 20.0).insert((TextColor(DISABLED_TEXT_COLOR), TipText));
+        });
+}
+
+fn update_progress_bar(
+    progress: Res<LoadingProgress>,
+    mut fill_query: Query<&mut Node, With<ProgressBarFill>>,
+    mut text_query: Query<&mut Text, With<ProgressText>>,
+) {
+    if !progress.is_changed() {
+        return;
+    }
+    let percent = progress.fraction() * 100.0;
+    for mut node in &mut fill_query {
+        node.width = Val::Percent(percent);
+    }
+    for mut text in &mut text_query {
+        text.0 = format!("{percent:.0}%");
+    }
+}
+
+fn rotate_tips(
+    time: Res<Time<Real>>,
+    progress: Res<LoadingProgress>,
+    mut text_query: Query<&mut Text, With<TipText>>,
+) {
+    let shown_for = time.elapsed().saturating_sub(progress.started);
+    let tip = TIPS[(shown_for.as_secs() / TIP_DURATION.as_secs()) as usize % TIPS.len()];
+    for mut text in &mut text_query {
+        if text.0 != tip {
+            text.0 = tip.to_string();
+        }
+    }
+}
+
+fn cleanup_loading_screen(mut commands: Commands, screen: Query<Entity, With<LoadingScreen>>) {
+    for entity in screen.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn setup_error_screen(mut commands: Commands, error: Option<Res<LoadingError>>) {
+    let (path, message) = error.map_or_else(
+        || ("unknown asset".to_string(), String::new()),
+        |error| (error.path.clone(), error.message.clone()),
+    );
+
+    commands
+        .spawn((menu_container(), ErrorScreen))
+        .with_children(|children| {
+            title(children, "Failed to load the game").insert(TextFont {
+                font_size: 48.0,
+                ..default()
+            });
+
+            label(children, path, 24.0).insert(TextColor(Color::linear_rgb(0.9, 0.4, 0.4)));
+
+            label(children, message, 16.0).insert((
+                TextColor(DISABLED_TEXT_COLOR),
+                Node {
+                    max_width: Val::Percent(80.0),
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                },
+            ));
+
+            // Quit Button
+            button(children, "Quit", ButtonStyle::LARGE.with_width(250.0)).insert(ErrorQuitButton);
+        });
+}
+
+fn handle_quit_button(
+    mut app_exit_events: EventWriter<bevy::app::AppExit>,
+    interaction_query: Query<&Interaction, (With<ErrorQuitButton>, Changed<Interaction>)>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            app_exit_events.send_default();
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use crate::animation::SpriteAnimations;
+use crate::enemies::EnemyArchetypes;
+use crate::level::LevelMap;
+use crate::AppState;
+use bevy::asset::LoadState;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_asset_loader::prelude::*;
+use bevy_kira_audio::AudioSource;
+use std::time::Duration;
+
+mod loading_screen;
+
+pub use loading_screen::LoadingScreenSettings;
+
+pub struct LoadingPlugin;
+
+/// This plugin loads all assets using [`AssetLoader`] from a third party bevy plugin
+/// Alternatively you can write the logic to load assets yourself
+/// If interested, take a look at <https://bevy-cheatbook.github.io/features/assets.html>
+///
+/// While loading, the progress over all collections is tracked in [`LoadingProgress`] and shown on a loading screen
+/// If any asset fails to load, the game continues to `AppState::LoadingFailed` and shows what went wrong
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_loading_state(
+            LoadingState::new(AppState::Loading)
+                .load_collection::<AudioAssets>()
+                .load_collection::<TextureAssets>()
+                .load_collection::<AnimationAssets>()
+                .load_collection::<LevelAssets>()
+                .load_collection::<EnemyAssets>(),
+        )
+        .add_plugins(loading_screen::LoadingScreenPlugin)
+        .init_resource::<LoadingProgress>()
+        // Every collection loaded above also has to be tracked here
+        .add_systems(
+            OnEnter(AppState::Loading),
+            (
+                track_collection::<AudioAssets>,
+                track_collection::<TextureAssets>,
+                track_collection::<AnimationAssets>,
+                track_collection::<LevelAssets>,
+                track_collection::<EnemyAssets>,
+            ),
+        )
+        .add_systems(
+            Update,
+            (update_loading_progress, finish_loading)
+                .chain()
+                .run_if(in_state(AppState::Loading)),
+        );
+    }
+}
+
+// the following asset collections will be loaded during the State `AppState::Loading`
+// when done loading, they will be inserted as resources (see <https://github.com/NiklasEi/bevy_asset_loader>)
+
+#[derive(AssetCollection, Resource)]
+pub struct AudioAssets {
+    #[asset(path = "audio/flying.ogg")]
+    pub flying: Handle<AudioSource>,
+    #[asset(path = "audio/pickup.ogg")]
+    pub pickup: Handle<AudioSource>,
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct TextureAssets {
+    #[asset(path = "textures/bevy.png")]
+    pub bevy: Handle<Image>,
+    #[asset(path = "textures/github.png")]
+    pub github: Handle<Image>,
+    #[asset(path = "textures/player.png")]
+    pub player: Handle<Image>,
+    #[asset(texture_atlas_layout(tile_size_x = 64, tile_size_y = 64, columns = 4, rows = 3))]
+    pub player_layout: Handle<TextureAtlasLayout>,
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct AnimationAssets {
+    #[asset(path = "animations/player.anim.ron")]
+    pub player: Handle<SpriteAnimations>,
+}
+
+/// One map for every level of the [`LevelRegistry`](crate::level::LevelRegistry), by level id
+#[derive(AssetCollection, Resource)]
+pub struct LevelAssets {
+    #[asset(
+        paths("levels/meadow.tmj", "levels/ruins.tmj"),
+        collection(typed, mapped)
+    )]
+    pub maps: HashMap<AssetFileStem, Handle<LevelMap>>,
+}
+
+#[derive(AssetCollection, Resource)]
+pub struct EnemyAssets {
+    #[asset(path = "enemies/archetypes.enemies.ron")]
+    pub archetypes: Handle<EnemyArchetypes>,
+}
+
+struct TrackedCollection {
+    handles: Vec<UntypedHandle>,
+    // The loader inserts a collection as resource a bit after all of its assets are loaded
+    is_ready: fn(&World) -> bool,
+}
+
+/// How far loading all asset collections has come
+#[derive(Resource, Default)]
+pub struct LoadingProgress {
+    collections: Vec<TrackedCollection>,
+    pub loaded: usize,
+    pub total: usize,
+    /// Real time at which loading started
+    pub started: Duration,
+}
+
+impl LoadingProgress {
+    /// Between `0.0` and `1.0`
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.loaded as f32 / self.total as f32
+        }
+    }
+
+    /// Adds handles that have to be loaded before loading is done
+    pub fn track(&mut self, handles: Vec<UntypedHandle>, is_ready: fn(&World) -> bool) {
+        self.total += handles.len();
+        self.collections
+            .push(TrackedCollection { handles, is_ready });
+    }
+}
+
+/// The asset that failed to load, shown on the error screen
+#[derive(Resource, Debug)]
+pub struct LoadingError {
+    pub path: String,
+    pub message: String,
+}
+
+fn collection_ready<A: AssetCollection>(world: &World) -> bool {
+    world.contains_resource::<A>()
+}
+
+// The asset server hands out the same handles the loader is waiting on, since they share their paths
+fn track_collection<A: AssetCollection>(world: &mut World) {
+    let handles = A::load(world);
+    let started = world.resource::<Time<Real>>().elapsed();
+    let mut progress = world.resource_mut::<LoadingProgress>();
+    if progress.collections.is_empty() {
+        progress.started = started;
+    }
+    progress.track(handles, collection_ready::<A>);
+}
+
+fn update_loading_progress(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut progress: ResMut<LoadingProgress>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let handles = || {
+        progress
+            .collections
+            .iter()
+            .flat_map(|collection| collection.handles.iter())
+    };
+
+    let failed = handles().find_map(|handle| match asset_server.get_load_state(handle.id()) {
+        Some(LoadState::Failed(error)) => Some((handle, error.to_string())),
+        _ if asset_server
+            .get_recursive_dependency_load_state(handle.id())
+            .is_some_and(|state| state.is_failed()) =>
+        {
+            Some((handle, "a dependency failed to load".to_string()))
+        }
+        _ => None,
+    });
+    if let Some((handle, message)) = failed {
+        let path = handle
+            .path()
+            .map_or_else(|| "unknown asset".to_string(), ToString::to_string);
+        error!("Failed to load {path}: {message}");
+        commands.insert_resource(LoadingError { path, message });
+        next_state.set(AppState::LoadingFailed);
+        return;
+    }
+
+    let loaded = handles()
+        .filter(|handle| asset_server.is_loaded_with_dependencies(handle.id()))
+        .count();
+    // Only touch the resource on change, so the loading screen only updates when there is progress
+    if progress.loaded != loaded {
+        progress.loaded = loaded;
+    }
+}
+
+fn finish_loading(world: &mut World) {
+    let progress = world.resource::<LoadingProgress>();
+    if progress.total == 0 || progress.loaded < progress.total {
+        return;
+    }
+    if !progress
+        .collections
+        .iter()
+        .all(|collection| (collection.is_ready)(world))
+    {
+        return;
+    }
+    let shown_for = world.resource::<Time<Real>>().elapsed() - progress.started;
+    if shown_for < world.resource::<LoadingScreenSettings>().min_display_time {
+        return;
+    }
+    // The handles only had to be kept for tracking, the collections hold on to their own
+    world.resource_mut::<LoadingProgress>().collections.clear();
+    world
+        .resource_mut::<NextState<AppState>>()
+        .set(AppState::MainMenu);
+}
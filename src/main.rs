@@ -3,29 +3,39 @@
 
 use bevy::asset::AssetMetaCheck;
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
+use bevy::window::{MonitorSelection, PrimaryWindow};
 use bevy::winit::WinitWindows;
 use bevy::DefaultPlugins;
 use bevy_game::camera::CameraPlugin;
+use bevy_game::settings_file::SettingsFile;
 use bevy_game::GamePlugin; // ToDo: Replace bevy_game with your new crate name.
 use std::io::Cursor;
 use winit::window::Icon;
 
 fn main() {
+    // Restore the display settings from the last session before the window gets created
+    let settings = SettingsFile::load();
+    let mut primary_window = Window {
+        title: "Bevy game".to_string(), // ToDo
+        // Bind to canvas included in `index.html`
+        canvas: Some("#bevy".to_owned()),
+        fit_canvas_to_parent: true,
+        // Tells wasm not to override default event handling, like F5 and Ctrl+R
+        prevent_default_event_handling: false,
+        ..default()
+    };
+    // Monitors are only known once the app runs, see `DisplaySettingsPlugin`
+    settings
+        .display
+        .apply_to_window(&mut primary_window, MonitorSelection::Primary);
+
     App::new()
         .insert_resource(ClearColor(Color::linear_rgb(0.05, 0.05, 0.05)))
+        .insert_resource(settings)
         .add_plugins(
             DefaultPlugins
                 .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        title: "Bevy game".to_string(), // ToDo
-                        // Bind to canvas included in `index.html`
-                        canvas: Some("#bevy".to_owned()),
-                        fit_canvas_to_parent: true,
-                        // Tells wasm not to override default event handling, like F5 and Ctrl+R
-                        prevent_default_event_handling: false,
-                        ..default()
-                    }),
+                    primary_window: Some(primary_window),
                     ..default()
                 })
                 .set(AssetPlugin {
@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+
+pub struct FocusPlugin;
+
+/// This plugin lets every menu be used with the keyboard or a gamepad
+/// Arrow keys and the d-pad move the focus between [`Focusable`] buttons based on where they are on screen,
+/// Enter and the gamepad's south button press the focused button, Space is left free for the game's own bindings
+/// The focused button is highlighted by the [`WidgetsPlugin`](super::widgets::WidgetsPlugin), like a hovered one
+impl Plugin for FocusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FocusedEntity>()
+            .init_resource::<ActivatedButtons>()
+            .add_systems(
+                PreUpdate,
+                (reset_activated_buttons, activate_focused)
+                    .chain()
+                    .in_set(FocusSystem)
+                    .after(UiSystem::Focus),
+            )
+            .add_systems(Update, (focus_hovered, navigate_focus).chain());
+    }
+}
+
+/// Systems that press the focused button
+/// Anything consuming keyboard or gamepad input before the menus see it should run before this set
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct FocusSystem;
+
+/// Marks buttons that can be reached with the keyboard or a gamepad
+#[derive(Component)]
+pub struct Focusable;
+
+#[derive(Resource, Default)]
+pub struct FocusedEntity(pub Option<Entity>);
+
+const ACTIVATE_KEYS: [KeyCode; 2] = [KeyCode::Enter, KeyCode::NumpadEnter];
+
+fn navigation_direction(
+    keyboard_input: &ButtonInput<KeyCode>,
+    gamepads: &Query<&Gamepad>,
+) -> Option<Vec2> {
+    // UI coordinates grow to the right and downwards
+    let directions = [
+        (KeyCode::ArrowUp, GamepadButton::DPadUp, Vec2::NEG_Y),
+        (KeyCode::ArrowDown, GamepadButton::DPadDown, Vec2::Y),
+        (KeyCode::ArrowLeft, GamepadButton::DPadLeft, Vec2::NEG_X),
+        (KeyCode::ArrowRight, GamepadButton::DPadRight, Vec2::X),
+    ];
+    directions
+        .into_iter()
+        .find(|(key, button, _)| {
+            keyboard_input.just_pressed(*key)
+                || gamepads.iter().any(|gamepad| gamepad.just_pressed(*button))
+        })
+        .map(|(_, _, direction)| direction)
+}
+
+fn navigate_focus(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut focused: ResMut<FocusedEntity>,
+    focusables: Query<(Entity, &GlobalTransform, &ComputedNode), With<Focusable>>,
+) {
+    let Some(direction) = navigation_direction(&keyboard_input, &gamepads) else {
+        return;
+    };
+
+    // Buttons in hidden containers (e.g. inactive settings tabs) have no size
+    let visible = || {
+        focusables
+            .iter()
+            .filter(|(_, _, node)| node.size() != Vec2::ZERO)
+            .map(|(entity, transform, _)| (entity, transform.translation().truncate()))
+    };
+
+    let current = focused
+        .0
+        .and_then(|entity| visible().find(|(candidate, _)| *candidate == entity));
+    let Some((current_entity, current_position)) = current else {
+        // Nothing focused yet, so the first press focuses the top left button
+        focused.0 = visible()
+            .min_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)))
+            .map(|(entity, _)| entity);
+        return;
+    };
+
+    // Prefer buttons that are straight in the given direction over closer ones that are off to the side
+    let next = visible()
+        .filter(|(entity, _)| *entity != current_entity)
+        .filter_map(|(entity, position)| {
+            let offset = position - current_position;
+            let along = offset.dot(direction);
+            if along <= 0.0 {
+                return None;
+            }
+            let across = (offset - direction * along).length();
+            Some((entity, along + across * 2.0))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(entity, _)| entity);
+
+    if let Some(next) = next {
+        focused.0 = Some(next);
+    }
+}
+
+fn focus_hovered(
+    mut focused: ResMut<FocusedEntity>,
+    interaction_query: Query<(Entity, &Interaction), (With<Focusable>, Changed<Interaction>)>,
+) {
+    for (entity, interaction) in &interaction_query {
+        if *interaction == Interaction::Hovered {
+            focused.0 = Some(entity);
+        }
+    }
+}
+
+/// Buttons pressed through the focus don't get a mouse release, so they are reset here one frame later
+#[derive(Resource, Default)]
+struct ActivatedButtons(Vec<Entity>);
+
+fn reset_activated_buttons(
+    mut activated: ResMut<ActivatedButtons>,
+    mut interaction_query: Query<&mut Interaction>,
+) {
+    for entity in activated.0.drain(..) {
+        if let Ok(mut interaction) = interaction_query.get_mut(entity) {
+            if *interaction == Interaction::Pressed {
+                *interaction = Interaction::None;
+            }
+        }
+    }
+}
+
+fn activate_focused(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut activated: ResMut<ActivatedButtons>,
+    focused: Res<FocusedEntity>,
+    mut interaction_query: Query<(&mut Interaction, &ComputedNode), With<Focusable>>,
+) {
+    let pressed = keyboard_input.any_just_pressed(ACTIVATE_KEYS)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::South));
+    if !pressed {
+        return;
+    }
+    let Some(entity) = focused.0 else {
+        return;
+    };
+    let Ok((mut interaction, node)) = interaction_query.get_mut(entity) else {
+        return;
+    };
+    // Like the navigation, buttons in hidden containers can't be pressed
+    if node.size() == Vec2::ZERO {
+        return;
+    }
+    // Same as a mouse click, so every menu handler reacting to `Interaction::Pressed` picks it up
+    *interaction = Interaction::Pressed;
+    activated.0.push(entity);
+}
@@ -1,4 +1,4 @@
-use crate::menus::ui_components::ButtonColors;
+use crate::menus::widgets::{button, menu_container, title, ButtonStyle};
 use crate::overlay_state::GameOverlayState;
 use crate::AppState;
 use bevy::prelude::*;
@@ -11,8 +11,9 @@ impl Plugin for GameMenuPlugin {
             .add_systems(
                 Update,
                 (
-                    handle_button_hover,
                     handle_resume_button,
+                    handle_save_button,
+                    handle_load_button,
                     handle_settings_button,
                     handle_quit_button,
                     handle_quit_to_os_button,
@@ -29,6 +30,12 @@ struct GameMenu;
 #[derive(Component)]
 struct ResumeButton;
 
+#[derive(Component)]
+struct SaveButton;
+
+#[derive(Component)]
+struct LoadButton;
+
 #[derive(Component)]
 struct SettingsButton;
 
@@ -39,162 +46,48 @@ struct QuitButton;
 struct QuitToOSButton;
 
 fn setup_game_menu(mut commands: Commands) {
-    // Menu container
     commands
-        .spawn((
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                flex_direction: FlexDirection::Column,
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },
-            GameMenu,
-        ))
+        .spawn((menu_container(), GameMenu))
         .with_children(|children| {
-            // Title
-            children.spawn((
-                Text::new("Game Menu"),
-                TextFont {
-                    font_size: 72.0,
-                    ..default()
-                },
-                TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                Node {
-                    margin: UiRect::bottom(Val::Px(50.0)),
-                    ..default()
-                },
-            ));
-
-            // Resume Button
-            children
-                .spawn((
-                    Button,
-                    Node {
-                        width: Val::Px(300.0),
-                        height: Val::Px(50.0),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        margin: UiRect::bottom(Val::Px(10.0)),
-                        ..Default::default()
-                    },
-                    BackgroundColor(ButtonColors::default().normal),
-                    ButtonColors::default(),
-                    ResumeButton,
-                ))
-                .with_child((
-                    Text::new("Resume"),
-                    TextFont {
-                        font_size: 40.0,
-                        ..default()
-                    },
-                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                ));
-
-            // Settings Button
-            children
-                .spawn((
-                    Button,
-                    Node {
-                        width: Val::Px(300.0),
-                        height: Val::Px(50.0),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        margin: UiRect::bottom(Val::Px(10.0)),
-                        ..Default::default()
-                    },
-                    BackgroundColor(ButtonColors::default().normal),
-                    ButtonColors::default(),
-                    SettingsButton,
-                ))
-                .with_child((
-                    Text::new("Settings"),
-                    TextFont {
-                        font_size: 40.0,
-                        ..default()
-                    },
-                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                ));
-
-            // Quit to Main Menu Button
-            children
-                .spawn((
-                    Button,
-                    Node {
-                        width: Val::Px(300.0),
-                        height: Val::Px(50.0),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        margin: UiRect::bottom(Val::Px(10.0)),
-                        ..Default::default()
-                    },
-                    BackgroundColor(ButtonColors::default().normal),
-                    ButtonColors::default(),
-                    QuitButton,
-                ))
-                .with_child((
-                    Text::new("Quit to Menu"),
-                    TextFont {
-                        font_size: 40.0,
-                        ..default()
-                    },
-                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                ));
-
-            // Quit to OS Button
-            children
-                .spawn((
-                    Button,
-                    Node {
-                        width: Val::Px(300.0),
-                        height: Val::Px(50.0),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        margin: UiRect::bottom(Val::Px(10.0)),
-                        ..Default::default()
-                    },
-                    BackgroundColor(ButtonColors::default().normal),
-                    ButtonColors::default(),
-                    QuitToOSButton,
-                ))
-                .with_child((
-                    Text::new("Quit to OS"),
-                    TextFont {
-                        font_size: 40.0,
-                        ..default()
-                    },
-                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                ));
+            title(children, "Game Menu");
+            button(children, "Resume", ButtonStyle::LARGE).insert(ResumeButton);
+            button(children, "Save", ButtonStyle::LARGE).insert(SaveButton);
+            button(children, "Load", ButtonStyle::LARGE).insert(LoadButton);
+            button(children, "Settings", ButtonStyle::LARGE).insert(SettingsButton);
+            button(children, "Quit to Menu", ButtonStyle::LARGE).insert(QuitButton);
+            button(children, "Quit to OS", ButtonStyle::LARGE).insert(QuitToOSButton);
         });
 }
 
-fn handle_button_hover(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, &ButtonColors),
-        Changed<Interaction>,
-    >,
+fn handle_resume_button(
+    mut next_overlay_state: ResMut<NextState<GameOverlayState>>,
+    interaction_query: Query<&Interaction, (With<ResumeButton>, Changed<Interaction>)>,
 ) {
-    for (interaction, mut color, button_colors) in &mut interaction_query {
-        match *interaction {
-            Interaction::Hovered => {
-                *color = button_colors.hovered.into();
-            }
-            Interaction::None => {
-                *color = button_colors.normal.into();
-            }
-            Interaction::Pressed => {}
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            next_overlay_state.set(GameOverlayState::None);
         }
     }
 }
 
-fn handle_resume_button(
+fn handle_save_button(
     mut next_overlay_state: ResMut<NextState<GameOverlayState>>,
-    interaction_query: Query<&Interaction, (With<ResumeButton>, Changed<Interaction>)>,
+    interaction_query: Query<&Interaction, (With<SaveButton>, Changed<Interaction>)>,
 ) {
     for interaction in &interaction_query {
         if *interaction == Interaction::Pressed {
-            next_overlay_state.set(GameOverlayState::None);
+            next_overlay_state.set(GameOverlayState::SaveMenu);
+        }
+    }
+}
+
+fn handle_load_button(
+    mut next_overlay_state: ResMut<NextState<GameOverlayState>>,
+    interaction_query: Query<&Interaction, (With<LoadButton>, Changed<Interaction>)>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            next_overlay_state.set(GameOverlayState::LoadMenu);
         }
     }
 }
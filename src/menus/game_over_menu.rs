@@ -0,0 +1,102 @@
+use crate::level::{CurrentLevel, LevelRegistry};
+use crate::menus::high_score_entry::spawn_high_score_entry;
+use crate::menus::widgets::{button, label, menu_container, title, ButtonStyle};
+use crate::score::{PendingHighScore, RunEndSystem, Score};
+use crate::AppState;
+use bevy::prelude::*;
+
+pub struct GameOverMenuPlugin;
+
+/// This plugin is responsible for the screen shown after the player died
+/// The level can be retried from its start or the game left to the main menu
+/// A score good enough for the high scores asks for the player's name first
+impl Plugin for GameOverMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(AppState::GameOver),
+            setup_game_over_menu.after(RunEndSystem),
+        )
+        .add_systems(
+            Update,
+            (handle_retry_button, handle_quit_button).run_if(in_state(AppState::GameOver)),
+        )
+        .add_systems(OnExit(AppState::GameOver), cleanup_game_over_menu);
+    }
+}
+
+#[derive(Component)]
+struct GameOverMenu;
+
+#[derive(Component)]
+struct RetryButton;
+
+#[derive(Component)]
+struct QuitButton;
+
+fn setup_game_over_menu(
+    mut commands: Commands,
+    current_level: Res<CurrentLevel>,
+    registry: Res<LevelRegistry>,
+    score: Res<Score>,
+    pending_high_score: Option<Res<PendingHighScore>>,
+) {
+    let name = registry
+        .get(&current_level.id)
+        .map_or(current_level.id.as_str(), |level| level.name);
+
+    commands
+        .spawn((menu_container(), GameOverMenu))
+        .with_children(|children| {
+            title(children, "Game Over").insert(TextColor(Color::linear_rgb(0.9, 0.2, 0.2)));
+
+            label(children, format!("You didn't make it through {name}"), 32.0);
+            label(children, format!("Score {}", score.points), 32.0);
+
+            if let Some(pending_high_score) = &pending_high_score {
+                spawn_high_score_entry(children, pending_high_score);
+            }
+
+            // Retry Button
+            button(
+                children,
+                "Retry",
+                ButtonStyle::LARGE.with_margin(UiRect::top(Val::Px(30.0))),
+            )
+            .insert(RetryButton);
+
+            // Quit Button
+            button(children, "Quit to Menu", ButtonStyle::LARGE).insert(QuitButton);
+        });
+}
+
+// The session was cleaned up when the game was left, so entering it again starts the level over
+fn handle_retry_button(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<AppState>>,
+    current_level: Res<CurrentLevel>,
+    interaction_query: Query<&Interaction, (With<RetryButton>, Changed<Interaction>)>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            commands.insert_resource(CurrentLevel::start(&current_level.id));
+            next_state.set(AppState::InGame);
+        }
+    }
+}
+
+fn handle_quit_button(
+    mut next_state: ResMut<NextState<AppState>>,
+    interaction_query: Query<&Interaction, (With<QuitButton>, Changed<Interaction>)>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            next_state.set(AppState::MainMenu);
+        }
+    }
+}
+
+fn cleanup_game_over_menu(mut commands: Commands, menu: Query<Entity, With<GameOverMenu>>) {
+    for entity in menu.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,201 @@
+use crate::menus::focus::FocusSystem;
+use crate::menus::widgets::{button, ButtonStyle};
+use crate::score::{HighScore, HighScores, PendingHighScore};
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::input::{ButtonState, InputSystem};
+use bevy::prelude::*;
+
+const MAX_NAME_LENGTH: usize = 12;
+/// Used if the player saves without typing a name
+const DEFAULT_NAME: &str = "Player";
+
+pub struct HighScoreEntryPlugin;
+
+/// This plugin asks for the player's name when a run made it into the [`HighScores`]
+/// Screens shown after a run add the entry with [`spawn_high_score_entry`] while there is a [`PendingHighScore`]
+/// The name is typed with the keyboard and saved with Enter or the save button
+impl Plugin for HighScoreEntryPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // Runs right after the input is collected, so typing a space doesn't also press the focused button
+            .add_systems(
+                PreUpdate,
+                type_name
+                    .after(InputSystem)
+                    .before(FocusSystem)
+                    .run_if(any_with_component::<HighScoreEntry>),
+            )
+            .add_systems(
+                Update,
+                (handle_save_button, save_high_score, update_name_text)
+                    .chain()
+                    .run_if(resource_exists::<PendingHighScore>),
+            );
+    }
+}
+
+/// Root of the name entry, holding the name typed so far
+#[derive(Component, Default)]
+struct HighScoreEntry {
+    name: String,
+    submitted: bool,
+}
+
+#[derive(Component)]
+struct HighScoreNameText;
+
+#[derive(Component)]
+struct HighScoreSaveButton;
+
+/// Adds the name entry for the pending high score to a menu
+pub fn spawn_high_score_entry(children: &mut ChildBuilder, pending: &PendingHighScore) {
+    children
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                margin: UiRect::vertical(Val::Px(20.0)),
+                ..default()
+            },
+            HighScoreEntry::default(),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(format!(
+                    "New high score: {}! Enter your name",
+                    pending.points
+                )),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::linear_rgb(0.9, 0.8, 0.2)),
+                Node {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(300.0),
+                        height: Val::Px(40.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        margin: UiRect::bottom(Val::Px(10.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::linear_rgb(0.05, 0.05, 0.05)),
+                ))
+                .with_child((
+                    Text::new("_"),
+                    TextFont {
+                        font_size: 28.0,
+                        ..default()
+                    },
+                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
+                    HighScoreNameText,
+                ));
+
+            button(parent, "Save Score", ButtonStyle::LARGE).insert(HighScoreSaveButton);
+        });
+}
+
+fn type_name(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut entry_query: Query<&mut HighScoreEntry>,
+) {
+    let Ok(mut entry) = entry_query.get_single_mut() else {
+        return;
+    };
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+        match (event.key_code, &event.logical_key) {
+            (KeyCode::Enter | KeyCode::NumpadEnter, _) => entry.submitted = true,
+            (KeyCode::Backspace, _) => {
+                entry.name.pop();
+            }
+            (KeyCode::Space, _) => push_name_character(&mut entry.name, ' '),
+            (_, Key::Character(characters)) => {
+                for character in characters.chars() {
+                    push_name_character(&mut entry.name, character);
+                }
+            }
+            _ => {}
+        }
+    }
+    // Consume the keys used for typing, otherwise they would also press the focused button
+    for key in [KeyCode::Enter, KeyCode::NumpadEnter, KeyCode::Space] {
+        keyboard_input.reset(key);
+    }
+}
+
+fn push_name_character(name: &mut String, character: char) {
+    let allowed = character.is_alphanumeric() || matches!(character, ' ' | '-' | '_' | '.');
+    if allowed && name.chars().count() < MAX_NAME_LENGTH {
+        name.push(character);
+    }
+}
+
+fn handle_save_button(
+    interaction_query: Query<&Interaction, (With<HighScoreSaveButton>, Changed<Interaction>)>,
+    mut entry_query: Query<&mut HighScoreEntry>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            for mut entry in &mut entry_query {
+                entry.submitted = true;
+            }
+        }
+    }
+}
+
+fn save_high_score(
+    mut commands: Commands,
+    pending: Res<PendingHighScore>,
+    mut high_scores: ResMut<HighScores>,
+    entry_query: Query<(Entity, &HighScoreEntry)>,
+) {
+    for (entity, entry) in &entry_query {
+        if !entry.submitted {
+            continue;
+        }
+        let name = entry.name.trim();
+        let rank = high_scores.insert(HighScore {
+            name: if name.is_empty() { DEFAULT_NAME } else { name }.to_string(),
+            points: pending.points,
+            level: pending.level.clone(),
+        });
+        high_scores.save();
+        commands.remove_resource::<PendingHighScore>();
+
+        // The entry makes room for the rank it got
+        commands
+            .entity(entity)
+            .remove::<HighScoreEntry>()
+            .despawn_descendants()
+            .with_child((
+                Text::new(format!("Saved as #{rank} of the high scores")),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::linear_rgb(0.9, 0.8, 0.2)),
+            ));
+    }
+}
+
+fn update_name_text(
+    entry_query: Query<&HighScoreEntry, Changed<HighScoreEntry>>,
+    mut text_query: Query<&mut Text, With<HighScoreNameText>>,
+) {
+    for entry in &entry_query {
+        for mut text in &mut text_query {
+            text.0 = format!("{}_", entry.name);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::level::LevelRegistry;
+use crate::menus::high_score_entry::HighScoreEntryPlugin;
+use crate::menus::widgets::{
+    button, label, menu_container, title, ButtonStyle, DISABLED_TEXT_COLOR,
+};
+use crate::score::HighScores;
+use crate::AppState;
+use bevy::prelude::*;
+
+pub struct HighScoresMenuPlugin;
+
+/// This plugin is responsible for the table of the best runs, reached from the main menu
+/// Names for new high scores are entered on the screens shown after a run, see [`HighScoreEntryPlugin`]
+impl Plugin for HighScoresMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(HighScoreEntryPlugin)
+            .add_systems(OnEnter(AppState::HighScores), setup_high_scores_menu)
+            .add_systems(
+                Update,
+                (handle_back_button).run_if(in_state(AppState::HighScores)),
+            )
+            .add_systems(OnExit(AppState::HighScores), cleanup_high_scores_menu);
+    }
+}
+
+#[derive(Component)]
+struct HighScoresMenu;
+
+#[derive(Component)]
+struct HighScoresBackButton;
+
+fn setup_high_scores_menu(
+    mut commands: Commands,
+    high_scores: Res<HighScores>,
+    registry: Res<LevelRegistry>,
+) {
+    commands
+        .spawn((menu_container(), HighScoresMenu))
+        .with_children(|children| {
+            title(children, "High Scores");
+
+            if high_scores.entries.is_empty() {
+                label(children, "No high scores yet", 24.0).insert(TextColor(DISABLED_TEXT_COLOR));
+            }
+
+            // One row per entry: rank and name on the left, level and points on the right
+            for (index, entry) in high_scores.entries.iter().enumerate() {
+                let level = registry
+                    .get(&entry.level)
+                    .map_or(entry.level.as_str(), |level| level.name);
+                children
+                    .spawn(Node {
+                        width: Val::Px(500.0),
+                        height: Val::Px(36.0),
+                        justify_content: JustifyContent::SpaceBetween,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::horizontal(Val::Px(15.0)),
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        label(parent, format!("{}. {}", index + 1, entry.name), 24.0);
+                        label(parent, format!("{level}  {}", entry.points), 24.0);
+                    });
+            }
+
+            // Back Button
+            button(
+                children,
+                "Back",
+                ButtonStyle::LARGE
+                    .with_width(400.0)
+                    .with_margin(UiRect::top(Val::Px(30.0))),
+            )
+            .insert(HighScoresBackButton);
+        });
+}
+
+fn handle_back_button(
+    mut next_state: ResMut<NextState<AppState>>,
+    interaction_query: Query<&Interaction, (With<HighScoresBackButton>, Changed<Interaction>)>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            next_state.set(AppState::MainMenu);
+        }
+    }
+}
+
+fn cleanup_high_scores_menu(mut commands: Commands, menu: Query<Entity, With<HighScoresMenu>>) {
+    for entity in menu.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,123 @@
+use crate::level::{format_time, CurrentLevel, LevelRegistry, LevelResult};
+use crate::menus::high_score_entry::spawn_high_score_entry;
+use crate::menus::widgets::{button, label, menu_container, title, ButtonStyle};
+use crate::score::{PendingHighScore, RunEndSystem, Score};
+use crate::AppState;
+use bevy::prelude::*;
+
+pub struct LevelCompleteMenuPlugin;
+
+/// This plugin is responsible for the screen shown after a level was completed
+/// It shows the [`LevelResult`] and continues with the next level of the [`LevelRegistry`] or back to the level select
+/// A score good enough for the high scores asks for the player's name first
+impl Plugin for LevelCompleteMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(AppState::LevelComplete),
+            setup_level_complete_menu.after(RunEndSystem),
+        )
+        .add_systems(
+            Update,
+            (handle_next_level_button, handle_level_select_button)
+                .run_if(in_state(AppState::LevelComplete)),
+        )
+        .add_systems(OnExit(AppState::LevelComplete), cleanup_level_complete_menu);
+    }
+}
+
+#[derive(Component)]
+struct LevelCompleteMenu;
+
+#[derive(Component)]
+struct NextLevelButton(&'static str);
+
+#[derive(Component)]
+struct LevelSelectButton;
+
+fn setup_level_complete_menu(
+    mut commands: Commands,
+    registry: Res<LevelRegistry>,
+    result: Res<LevelResult>,
+    score: Res<Score>,
+    pending_high_score: Option<Res<PendingHighScore>>,
+) {
+    let name = registry
+        .get(&result.level)
+        .map_or(result.level.as_str(), |level| level.name);
+    let best_time = if result.new_best {
+        "New best time!".to_string()
+    } else {
+        format!("Best {}", format_time(result.best_time))
+    };
+    let next_level = registry.next(&result.level);
+
+    commands
+        .spawn((menu_container(), LevelCompleteMenu))
+        .with_children(|children| {
+            title(children, "Level Complete");
+
+            // Result
+            label(
+                children,
+                format!("{name} cleared in {}", format_time(result.time)),
+                32.0,
+            );
+            label(children, best_time, 32.0);
+            label(children, format!("Score {}", score.points), 32.0);
+
+            if let Some(pending_high_score) = &pending_high_score {
+                spawn_high_score_entry(children, pending_high_score);
+            }
+
+            // Next Level Button, the last level has none
+            if let Some(next_level) = next_level {
+                button(
+                    children,
+                    "Next Level",
+                    ButtonStyle::LARGE.with_margin(UiRect::top(Val::Px(30.0))),
+                )
+                .insert(NextLevelButton(next_level.id));
+            } else {
+                label(children, "All levels complete!", 32.0).insert(Node {
+                    margin: UiRect::vertical(Val::Px(10.0)),
+                    ..default()
+                });
+            }
+
+            // Level Select Button
+            button(children, "Level Select", ButtonStyle::LARGE).insert(LevelSelectButton);
+        });
+}
+
+fn handle_next_level_button(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<AppState>>,
+    interaction_query: Query<(&Interaction, &NextLevelButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            commands.insert_resource(CurrentLevel::start(button.0));
+            next_state.set(AppState::InGame);
+        }
+    }
+}
+
+fn handle_level_select_button(
+    mut next_state: ResMut<NextState<AppState>>,
+    interaction_query: Query<&Interaction, (With<LevelSelectButton>, Changed<Interaction>)>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            next_state.set(AppState::LevelSelect);
+        }
+    }
+}
+
+fn cleanup_level_complete_menu(
+    mut commands: Commands,
+    menu: Query<Entity, With<LevelCompleteMenu>>,
+) {
+    for entity in menu.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,110 @@
+use crate::level::{format_time, CurrentLevel, LevelProgress, LevelRegistry};
+use crate::menus::widgets::{button, button_row, menu_container, title, ButtonStyle};
+use crate::AppState;
+use bevy::prelude::*;
+
+pub struct LevelSelectMenuPlugin;
+
+/// This plugin is responsible for picking the level to play, between the main menu and the game
+/// Every level of the [`LevelRegistry`] is listed with its best time, locked levels can't be picked
+impl Plugin for LevelSelectMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::LevelSelect), setup_level_select_menu)
+            .add_systems(
+                Update,
+                (handle_level_buttons, handle_back_button).run_if(in_state(AppState::LevelSelect)),
+            )
+            .add_systems(OnExit(AppState::LevelSelect), cleanup_level_select_menu);
+    }
+}
+
+#[derive(Component)]
+struct LevelSelectMenu;
+
+#[derive(Component)]
+struct LevelButton(usize);
+
+#[derive(Component)]
+struct LevelSelectBackButton;
+
+fn level_status(progress: &LevelProgress, registry: &LevelRegistry, index: usize) -> String {
+    if !progress.is_unlocked(registry, index) {
+        return "Locked".to_string();
+    }
+    match progress.best_time(registry.levels[index].id) {
+        Some(best_time) => format!("Best {}", format_time(best_time)),
+        None => "Not completed".to_string(),
+    }
+}
+
+fn setup_level_select_menu(
+    mut commands: Commands,
+    registry: Res<LevelRegistry>,
+    progress: Res<LevelProgress>,
+) {
+    commands
+        .spawn((menu_container(), LevelSelectMenu))
+        .with_children(|children| {
+            title(children, "Select Level");
+
+            // Level Buttons, locked levels can't be picked
+            let style = ButtonStyle {
+                font_size: 32.0,
+                ..ButtonStyle::LARGE
+            };
+            for (index, level) in registry.levels.iter().enumerate() {
+                let unlocked = progress.is_unlocked(&registry, index);
+                let mut row = button_row(
+                    children,
+                    level.name,
+                    level_status(&progress, &registry, index),
+                    style.with_width(400.0),
+                    unlocked,
+                );
+                if unlocked {
+                    row.insert(LevelButton(index));
+                }
+            }
+
+            // Back Button
+            button(
+                children,
+                "Back",
+                ButtonStyle::LARGE
+                    .with_width(400.0)
+                    .with_margin(UiRect::top(Val::Px(30.0))),
+            )
+            .insert(LevelSelectBackButton);
+        });
+}
+
+fn handle_level_buttons(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<AppState>>,
+    registry: Res<LevelRegistry>,
+    interaction_query: Query<(&Interaction, &LevelButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            commands.insert_resource(CurrentLevel::start(registry.levels[button.0].id));
+            next_state.set(AppState::InGame);
+        }
+    }
+}
+
+fn handle_back_button(
+    mut next_state: ResMut<NextState<AppState>>,
+    interaction_query: Query<&Interaction, (With<LevelSelectBackButton>, Changed<Interaction>)>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            next_state.set(AppState::MainMenu);
+        }
+    }
+}
+
+fn cleanup_level_select_menu(mut commands: Commands, menu: Query<Entity, With<LevelSelectMenu>>) {
+    for entity in menu.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,5 +1,6 @@
 use crate::loading::TextureAssets;
-use crate::menus::ui_components::ButtonColors;
+use crate::menus::widgets::{button, label, menu_container, title, ButtonStyle};
+use crate::save_game::{latest_slot, read_slot, PendingLoad};
 use crate::AppState;
 use bevy::prelude::*;
 
@@ -13,8 +14,9 @@ impl Plugin for MainMenuPlugin {
             .add_systems(
                 Update,
                 (
-                    handle_button_hover,
+                    handle_main_menu_continue_button,
                     handle_main_menu_play_button,
+                    handle_main_menu_high_scores_button,
                     handle_main_menu_settings_button,
                     handle_main_menu_quit_button,
                     handle_external_links,
@@ -28,121 +30,47 @@ impl Plugin for MainMenuPlugin {
 #[derive(Component)]
 struct MainMenu;
 
+#[derive(Component)]
+struct MainMenuContinueButton(usize);
+
 #[derive(Component)]
 struct MainMenuPlayButton;
 
+#[derive(Component)]
+struct MainMenuHighScoresButton;
+
 #[derive(Component)]
 struct MainMenuSettingsButton;
 
 #[derive(Component)]
 struct MainMenuQuitButton;
 
+#[derive(Component)]
+struct MainMenuStatusText;
+
 #[derive(Component)]
 struct OpenLink(&'static str);
 
 fn setup_main_menu(mut commands: Commands, textures: Res<TextureAssets>) {
-    commands
-        .spawn((
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                flex_direction: FlexDirection::Column,
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },
-            MainMenu,
-        ))
-        .with_children(|children: &mut ChildBuilder<'_>| {
+    let style = ButtonStyle::LARGE.with_width(250.0);
+    commands.spawn((menu_container(), MainMenu)).with_children(
+        |children: &mut ChildBuilder<'_>| {
             // TODO: Game Title TBD
-            children.spawn((
-                Text::new("Untitled Game"),
-                TextFont {
-                    font_size: 72.0,
-                    ..default()
-                },
-                TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                Node {
-                    margin: UiRect::bottom(Val::Px(50.0)),
-                    ..default()
-                },
-            ));
-
-            // Play Button
-            children
-                .spawn((
-                    Button,
-                    Node {
-                        width: Val::Px(250.0),
-                        height: Val::Px(50.0),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        margin: UiRect::bottom(Val::Px(10.0)),
-                        ..Default::default()
-                    },
-                    BackgroundColor(ButtonColors::default().normal),
-                    ButtonColors::default(),
-                    MainMenuPlayButton,
-                ))
-                .with_child((
-                    Text::new("Play"),
-                    TextFont {
-                        font_size: 40.0,
-                        ..default()
-                    },
-                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                ));
+            title(children, "Untitled Game");
 
-            // Settings Button
-            children
-                .spawn((
-                    Button,
-                    Node {
-                        width: Val::Px(250.0),
-                        height: Val::Px(50.0),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        margin: UiRect::bottom(Val::Px(10.0)),
-                        ..Default::default()
-                    },
-                    BackgroundColor(ButtonColors::default().normal),
-                    ButtonColors::default(),
-                    MainMenuSettingsButton,
-                ))
-                .with_child((
-                    Text::new("Settings"),
-                    TextFont {
-                        font_size: 40.0,
-                        ..default()
-                    },
-                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                ));
+            // Continue Button, only offered if there is a save to continue from
+            if let Some(slot) = latest_slot() {
+                button(children, "Continue", style).insert(MainMenuContinueButton(slot));
+            }
+            button(children, "Play", style).insert(MainMenuPlayButton);
+            button(children, "High Scores", style).insert(MainMenuHighScoresButton);
+            button(children, "Settings", style).insert(MainMenuSettingsButton);
+            button(children, "Quit", style).insert(MainMenuQuitButton);
 
-            // Quit Button
-            children
-                .spawn((
-                    Button,
-                    Node {
-                        width: Val::Px(250.0),
-                        height: Val::Px(50.0),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        margin: UiRect::bottom(Val::Px(10.0)),
-                        ..Default::default()
-                    },
-                    BackgroundColor(ButtonColors::default().normal),
-                    ButtonColors::default(),
-                    MainMenuQuitButton,
-                ))
-                .with_child((
-                    Text::new("Quit"),
-                    TextFont {
-                        font_size: 40.0,
-                        ..default()
-                    },
-                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                ));
-        });
+            // Status, e.g. when a save game can't be continued
+            label(children, "", 20.0).insert(MainMenuStatusText);
+        },
+    );
 
     // Footer buttons
     commands
@@ -159,91 +87,48 @@ fn setup_main_menu(mut commands: Commands, textures: Res<TextureAssets>) {
             MainMenu,
         ))
         .with_children(|children| {
-            children
-                .spawn((
-                    Button,
-                    Node {
-                        width: Val::Px(170.0),
-                        height: Val::Px(50.0),
-                        justify_content: JustifyContent::SpaceAround,
-                        align_items: AlignItems::Center,
-                        padding: UiRect::all(Val::Px(5.)),
-                        ..Default::default()
-                    },
-                    BackgroundColor(Color::NONE),
-                    ButtonColors::default(),
-                    OpenLink("https://bevyengine.org"),
-                ))
-                .with_children(|parent| {
-                    parent.spawn((
-                        Text::new("Made with Bevy"),
-                        TextFont {
-                            font_size: 15.0,
-                            ..default()
-                        },
-                        TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                    ));
-                    parent.spawn((
-                        ImageNode {
-                            image: textures.bevy.clone(),
-                            ..default()
-                        },
-                        Node {
-                            width: Val::Px(32.),
-                            ..default()
-                        },
-                    ));
-                });
-            children
-                .spawn((
-                    Button,
-                    Node {
-                        width: Val::Px(170.0),
-                        height: Val::Px(50.0),
-                        justify_content: JustifyContent::SpaceAround,
-                        align_items: AlignItems::Center,
-                        padding: UiRect::all(Val::Px(5.)),
-                        ..default()
-                    },
-                    BackgroundColor(Color::NONE),
-                    ButtonColors::default(),
-                    OpenLink("https://github.com/NiklasEi/bevy_game_template"),
-                ))
-                .with_children(|parent| {
-                    parent.spawn((
-                        Text::new("Open source"),
-                        TextFont {
-                            font_size: 15.0,
-                            ..default()
-                        },
-                        TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                    ));
-                    parent.spawn((
-                        ImageNode::new(textures.github.clone()),
-                        Node {
-                            width: Val::Px(32.),
-                            ..default()
-                        },
-                    ));
-                });
+            button(children, "Made with Bevy", ButtonStyle::LINK)
+                .insert(OpenLink("https://bevyengine.org"))
+                .with_child(link_icon(textures.bevy.clone()));
+            button(children, "Open source", ButtonStyle::LINK)
+                .insert(OpenLink("https://github.com/NiklasEi/bevy_game_template"))
+                .with_child(link_icon(textures.github.clone()));
         });
 }
 
-fn handle_button_hover(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, &ButtonColors),
-        Changed<Interaction>,
-    >,
+fn link_icon(image: Handle<Image>) -> impl Bundle {
+    (
+        ImageNode::new(image),
+        Node {
+            width: Val::Px(32.),
+            margin: UiRect::left(Val::Px(10.)),
+            ..default()
+        },
+    )
+}
+
+fn handle_main_menu_continue_button(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<AppState>>,
+    type_registry: Res<AppTypeRegistry>,
+    interaction_query: Query<(&Interaction, &MainMenuContinueButton), Changed<Interaction>>,
+    mut status_text_query: Query<&mut Text, With<MainMenuStatusText>>,
 ) {
-    for (interaction, mut color, button_colors) in &mut interaction_query {
-        match *interaction {
-            Interaction::Hovered => {
-                *color = button_colors.hovered.into();
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match read_slot(button.0, &type_registry.read()) {
+            Ok(scene) => {
+                commands.insert_resource(PendingLoad(scene));
+                next_state.set(AppState::InGame);
             }
-            Interaction::None => {
-                *color = button_colors.normal.into();
+            Err(error) => {
+                warn!("Failed to continue from slot {}: {error}", button.0 + 1);
+                for mut text in &mut status_text_query {
+                    text.0 = format!("Could not continue: {error}");
+                }
             }
-            Interaction::Pressed => {}
         }
     }
 }
@@ -254,7 +139,18 @@ fn handle_main_menu_play_button(
 ) {
     for interaction in &interaction_query {
         if *interaction == Interaction::Pressed {
-            next_state.set(AppState::InGame);
+            next_state.set(AppState::LevelSelect);
+        }
+    }
+}
+
+fn handle_main_menu_high_scores_button(
+    mut next_state: ResMut<NextState<AppState>>,
+    interaction_query: Query<&Interaction, (With<MainMenuHighScoresButton>, Changed<Interaction>)>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            next_state.set(AppState::HighScores);
         }
     }
 }
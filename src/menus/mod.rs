@@ -1,9 +1,23 @@
+pub mod focus;
 pub mod game_menu;
+pub mod game_over_menu;
+pub mod high_score_entry;
+pub mod high_scores_menu;
+pub mod level_complete_menu;
+pub mod level_select_menu;
 pub mod main_menu;
+pub mod save_slots_menu;
 pub mod settings;
 pub mod settings_menu;
 pub mod ui_components;
+pub mod widgets;
 
 pub use game_menu::GameMenuPlugin;
+pub use game_over_menu::GameOverMenuPlugin;
+pub use high_scores_menu::HighScoresMenuPlugin;
+pub use level_complete_menu::LevelCompleteMenuPlugin;
+pub use level_select_menu::LevelSelectMenuPlugin;
 pub use main_menu::MainMenuPlugin;
+pub use save_slots_menu::SaveSlotsMenuPlugin;
 pub use settings_menu::SettingsMenuPlugin;
+pub use widgets::WidgetsPlugin;
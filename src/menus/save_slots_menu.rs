@@ -0,0 +1,145 @@
+use crate::menus::widgets::{
+    button, label, menu_container, title, ButtonLabels, ButtonStyle, WidgetLabel,
+};
+use crate::overlay_state::GameOverlayState;
+use crate::save_game::{
+    read_slot, slot_summary, PendingLoad, SaveGameRequest, SaveGameStatus, SAVE_SLOT_COUNT,
+};
+use bevy::prelude::*;
+
+pub struct SaveSlotsMenuPlugin;
+
+/// This plugin is responsible for picking a save slot from the game menu
+/// The same menu is drawn for saving (`GameOverlayState::SaveMenu`) and loading (`GameOverlayState::LoadMenu`)
+impl Plugin for SaveSlotsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameOverlayState::SaveMenu), setup_save_slots_menu)
+            .add_systems(OnEnter(GameOverlayState::LoadMenu), setup_save_slots_menu)
+            .add_systems(
+                Update,
+                (
+                    handle_slot_buttons,
+                    handle_back_button,
+                    update_slot_text.run_if(resource_changed::<SaveGameStatus>),
+                )
+                    .run_if(
+                        in_state(GameOverlayState::SaveMenu)
+                            .or(in_state(GameOverlayState::LoadMenu)),
+                    ),
+            )
+            .add_systems(OnExit(GameOverlayState::SaveMenu), cleanup_save_slots_menu)
+            .add_systems(OnExit(GameOverlayState::LoadMenu), cleanup_save_slots_menu);
+    }
+}
+
+#[derive(Component)]
+struct SaveSlotsMenu;
+
+#[derive(Component)]
+struct SlotButton(usize);
+
+#[derive(Component)]
+struct SaveSlotsStatusText;
+
+#[derive(Component)]
+struct SaveSlotsBackButton;
+
+fn slot_label(slot: usize) -> String {
+    format!("Slot {}: {}", slot + 1, slot_summary(slot).label())
+}
+
+fn setup_save_slots_menu(
+    mut commands: Commands,
+    overlay_state: Res<State<GameOverlayState>>,
+    mut status: ResMut<SaveGameStatus>,
+) {
+    status.0 = None;
+    let heading = if *overlay_state.get() == GameOverlayState::SaveMenu {
+        "Save Game"
+    } else {
+        "Load Game"
+    };
+
+    commands
+        .spawn((menu_container(), SaveSlotsMenu))
+        .with_children(|children| {
+            title(children, heading);
+
+            // Slot Buttons
+            for slot in 0..SAVE_SLOT_COUNT {
+                let style = ButtonStyle {
+                    font_size: 32.0,
+                    ..ButtonStyle::LARGE
+                };
+                button(children, slot_label(slot), style).insert(SlotButton(slot));
+            }
+
+            // Status
+            label(children, "", 20.0).insert(SaveSlotsStatusText);
+
+            // Back Button
+            button(children, "Back", ButtonStyle::LARGE).insert(SaveSlotsBackButton);
+        });
+}
+
+fn handle_slot_buttons(
+    mut commands: Commands,
+    overlay_state: Res<State<GameOverlayState>>,
+    mut next_overlay_state: ResMut<NextState<GameOverlayState>>,
+    mut save_requests: EventWriter<SaveGameRequest>,
+    mut status: ResMut<SaveGameStatus>,
+    type_registry: Res<AppTypeRegistry>,
+    interaction_query: Query<(&Interaction, &SlotButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        if *overlay_state.get() == GameOverlayState::SaveMenu {
+            save_requests.send(SaveGameRequest { slot: button.0 });
+            continue;
+        }
+        match read_slot(button.0, &type_registry.read()) {
+            Ok(scene) => {
+                commands.insert_resource(PendingLoad(scene));
+                next_overlay_state.set(GameOverlayState::None);
+            }
+            Err(error) => {
+                warn!("Failed to load slot {}: {error}", button.0 + 1);
+                status.0 = Some(format!("Could not load slot {}: {error}", button.0 + 1));
+            }
+        }
+    }
+}
+
+fn handle_back_button(
+    mut next_overlay_state: ResMut<NextState<GameOverlayState>>,
+    interaction_query: Query<&Interaction, (With<SaveSlotsBackButton>, Changed<Interaction>)>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            next_overlay_state.set(GameOverlayState::GameMenu);
+        }
+    }
+}
+
+// Saving changes what's in the slots, so their labels are refreshed together with the status
+fn update_slot_text(
+    status: Res<SaveGameStatus>,
+    mut status_text_query: Query<&mut Text, (With<SaveSlotsStatusText>, Without<WidgetLabel>)>,
+    slot_button_query: Query<(Entity, &SlotButton)>,
+    mut labels: ButtonLabels,
+) {
+    for mut text in &mut status_text_query {
+        text.0 = status.0.clone().unwrap_or_default();
+    }
+    for (entity, slot_button) in &slot_button_query {
+        labels.set(entity, &slot_label(slot_button.0));
+    }
+}
+
+fn cleanup_save_slots_menu(mut commands: Commands, menu: Query<Entity, With<SaveSlotsMenu>>) {
+    for entity in menu.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,175 +1,114 @@
-use crate::menus::settings::ui_constants::{
-    SETTINGS_CONTAINER_WIDTH, SETTING_ROW_HEIGHT, SETTING_ROW_MARGIN,
-};
-use crate::menus::ui_components::ButtonColors;
+use crate::audio::AudioSettings;
+use crate::menus::widgets::{scroll_list, setting_row, slider, Slider, WidgetChanged};
+use crate::settings_file::SettingsFile;
 use bevy::prelude::*;
 
+/// How much a single press on one of the volume buttons changes the level, dragging snaps to it too
+const VOLUME_STEP: f32 = 0.1;
+
 pub struct AudioSettingsPlugin;
 
 impl Plugin for AudioSettingsPlugin {
-    fn build(&self, _app: &mut App) {
-        // TODO: We'll add systems here later when we implement the functionality
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                handle_volume_sliders,
+                update_volume_sliders.run_if(resource_changed::<AudioSettings>),
+            ),
+        );
     }
 }
 
 #[derive(Component)]
-pub struct AudioSettings;
+pub struct AudioSettingsUI;
 
-pub fn setup_audio_settings(parent: &mut ChildBuilder) {
-    parent
-        .spawn((
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                flex_direction: FlexDirection::Column,
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::FlexStart,
-                overflow: Overflow::clip_y(),
-                ..default()
-            },
-            AudioSettings,
-        ))
-        .with_children(|parent| {
-            // Scroll container
-            parent
-                .spawn(Node {
-                    width: Val::Percent(100.0),
-                    height: Val::Percent(100.0),
-                    flex_direction: FlexDirection::Column,
-                    align_items: AlignItems::Center,
-                    justify_content: JustifyContent::FlexStart,
-                    overflow: Overflow::clip_y(),
-                    ..default()
-                })
-                .with_children(|parent| {
-                    // Master Volume setting
-                    parent
-                        .spawn(Node {
-                            width: Val::Percent(SETTINGS_CONTAINER_WIDTH),
-                            height: Val::Px(SETTING_ROW_HEIGHT),
-                            flex_direction: FlexDirection::Row,
-                            align_items: AlignItems::Center,
-                            justify_content: JustifyContent::SpaceBetween,
-                            margin: UiRect::all(Val::Px(SETTING_ROW_MARGIN)),
-                            ..default()
-                        })
-                        .with_children(|parent| {
-                            parent.spawn((
-                                Text::new("Master Volume"),
-                                TextFont {
-                                    font_size: 24.0,
-                                    ..default()
-                                },
-                                TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                            ));
-                            parent
-                                .spawn((
-                                    Button,
-                                    Node {
-                                        width: Val::Px(150.0),
-                                        height: Val::Px(40.0),
-                                        justify_content: JustifyContent::Center,
-                                        align_items: AlignItems::Center,
-                                        ..Default::default()
-                                    },
-                                    BackgroundColor(ButtonColors::default().normal),
-                                    ButtonColors::default(),
-                                ))
-                                .with_child((
-                                    Text::new("100%"),
-                                    TextFont {
-                                        font_size: 20.0,
-                                        ..default()
-                                    },
-                                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                                ));
-                        });
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum VolumeBus {
+    Master,
+    Sfx,
+}
+
+impl VolumeBus {
+    fn level(self, audio_settings: &AudioSettings) -> f32 {
+        match self {
+            VolumeBus::Master => audio_settings.master,
+            VolumeBus::Sfx => audio_settings.sfx,
+        }
+    }
 
-                    // Music Volume setting
-                    parent
-                        .spawn(Node {
-                            width: Val::Percent(SETTINGS_CONTAINER_WIDTH),
-                            height: Val::Px(SETTING_ROW_HEIGHT),
-                            flex_direction: FlexDirection::Row,
-                            align_items: AlignItems::Center,
-                            justify_content: JustifyContent::SpaceBetween,
-                            margin: UiRect::all(Val::Px(SETTING_ROW_MARGIN)),
-                            ..default()
-                        })
-                        .with_children(|parent| {
-                            parent.spawn((
-                                Text::new("Music Volume"),
-                                TextFont {
-                                    font_size: 24.0,
-                                    ..default()
-                                },
-                                TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                            ));
-                            parent
-                                .spawn((
-                                    Button,
-                                    Node {
-                                        width: Val::Px(150.0),
-                                        height: Val::Px(40.0),
-                                        justify_content: JustifyContent::Center,
-                                        align_items: AlignItems::Center,
-                                        ..Default::default()
-                                    },
-                                    BackgroundColor(ButtonColors::default().normal),
-                                    ButtonColors::default(),
-                                ))
-                                .with_child((
-                                    Text::new("80%"),
-                                    TextFont {
-                                        font_size: 20.0,
-                                        ..default()
-                                    },
-                                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                                ));
-                        });
+    fn level_mut(self, audio_settings: &mut AudioSettings) -> &mut f32 {
+        match self {
+            VolumeBus::Master => &mut audio_settings.master,
+            VolumeBus::Sfx => &mut audio_settings.sfx,
+        }
+    }
+}
+
+#[derive(Component)]
+struct VolumeSlider(VolumeBus);
+
+fn volume_label(level: f32) -> String {
+    format!("{:.0}%", level * 100.0)
+}
 
-                    // Sound Effects Volume setting
-                    parent
-                        .spawn(Node {
-                            width: Val::Percent(SETTINGS_CONTAINER_WIDTH),
-                            height: Val::Px(SETTING_ROW_HEIGHT),
-                            flex_direction: FlexDirection::Row,
-                            align_items: AlignItems::Center,
-                            justify_content: JustifyContent::SpaceBetween,
-                            margin: UiRect::all(Val::Px(SETTING_ROW_MARGIN)),
-                            ..default()
-                        })
-                        .with_children(|parent| {
-                            parent.spawn((
-                                Text::new("Sound Effects"),
-                                TextFont {
-                                    font_size: 24.0,
-                                    ..default()
-                                },
-                                TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                            ));
-                            parent
-                                .spawn((
-                                    Button,
-                                    Node {
-                                        width: Val::Px(150.0),
-                                        height: Val::Px(40.0),
-                                        justify_content: JustifyContent::Center,
-                                        align_items: AlignItems::Center,
-                                        ..Default::default()
-                                    },
-                                    BackgroundColor(ButtonColors::default().normal),
-                                    ButtonColors::default(),
-                                ))
-                                .with_child((
-                                    Text::new("90%"),
-                                    TextFont {
-                                        font_size: 20.0,
-                                        ..default()
-                                    },
-                                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                                ));
-                        });
-                });
+// The game has no music yet, the music level gets a slider once there is some to hear
+pub fn setup_audio_settings(parent: &mut ChildBuilder, audio_settings: &AudioSettings) {
+    scroll_list(parent, Val::Percent(100.0))
+        .insert(AudioSettingsUI)
+        .with_children(|parent| {
+            spawn_volume_row(parent, "Master Volume", VolumeBus::Master, audio_settings);
+            spawn_volume_row(parent, "Sound Effects", VolumeBus::Sfx, audio_settings);
         });
 }
+
+fn spawn_volume_row(
+    parent: &mut ChildBuilder,
+    label: &str,
+    bus: VolumeBus,
+    audio_settings: &AudioSettings,
+) {
+    setting_row(parent, label, |parent| {
+        slider(
+            parent,
+            Slider {
+                value: bus.level(audio_settings),
+                min: 0.0,
+                max: 1.0,
+                step: VOLUME_STEP,
+                format: volume_label,
+            },
+        )
+        .insert(VolumeSlider(bus));
+    });
+}
+
+fn handle_volume_sliders(
+    mut changed_events: EventReader<WidgetChanged>,
+    slider_query: Query<(&Slider, &VolumeSlider)>,
+    mut audio_settings: ResMut<AudioSettings>,
+    mut settings_file: ResMut<SettingsFile>,
+) {
+    for event in changed_events.read() {
+        let Ok((slider, VolumeSlider(bus))) = slider_query.get(event.entity) else {
+            continue;
+        };
+        *bus.level_mut(&mut audio_settings) = slider.value;
+
+        // Volume changes apply immediately, so there is no apply button to wait for
+        settings_file.audio = audio_settings.clone();
+        settings_file.save();
+    }
+}
+
+fn update_volume_sliders(
+    audio_settings: Res<AudioSettings>,
+    mut slider_query: Query<(&mut Slider, &VolumeSlider)>,
+) {
+    for (mut slider, VolumeSlider(bus)) in &mut slider_query {
+        let level = bus.level(&audio_settings);
+        if slider.value != level {
+            slider.value = level;
+        }
+    }
+}
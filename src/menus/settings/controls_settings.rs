@@ -1,175 +1,298 @@
-use crate::menus::settings::ui_constants::{
-    SETTINGS_CONTAINER_WIDTH, SETTING_ROW_HEIGHT, SETTING_ROW_MARGIN,
+use crate::actions::{
+    key_label, ActionSystem, BindingSlot, GameControl, KeyBindings, TouchControlSettings,
+    TouchMovement,
 };
-use crate::menus::ui_components::ButtonColors;
+use crate::menus::focus::FocusSystem;
+use crate::menus::settings::ui_constants::SETTING_ROW_MARGIN;
+use crate::menus::ui_components::BUTTON_WIDTH;
+use crate::menus::widgets::{
+    button, compact_setting_row, cycle_selector, scroll_list, ButtonLabels, ButtonStyle,
+    CycleSelector, WidgetChanged, WidgetLabel,
+};
+use crate::overlay_state::GameOverlayState;
+use crate::settings_file::SettingsFile;
+use crate::AppState;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::{ButtonState, InputSystem};
 use bevy::prelude::*;
 
 pub struct ControlsSettingsPlugin;
 
 impl Plugin for ControlsSettingsPlugin {
-    fn build(&self, _app: &mut App) {
-        // TODO: We'll add systems here later when we implement the functionality
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BindingCapture>()
+            // Runs right after the input is collected, so a captured key never reaches the gameplay or menu systems
+            .add_systems(
+                PreUpdate,
+                (
+                    hide_captured_key.run_if(|capture: Res<BindingCapture>| capture.held.is_some()),
+                    capture_binding.run_if(|capture: Res<BindingCapture>| capture.target.is_some()),
+                )
+                    .chain()
+                    .after(InputSystem)
+                    .before(ActionSystem)
+                    .before(FocusSystem),
+            )
+            .add_systems(
+                Update,
+                (
+                    handle_binding_buttons,
+                    handle_reset_bindings_button,
+                    handle_touch_movement_selector,
+                    update_binding_text.run_if(
+                        resource_changed::<KeyBindings>.or(resource_changed::<BindingCapture>),
+                    ),
+                ),
+            )
+            .add_systems(OnExit(AppState::SettingsMenu), cancel_binding_capture)
+            .add_systems(
+                OnExit(GameOverlayState::SettingsMenu),
+                cancel_binding_capture,
+            );
     }
 }
 
 #[derive(Component)]
 pub struct ControlsSettings;
 
-pub fn setup_controls_settings(parent: &mut ChildBuilder) {
-    parent
-        .spawn((
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                flex_direction: FlexDirection::Column,
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::FlexStart,
-                overflow: Overflow::clip_y(),
-                ..default()
-            },
-            ControlsSettings,
-        ))
+/// The binding that is waiting for a key press, if any
+#[derive(Resource, Default)]
+struct BindingCapture {
+    target: Option<(GameControl, BindingSlot)>,
+    message: Option<String>,
+    /// The captured key until it's released, so its key repeats don't count as new presses
+    held: Option<KeyCode>,
+}
+
+#[derive(Component)]
+struct BindingButton {
+    control: GameControl,
+    slot: BindingSlot,
+}
+
+#[derive(Component)]
+struct BindingStatusText;
+
+#[derive(Component)]
+struct ResetBindingsButton;
+
+#[derive(Component)]
+struct TouchMovementSelector;
+
+const BINDING_HINT: &str = "Click a key to change it";
+
+fn binding_label(key_bindings: &KeyBindings, control: GameControl, slot: BindingSlot) -> String {
+    key_bindings
+        .get(control)
+        .get(slot)
+        .map(key_label)
+        .unwrap_or_else(|| "-".to_string())
+}
+
+pub fn setup_controls_settings(
+    parent: &mut ChildBuilder,
+    key_bindings: &KeyBindings,
+    touch_settings: &TouchControlSettings,
+) {
+    scroll_list(parent, Val::Percent(100.0))
+        .insert(ControlsSettings)
         .with_children(|parent| {
-            // Scroll container
-            parent
-                .spawn(Node {
-                    width: Val::Percent(100.0),
-                    height: Val::Percent(100.0),
-                    flex_direction: FlexDirection::Column,
-                    align_items: AlignItems::Center,
-                    justify_content: JustifyContent::FlexStart,
-                    overflow: Overflow::clip_y(),
+            spawn_touch_movement_row(parent, touch_settings);
+
+            for control in GameControl::ALL {
+                spawn_binding_row(parent, control, key_bindings);
+            }
+
+            // Capture instructions and conflict messages
+            parent.spawn((
+                Text::new(BINDING_HINT),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::linear_rgb(0.7, 0.7, 0.7)),
+                Node {
+                    margin: UiRect::all(Val::Px(SETTING_ROW_MARGIN)),
                     ..default()
-                })
-                .with_children(|parent| {
-                    // Movement Controls
-                    parent
-                        .spawn(Node {
-                            width: Val::Percent(SETTINGS_CONTAINER_WIDTH),
-                            height: Val::Px(SETTING_ROW_HEIGHT),
-                            flex_direction: FlexDirection::Row,
-                            align_items: AlignItems::Center,
-                            justify_content: JustifyContent::SpaceBetween,
-                            margin: UiRect::all(Val::Px(SETTING_ROW_MARGIN)),
-                            ..default()
-                        })
-                        .with_children(|parent| {
-                            parent.spawn((
-                                Text::new("Movement"),
-                                TextFont {
-                                    font_size: 24.0,
-                                    ..default()
-                                },
-                                TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                            ));
-                            parent
-                                .spawn((
-                                    Button,
-                                    Node {
-                                        width: Val::Px(150.0),
-                                        height: Val::Px(40.0),
-                                        justify_content: JustifyContent::Center,
-                                        align_items: AlignItems::Center,
-                                        ..Default::default()
-                                    },
-                                    BackgroundColor(ButtonColors::default().normal),
-                                    ButtonColors::default(),
-                                ))
-                                .with_child((
-                                    Text::new("WASD"),
-                                    TextFont {
-                                        font_size: 20.0,
-                                        ..default()
-                                    },
-                                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                                ));
-                        });
-
-                    // Jump Control
-                    parent
-                        .spawn(Node {
-                            width: Val::Percent(SETTINGS_CONTAINER_WIDTH),
-                            height: Val::Px(SETTING_ROW_HEIGHT),
-                            flex_direction: FlexDirection::Row,
-                            align_items: AlignItems::Center,
-                            justify_content: JustifyContent::SpaceBetween,
-                            margin: UiRect::all(Val::Px(SETTING_ROW_MARGIN)),
-                            ..default()
-                        })
-                        .with_children(|parent| {
-                            parent.spawn((
-                                Text::new("Jump"),
-                                TextFont {
-                                    font_size: 24.0,
-                                    ..default()
-                                },
-                                TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                            ));
-                            parent
-                                .spawn((
-                                    Button,
-                                    Node {
-                                        width: Val::Px(150.0),
-                                        height: Val::Px(40.0),
-                                        justify_content: JustifyContent::Center,
-                                        align_items: AlignItems::Center,
-                                        ..Default::default()
-                                    },
-                                    BackgroundColor(ButtonColors::default().normal),
-                                    ButtonColors::default(),
-                                ))
-                                .with_child((
-                                    Text::new("Space"),
-                                    TextFont {
-                                        font_size: 20.0,
-                                        ..default()
-                                    },
-                                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                                ));
-                        });
-
-                    // Interact Control
-                    parent
-                        .spawn(Node {
-                            width: Val::Percent(SETTINGS_CONTAINER_WIDTH),
-                            height: Val::Px(SETTING_ROW_HEIGHT),
-                            flex_direction: FlexDirection::Row,
-                            align_items: AlignItems::Center,
-                            justify_content: JustifyContent::SpaceBetween,
-                            margin: UiRect::all(Val::Px(SETTING_ROW_MARGIN)),
-                            ..default()
-                        })
-                        .with_children(|parent| {
-                            parent.spawn((
-                                Text::new("Interact"),
-                                TextFont {
-                                    font_size: 24.0,
-                                    ..default()
-                                },
-                                TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                            ));
-                            parent
-                                .spawn((
-                                    Button,
-                                    Node {
-                                        width: Val::Px(150.0),
-                                        height: Val::Px(40.0),
-                                        justify_content: JustifyContent::Center,
-                                        align_items: AlignItems::Center,
-                                        ..Default::default()
-                                    },
-                                    BackgroundColor(ButtonColors::default().normal),
-                                    ButtonColors::default(),
-                                ))
-                                .with_child((
-                                    Text::new("E"),
-                                    TextFont {
-                                        font_size: 20.0,
-                                        ..default()
-                                    },
-                                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                                ));
-                        });
-                });
+                },
+                BindingStatusText,
+            ));
+
+            button(
+                parent,
+                "Reset to Defaults",
+                ButtonStyle::MEDIUM.with_width(BUTTON_WIDTH * 1.5),
+            )
+            .insert(ResetBindingsButton);
         });
 }
+
+// Six controls don't fit with the regular row height, so these rows are only as high as their buttons
+fn spawn_binding_row(parent: &mut ChildBuilder, control: GameControl, key_bindings: &KeyBindings) {
+    compact_setting_row(parent, control.label(), |parent| {
+        for slot in [BindingSlot::Primary, BindingSlot::Secondary] {
+            button(
+                parent,
+                binding_label(key_bindings, control, slot),
+                ButtonStyle::SMALL,
+            )
+            .insert(BindingButton { control, slot });
+        }
+    });
+}
+
+fn spawn_touch_movement_row(parent: &mut ChildBuilder, touch_settings: &TouchControlSettings) {
+    let options = TouchMovement::ALL
+        .iter()
+        .map(|movement| movement.label().to_string())
+        .collect();
+    let selected = TouchMovement::ALL
+        .iter()
+        .position(|movement| *movement == touch_settings.movement)
+        .unwrap_or(0);
+    compact_setting_row(parent, "Touch Movement", |parent| {
+        // As wide as both binding buttons
+        cycle_selector(
+            parent,
+            CycleSelector::new(options, selected),
+            ButtonStyle::SMALL.with_width(BUTTON_WIDTH * 2.0 + SETTING_ROW_MARGIN),
+        )
+        .insert(TouchMovementSelector);
+    });
+}
+
+// Takes effect and is saved right away, like key bindings
+fn handle_touch_movement_selector(
+    mut changed_events: EventReader<WidgetChanged>,
+    selector_query: Query<&CycleSelector, With<TouchMovementSelector>>,
+    mut touch_settings: ResMut<TouchControlSettings>,
+    mut settings_file: ResMut<SettingsFile>,
+) {
+    for event in changed_events.read() {
+        if let Ok(selector) = selector_query.get(event.entity) {
+            touch_settings.movement = TouchMovement::ALL[selector.selected];
+            settings_file.touch = touch_settings.clone();
+            settings_file.save();
+        }
+    }
+}
+
+fn handle_binding_buttons(
+    interaction_query: Query<(&Interaction, &BindingButton), Changed<Interaction>>,
+    mut capture: ResMut<BindingCapture>,
+) {
+    for (interaction, button) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            capture.target = Some((button.control, button.slot));
+            capture.message = Some(format!(
+                "Press a key for {} (Esc to cancel, Delete to unbind)",
+                button.control.label()
+            ));
+        }
+    }
+}
+
+fn capture_binding(
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut capture: ResMut<BindingCapture>,
+    mut key_bindings: ResMut<KeyBindings>,
+    mut settings_file: ResMut<SettingsFile>,
+) {
+    let Some(key) = keyboard_input.get_just_pressed().next().copied() else {
+        return;
+    };
+    let Some((control, slot)) = capture.target.take() else {
+        return;
+    };
+    // Consume the key, otherwise e.g. Escape would also close the menu
+    keyboard_input.reset(key);
+    capture.held = Some(key);
+
+    capture.message = match key {
+        KeyCode::Escape => None,
+        KeyCode::Delete | KeyCode::Backspace => {
+            key_bindings.get_mut(control).set(slot, None);
+            None
+        }
+        key => key_bindings
+            .bind(control, slot, key)
+            .map(|(conflicting_control, _)| {
+                format!(
+                    "{} was unbound from {}",
+                    key_label(key),
+                    conflicting_control.label()
+                )
+            }),
+    };
+
+    if settings_file.controls != *key_bindings {
+        settings_file.controls = key_bindings.clone();
+        settings_file.save();
+    }
+}
+
+// A reset key isn't pressed anymore, so the keyboard's repeats of it would be new presses
+fn hide_captured_key(
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut capture: ResMut<BindingCapture>,
+) {
+    let Some(key) = capture.held else {
+        return;
+    };
+    let released = keyboard_events
+        .read()
+        .filter(|event| event.key_code == key)
+        .last()
+        .is_some_and(|event| event.state == ButtonState::Released);
+    if released {
+        capture.held = None;
+    } else {
+        keyboard_input.reset(key);
+    }
+}
+
+fn handle_reset_bindings_button(
+    interaction_query: Query<&Interaction, (With<ResetBindingsButton>, Changed<Interaction>)>,
+    mut capture: ResMut<BindingCapture>,
+    mut key_bindings: ResMut<KeyBindings>,
+    mut settings_file: ResMut<SettingsFile>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            *capture = BindingCapture::default();
+            *key_bindings = KeyBindings::default();
+            settings_file.controls = KeyBindings::default();
+            settings_file.save();
+        }
+    }
+}
+
+fn update_binding_text(
+    key_bindings: Res<KeyBindings>,
+    capture: Res<BindingCapture>,
+    button_query: Query<(Entity, &BindingButton)>,
+    mut labels: ButtonLabels,
+    mut status_query: Query<&mut Text, (With<BindingStatusText>, Without<WidgetLabel>)>,
+) {
+    for (entity, button) in &button_query {
+        let label = if capture.target == Some((button.control, button.slot)) {
+            "...".to_string()
+        } else {
+            binding_label(&key_bindings, button.control, button.slot)
+        };
+        labels.set(entity, &label);
+    }
+    for mut text in &mut status_query {
+        text.0 = capture
+            .message
+            .clone()
+            .unwrap_or_else(|| BINDING_HINT.to_string());
+    }
+}
+
+// The captured key stays hidden until it's released, even once the menu is gone
+fn cancel_binding_capture(mut capture: ResMut<BindingCapture>) {
+    capture.target = None;
+    capture.message = None;
+}
@@ -0,0 +1,207 @@
+use crate::menus::focus::FocusedEntity;
+use crate::menus::settings::display_settings::{
+    DisplaySettingsChanged, DisplayTarget, NewDisplaySettings,
+};
+use crate::menus::widgets::{button, ButtonStyle};
+use crate::settings_file::SettingsFile;
+use bevy::prelude::*;
+use bevy::ui::FocusPolicy;
+use std::time::Duration;
+
+/// How long the player has to confirm new display settings before the previous ones come back
+pub const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(15);
+
+pub struct DisplayConfirmationPlugin;
+
+/// This plugin asks the player to keep display settings after they were applied
+/// If the player doesn't answer in time, e.g. because the monitor can't show the new mode,
+/// the previous settings are restored
+impl Plugin for DisplayConfirmationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                setup_confirmation_dialog.run_if(resource_added::<DisplayConfirmation>),
+                (
+                    handle_keep_button,
+                    handle_revert_button,
+                    count_down_confirmation,
+                )
+                    .chain()
+                    .distributive_run_if(resource_exists::<DisplayConfirmation>),
+                cleanup_confirmation_dialog.run_if(resource_removed::<DisplayConfirmation>),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Exists while the player is asked whether to keep the applied display settings
+#[derive(Resource)]
+pub struct DisplayConfirmation {
+    /// The settings that were applied before, restored if the new ones aren't kept
+    pub previous: NewDisplaySettings,
+    pub applied: NewDisplaySettings,
+    /// Runs on real time, since the game may be paused
+    pub timer: Timer,
+}
+
+impl DisplayConfirmation {
+    pub fn new(previous: NewDisplaySettings, applied: NewDisplaySettings) -> Self {
+        Self {
+            previous,
+            applied,
+            timer: Timer::new(CONFIRMATION_TIMEOUT, TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Component)]
+struct ConfirmationDialog;
+
+#[derive(Component)]
+struct CountdownText;
+
+#[derive(Component)]
+struct KeepButton;
+
+#[derive(Component)]
+struct RevertButton;
+
+fn countdown_label(remaining: Duration) -> String {
+    format!("Reverting in {} seconds", remaining.as_secs_f32().ceil())
+}
+
+fn setup_confirmation_dialog(
+    mut commands: Commands,
+    confirmation: Res<DisplayConfirmation>,
+    mut focused: ResMut<FocusedEntity>,
+) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            // Drawn above the settings menu and keeps the mouse away from it
+            GlobalZIndex(1),
+            FocusPolicy::Block,
+            BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+            ConfirmationDialog,
+        ))
+        .with_children(|children| {
+            // Title
+            children.spawn((
+                Text::new("Keep these settings?"),
+                TextFont {
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
+                Node {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                },
+            ));
+
+            children.spawn((
+                Text::new(countdown_label(confirmation.timer.remaining())),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
+                Node {
+                    margin: UiRect::bottom(Val::Px(30.0)),
+                    ..default()
+                },
+                CountdownText,
+            ));
+
+            // Buttons
+            children
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(20.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    let keep_button = button(parent, "Keep", ButtonStyle::MEDIUM)
+                        .insert(KeepButton)
+                        .id();
+                    // If the new mode works, keeping it is only a key press away
+                    focused.0 = Some(keep_button);
+
+                    button(parent, "Revert", ButtonStyle::MEDIUM).insert(RevertButton);
+                });
+        });
+}
+
+fn handle_keep_button(
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, (With<KeepButton>, Changed<Interaction>)>,
+    confirmation: Res<DisplayConfirmation>,
+    mut settings_file: ResMut<SettingsFile>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            // Remember the applied settings for the next launch
+            settings_file.display = confirmation.applied.clone();
+            settings_file.save();
+            commands.remove_resource::<DisplayConfirmation>();
+        }
+    }
+}
+
+fn handle_revert_button(
+    interaction_query: Query<&Interaction, (With<RevertButton>, Changed<Interaction>)>,
+    mut confirmation: ResMut<DisplayConfirmation>,
+) {
+    for interaction in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            // Reverted by `count_down_confirmation` in the same frame
+            let remaining = confirmation.timer.remaining();
+            confirmation.timer.tick(remaining);
+        }
+    }
+}
+
+fn count_down_confirmation(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut confirmation: ResMut<DisplayConfirmation>,
+    mut text_query: Query<&mut Text, With<CountdownText>>,
+    mut display_target: DisplayTarget,
+    mut display_settings: ResMut<NewDisplaySettings>,
+    mut event_writer: EventWriter<DisplaySettingsChanged>,
+) {
+    confirmation.timer.tick(time.delta());
+    if !confirmation.timer.finished() {
+        let label = countdown_label(confirmation.timer.remaining());
+        for mut text in &mut text_query {
+            if text.0 != label {
+                text.0 = label.clone();
+            }
+        }
+        return;
+    }
+
+    *display_settings = confirmation.previous.clone();
+    display_target.apply(&display_settings);
+    event_writer.send(DisplaySettingsChanged);
+    commands.remove_resource::<DisplayConfirmation>();
+}
+
+fn cleanup_confirmation_dialog(
+    mut commands: Commands,
+    dialog: Query<Entity, With<ConfirmationDialog>>,
+) {
+    for entity in dialog.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
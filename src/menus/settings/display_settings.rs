@@ -1,67 +1,150 @@
-use crate::camera::GameRenderResolution;
-use crate::menus::settings::ui_constants::{
-    BOTTOM_BUTTONS_MARGIN, SETTINGS_CONTAINER_HEIGHT, SETTINGS_CONTAINER_WIDTH, SETTING_ROW_HEIGHT,
-    SETTING_ROW_MARGIN,
+use crate::camera::{GameRenderResolution, RenderScaling};
+use crate::menus::settings::display_confirmation::{
+    DisplayConfirmation, DisplayConfirmationPlugin,
 };
-use crate::menus::ui_components::{
-    ButtonColors, BOTTOM_BUTTON_HEIGHT, BUTTON_HEIGHT, BUTTON_WIDTH,
+use crate::menus::settings::monitors::{
+    refresh_rate_label, MonitorEntry, Monitors, MonitorsPlugin, PendingVideoMode, VideoModeRequest,
+    FALLBACK_RESOLUTIONS,
+};
+use crate::menus::settings::ui_constants::{BOTTOM_BUTTONS_MARGIN, SETTINGS_CONTAINER_HEIGHT};
+use crate::menus::ui_components::BOTTOM_BUTTON_HEIGHT;
+use crate::menus::widgets::{
+    button, cycle_selector, dropdown, scroll_list, setting_row, toggle, ButtonStyle, CycleSelector,
+    Dropdown, Toggle, WidgetChanged,
 };
 use crate::overlay_state::GameOverlayState;
+use crate::settings_file::SettingsFile;
 use crate::AppState;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
-use bevy::window::{MonitorSelection, PresentMode, WindowMode, WindowPosition, WindowResolution};
+use bevy::window::{
+    Monitor, MonitorSelection, PresentMode, PrimaryWindow, WindowMode, WindowPosition,
+    WindowResized, WindowResolution,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// A window size dragged by the player is only saved once it stopped changing for this long
+const RESIZE_SAVE_DELAY: Duration = Duration::from_millis(500);
 
 #[derive(Event)]
 pub struct DisplaySettingsChanged;
 
-#[derive(Resource)]
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
 pub struct NewDisplaySettings {
     pub resolution: (u32, u32),
     pub window_mode: WindowModeType,
     pub vsync: bool,
+    /// Label of the monitor used in the fullscreen modes, the primary one if `None`
+    pub monitor: Option<String>,
+    /// Only used in exclusive fullscreen, `None` picks the highest one for the resolution
+    pub refresh_rate_millihertz: Option<u32>,
+    pub scaling: RenderScaling,
+    /// Size of the menus and the HUD, one of [`UI_SCALES`]
+    pub ui_scale: f32,
+}
+
+/// The UI scales offered in the display settings
+pub const UI_SCALES: [f32; 4] = [0.75, 1.0, 1.25, 1.5];
+
+fn ui_scale_label(ui_scale: f32) -> String {
+    format!("{}%", (ui_scale * 100.0).round())
 }
 
-impl FromWorld for NewDisplaySettings {
-    fn from_world(world: &mut World) -> Self {
-        let window = world.query::<&Window>().single(world);
-        Self::new(window)
+// Matches Bevy's default window, so a first launch without a settings file looks the same as before
+// The render resolution isn't taken from it until settings were stored, see `initialize_display_settings`
+impl Default for NewDisplaySettings {
+    fn default() -> Self {
+        Self {
+            resolution: (1280, 720),
+            window_mode: WindowModeType::Windowed,
+            vsync: true,
+            monitor: None,
+            refresh_rate_millihertz: None,
+            scaling: RenderScaling::default(),
+            ui_scale: 1.0,
+        }
     }
 }
 
 impl NewDisplaySettings {
-    fn new(window: &Window) -> Self {
-        let current_res = (
-            window.resolution.width() as u32,
-            window.resolution.height() as u32,
-        );
-
-        let current_mode = match window.mode {
-            WindowMode::Windowed => WindowModeType::Windowed,
-            WindowMode::Fullscreen { .. } => WindowModeType::Fullscreen,
-            WindowMode::BorderlessFullscreen { .. } => WindowModeType::BorderlessFullscreen,
-            _ => WindowModeType::Windowed,
+    /// Writes mode, resolution and present mode to the given window
+    /// Also used to configure the primary window before it is created
+    pub fn apply_to_window(&self, window: &mut Window, monitor: MonitorSelection) {
+        // In borderless fullscreen, keep the window at native resolution
+        let (width, height) = match self.window_mode {
+            WindowModeType::Windowed | WindowModeType::Fullscreen => self.resolution,
+            WindowModeType::BorderlessFullscreen => (
+                window.resolution.physical_width(),
+                window.resolution.physical_height(),
+            ),
         };
 
-        let current_vsync = window.present_mode == PresentMode::AutoVsync;
+        // Apply window mode first
+        match self.window_mode {
+            WindowModeType::Windowed => {
+                window.mode = WindowMode::Windowed;
+                window.position = WindowPosition::Automatic;
+            }
+            WindowModeType::Fullscreen => {
+                // Picks the video mode with exactly the window's resolution
+                window.mode = WindowMode::SizedFullscreen(monitor);
+            }
+            WindowModeType::BorderlessFullscreen => {
+                window.mode = WindowMode::BorderlessFullscreen(monitor);
+            }
+        }
+
+        // Then set the window resolution
+        window.resolution = WindowResolution::new(width as f32, height as f32);
 
-        Self {
-            resolution: match current_res {
-                (800, 600)
-                | (1280, 720)
-                | (1366, 768)
-                | (1600, 900)
-                | (1920, 1080)
-                | (2560, 1440)
-                | (3840, 2160) => current_res,
-                _ => (1920, 1080),
-            },
-            window_mode: current_mode,
-            vsync: current_vsync,
+        // Apply other settings
+        window.present_mode = if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        };
+    }
+}
+
+/// Everything applying display settings writes to
+#[derive(SystemParam)]
+pub struct DisplayTarget<'w, 's> {
+    window: Query<'w, 's, &'static mut Window, With<PrimaryWindow>>,
+    monitors: Monitors<'w, 's>,
+    pending_video_mode: ResMut<'w, PendingVideoMode>,
+    render_resolution: Option<ResMut<'w, GameRenderResolution>>,
+    ui_scale: ResMut<'w, UiScale>,
+}
+
+impl DisplayTarget<'_, '_> {
+    /// Applies the settings to the window on the chosen monitor
+    /// If a refresh rate was chosen, the exact video mode is set by [`MonitorsPlugin`] in the next frame
+    pub fn apply(&mut self, display_settings: &NewDisplaySettings) {
+        let Ok(mut window) = self.window.get_single_mut() else {
+            return;
+        };
+        if let Some(render_resolution) = self.render_resolution.as_mut() {
+            render_resolution.width = display_settings.resolution.0 as f32;
+            render_resolution.height = display_settings.resolution.1 as f32;
+            render_resolution.scaling = display_settings.scaling;
         }
+        self.ui_scale.0 = display_settings.ui_scale;
+
+        let monitor = self.monitors.find(display_settings.monitor.as_deref());
+        let selection = monitor
+            .as_ref()
+            .map_or(MonitorSelection::Current, |monitor| {
+                MonitorSelection::Entity(monitor.entity)
+            });
+        display_settings.apply_to_window(&mut window, selection);
+        self.pending_video_mode.0 =
+            monitor.and_then(|monitor| VideoModeRequest::from_settings(display_settings, &monitor));
     }
 }
 
-#[derive(Default, Clone, Copy, PartialEq, Debug)]
+#[derive(Default, Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub enum WindowModeType {
     #[default]
     Fullscreen,
@@ -69,23 +152,57 @@ pub enum WindowModeType {
     Windowed,
 }
 
+impl WindowModeType {
+    /// In the order they are cycled through in the settings
+    pub const ALL: [WindowModeType; 3] = [
+        WindowModeType::Windowed,
+        WindowModeType::Fullscreen,
+        WindowModeType::BorderlessFullscreen,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WindowModeType::Windowed => "Windowed",
+            WindowModeType::Fullscreen => "Fullscreen",
+            WindowModeType::BorderlessFullscreen => "Borderless",
+        }
+    }
+}
+
 pub struct DisplaySettingsPlugin;
 
 impl Plugin for DisplaySettingsPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<NewDisplaySettings>()
+        app.add_plugins((MonitorsPlugin, DisplayConfirmationPlugin))
+            .init_resource::<NewDisplaySettings>()
+            // Normally added by the `UiPlugin`, the settings are kept without it too
+            .init_resource::<UiScale>()
             .add_event::<DisplaySettingsChanged>()
             .add_systems(Startup, initialize_display_settings)
             .add_systems(
                 Update,
                 (
+                    // Monitors are only known once the app runs, the window was created on the primary one
+                    restore_display_settings.run_if(any_with_component::<Monitor>.and(run_once)),
+                    // The size changes while new settings are tried out, which doesn't count as dragging
+                    remember_window_size.run_if(not(resource_exists::<DisplayConfirmation>)),
+                ),
+            )
+            .add_systems(
+                Update,
+                (
+                    handle_monitor_button,
                     handle_resolution_button,
+                    handle_refresh_rate_button,
                     handle_fullscreen_button,
                     handle_vsync_button,
+                    handle_scaling_button,
+                    handle_ui_scale_button,
                     handle_apply_button,
                     handle_display_settings_back_button,
-                    update_display_settings_text,
-                ),
+                    update_display_settings_widgets,
+                )
+                    .chain(),
             )
             .add_systems(OnEnter(AppState::SettingsMenu), load_display_settings)
             .add_systems(
@@ -95,36 +212,91 @@ impl Plugin for DisplaySettingsPlugin {
     }
 }
 
-fn initialize_display_settings(mut commands: Commands, window: Query<&Window>) {
-    let window = window.single();
-    commands.insert_resource(NewDisplaySettings::new(window));
+fn initialize_display_settings(
+    mut commands: Commands,
+    settings_file: Res<SettingsFile>,
+    render_resolution: Option<ResMut<GameRenderResolution>>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    commands.insert_resource(settings_file.display.clone());
+    ui_scale.0 = settings_file.display.ui_scale;
+
+    // The window itself was already configured from the settings file before it was created
+    // On a first launch the render resolution keeps its own default rather than the default window size
+    if let Some(mut render_resolution) = render_resolution.filter(|_| settings_file.loaded) {
+        render_resolution.width = settings_file.display.resolution.0 as f32;
+        render_resolution.height = settings_file.display.resolution.1 as f32;
+        render_resolution.scaling = settings_file.display.scaling;
+    }
 }
 
-fn load_display_settings(
+fn restore_display_settings(settings_file: Res<SettingsFile>, mut display_target: DisplayTarget) {
+    let display = &settings_file.display;
+    if display.monitor.is_none() && display.refresh_rate_millihertz.is_none() {
+        return;
+    }
+    display_target.apply(display);
+}
+
+// Keeps the size the player dragged the window to for the next launch
+fn remember_window_size(
+    mut resize_events: EventReader<WindowResized>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    mut settings_file: ResMut<SettingsFile>,
     mut display_settings: ResMut<NewDisplaySettings>,
-    window: Query<&Window>,
     mut event_writer: EventWriter<DisplaySettingsChanged>,
+    time: Res<Time<Real>>,
+    mut save_at: Local<Option<Duration>>,
 ) {
-    let window = window.single();
-    let new_settings = NewDisplaySettings::new(window);
+    for event in resize_events.read() {
+        let Ok(window) = window.get(event.window) else {
+            continue;
+        };
+        if window.mode != WindowMode::Windowed
+            || settings_file.display.window_mode != WindowModeType::Windowed
+        {
+            continue;
+        }
+        let size = (event.width.round() as u32, event.height.round() as u32);
+        if size == settings_file.display.resolution {
+            continue;
+        }
+        // Don't overwrite a resolution that was picked in the menu but not applied yet
+        if display_settings.resolution == settings_file.display.resolution {
+            display_settings.resolution = size;
+            event_writer.send(DisplaySettingsChanged);
+        }
+        settings_file.display.resolution = size;
+        *save_at = Some(time.elapsed() + RESIZE_SAVE_DELAY);
+    }
 
-    // Update settings from window
-    *display_settings = new_settings;
+    if save_at.is_some_and(|save_at| time.elapsed() >= save_at) {
+        *save_at = None;
+        settings_file.save();
+    }
+}
+
+fn load_display_settings(
+    mut display_settings: ResMut<NewDisplaySettings>,
+    settings_file: Res<SettingsFile>,
+    mut event_writer: EventWriter<DisplaySettingsChanged>,
+) {
+    // Discard any changes that weren't applied the last time the menu was open
+    *display_settings = settings_file.display.clone();
 
     // Trigger UI update
     event_writer.send(DisplaySettingsChanged);
 }
 
-#[derive(Component)]
-struct ResolutionText;
-
-#[derive(Component)]
-struct FullscreenText;
-
-#[derive(Component)]
-struct VSyncText;
-
 pub fn setup_display_settings(parent: &mut ChildBuilder, display_settings: &NewDisplaySettings) {
+    // Options depending on the monitors are filled in by `update_display_settings_widgets`
+    let monitor = display_settings
+        .monitor
+        .clone()
+        .unwrap_or_else(|| "Primary".to_string());
+    let resolution = resolution_label(display_settings.resolution);
+    let refresh_rate = refresh_rate_label(display_settings.refresh_rate_millihertz);
+
     parent
         .spawn((
             Node {
@@ -133,231 +305,209 @@ pub fn setup_display_settings(parent: &mut ChildBuilder, display_settings: &NewD
                 flex_direction: FlexDirection::Column,
                 align_items: AlignItems::Center,
                 justify_content: JustifyContent::SpaceBetween,
-                overflow: Overflow::clip_y(),
                 ..default()
             },
             DisplaySettingsUI,
         ))
         .with_children(|parent| {
-            // Settings container
-            parent
-                .spawn(Node {
-                    width: Val::Percent(100.0),
-                    height: Val::Percent(SETTINGS_CONTAINER_HEIGHT),
-                    flex_direction: FlexDirection::Column,
-                    align_items: AlignItems::Center,
-                    justify_content: JustifyContent::FlexStart,
-                    overflow: Overflow::clip_y(),
-                    ..default()
-                })
-                .with_children(|parent| {
-                    // Resolution setting
-                    parent
-                        .spawn(Node {
-                            width: Val::Percent(SETTINGS_CONTAINER_WIDTH),
-                            height: Val::Px(SETTING_ROW_HEIGHT),
-                            flex_direction: FlexDirection::Row,
-                            align_items: AlignItems::Center,
-                            justify_content: JustifyContent::SpaceBetween,
-                            margin: UiRect::all(Val::Px(SETTING_ROW_MARGIN)),
-                            ..default()
-                        })
-                        .with_children(|parent| {
-                            // Label
-                            parent.spawn((
-                                Text::new("Resolution"),
-                                TextFont {
-                                    font_size: 24.0,
-                                    ..default()
-                                },
-                                TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                            ));
-
-                            // Button
-                            parent
-                                .spawn((
-                                    Button,
-                                    Node {
-                                        width: Val::Px(BUTTON_WIDTH),
-                                        height: Val::Px(BUTTON_HEIGHT),
-                                        justify_content: JustifyContent::Center,
-                                        align_items: AlignItems::Center,
-                                        ..Default::default()
-                                    },
-                                    BackgroundColor(ButtonColors::default().normal),
-                                    ButtonColors::default(),
-                                    ResolutionButton,
-                                ))
-                                .with_child((
-                                    Text::new(format!(
-                                        "{}x{}",
-                                        display_settings.resolution.0,
-                                        display_settings.resolution.1
-                                    )),
-                                    TextFont {
-                                        font_size: 20.0,
-                                        ..default()
-                                    },
-                                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                                    ResolutionText,
-                                ));
-                        });
-
-                    // Fullscreen setting
-                    parent
-                        .spawn(Node {
-                            width: Val::Percent(SETTINGS_CONTAINER_WIDTH),
-                            height: Val::Px(SETTING_ROW_HEIGHT),
-                            flex_direction: FlexDirection::Row,
-                            align_items: AlignItems::Center,
-                            justify_content: JustifyContent::SpaceBetween,
-                            margin: UiRect::all(Val::Px(SETTING_ROW_MARGIN)),
-                            ..default()
-                        })
-                        .with_children(|parent| {
-                            // Label
-                            parent.spawn((
-                                Text::new("Fullscreen"),
-                                TextFont {
-                                    font_size: 24.0,
-                                    ..default()
-                                },
-                                TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                            ));
-
-                            // Button
-                            parent
-                                .spawn((
-                                    Button,
-                                    Node {
-                                        width: Val::Px(BUTTON_WIDTH),
-                                        height: Val::Px(BUTTON_HEIGHT),
-                                        justify_content: JustifyContent::Center,
-                                        align_items: AlignItems::Center,
-                                        ..Default::default()
-                                    },
-                                    BackgroundColor(ButtonColors::default().normal),
-                                    ButtonColors::default(),
-                                    FullscreenButton,
-                                ))
-                                .with_child((
-                                    Text::new(match display_settings.window_mode {
-                                        WindowModeType::Windowed => "Windowed",
-                                        WindowModeType::Fullscreen => "Fullscreen",
-                                        WindowModeType::BorderlessFullscreen => "Borderless",
-                                    }),
-                                    TextFont {
-                                        font_size: 20.0,
-                                        ..default()
-                                    },
-                                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                                    FullscreenText,
-                                ));
-                        });
-
-                    // VSync setting
-                    parent
-                        .spawn(Node {
-                            width: Val::Percent(SETTINGS_CONTAINER_WIDTH),
-                            height: Val::Px(SETTING_ROW_HEIGHT),
-                            flex_direction: FlexDirection::Row,
-                            align_items: AlignItems::Center,
-                            justify_content: JustifyContent::SpaceBetween,
-                            margin: UiRect::all(Val::Px(SETTING_ROW_MARGIN)),
-                            ..default()
-                        })
-                        .with_children(|parent| {
-                            // Label
-                            parent.spawn((
-                                Text::new("VSync"),
-                                TextFont {
-                                    font_size: 24.0,
-                                    ..default()
-                                },
-                                TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                            ));
-
-                            // Button
-                            parent
-                                .spawn((
-                                    Button,
-                                    Node {
-                                        width: Val::Px(BUTTON_WIDTH),
-                                        height: Val::Px(BUTTON_HEIGHT),
-                                        justify_content: JustifyContent::Center,
-                                        align_items: AlignItems::Center,
-                                        ..Default::default()
-                                    },
-                                    BackgroundColor(ButtonColors::default().normal),
-                                    ButtonColors::default(),
-                                    VSyncButton,
-                                ))
-                                .with_child((
-                                    Text::new(if display_settings.vsync { "On" } else { "Off" }),
-                                    TextFont {
-                                        font_size: 20.0,
-                                        ..default()
-                                    },
-                                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                                    VSyncText,
-                                ));
-                        });
+            scroll_list(parent, Val::Percent(SETTINGS_CONTAINER_HEIGHT)).with_children(|parent| {
+                setting_row(parent, "Monitor", |parent| {
+                    cycle_selector(
+                        parent,
+                        CycleSelector::new(vec![monitor], 0),
+                        ButtonStyle::SMALL,
+                    )
+                    .insert(MonitorButton);
                 });
+                setting_row(parent, "Resolution", |parent| {
+                    cycle_selector(
+                        parent,
+                        CycleSelector::new(vec![resolution], 0),
+                        ButtonStyle::SMALL,
+                    )
+                    .insert(ResolutionButton);
+                });
+                setting_row(parent, "Refresh Rate", |parent| {
+                    cycle_selector(
+                        parent,
+                        CycleSelector::new(vec![refresh_rate], 0),
+                        ButtonStyle::SMALL,
+                    )
+                    .insert(RefreshRateButton);
+                });
+                setting_row(parent, "Fullscreen", |parent| {
+                    cycle_selector(
+                        parent,
+                        window_mode_selector(display_settings.window_mode),
+                        ButtonStyle::SMALL,
+                    )
+                    .insert(FullscreenButton);
+                });
+                setting_row(parent, "VSync", |parent| {
+                    toggle(parent, display_settings.vsync, ButtonStyle::SMALL).insert(VSyncButton);
+                });
+                setting_row(parent, "Scaling", |parent| {
+                    cycle_selector(
+                        parent,
+                        scaling_selector(display_settings.scaling),
+                        ButtonStyle::SMALL,
+                    )
+                    .insert(ScalingButton);
+                });
+                setting_row(parent, "UI Scale", |parent| {
+                    dropdown(
+                        parent,
+                        ui_scale_dropdown(display_settings.ui_scale),
+                        ButtonStyle::SMALL,
+                    )
+                    .insert(UiScaleButton);
+                });
+            });
 
-            // Bottom buttons
             create_bottom_buttons(parent);
         });
 }
 
-fn update_display_settings_text(
-    mut text_queries: ParamSet<(
-        Query<&mut Text, With<ResolutionText>>,
-        Query<&mut Text, With<FullscreenText>>,
-        Query<&mut Text, With<VSyncText>>,
+fn resolution_label((width, height): (u32, u32)) -> String {
+    format!("{width}x{height}")
+}
+
+/// A selector offering `values`, with `current` selected
+fn selector_for<T: PartialEq>(
+    values: &[T],
+    current: &T,
+    label: impl Fn(&T) -> String,
+) -> CycleSelector {
+    CycleSelector::new(
+        values.iter().map(label).collect(),
+        values
+            .iter()
+            .position(|value| value == current)
+            .unwrap_or_default(),
+    )
+}
+
+fn window_mode_selector(window_mode: WindowModeType) -> CycleSelector {
+    selector_for(&WindowModeType::ALL, &window_mode, |mode| {
+        mode.label().to_string()
+    })
+}
+
+fn scaling_selector(scaling: RenderScaling) -> CycleSelector {
+    selector_for(&RenderScaling::ALL, &scaling, |scaling| {
+        scaling.label().to_string()
+    })
+}
+
+fn ui_scale_dropdown(ui_scale: f32) -> Dropdown {
+    let selector = selector_for(&UI_SCALES, &ui_scale, |scale| ui_scale_label(*scale));
+    Dropdown {
+        options: selector.options,
+        selected: selector.selected,
+    }
+}
+
+/// The labels of the connected monitors, and the one of the chosen monitor
+fn monitor_labels(
+    monitors: &Monitors,
+    display_settings: &NewDisplaySettings,
+) -> (Vec<String>, String) {
+    let labels: Vec<_> = monitors
+        .list()
+        .into_iter()
+        .map(|monitor| monitor.label)
+        .collect();
+    let current = monitors
+        .find(display_settings.monitor.as_deref())
+        .map_or_else(|| "Primary".to_string(), |monitor| monitor.label);
+    if labels.is_empty() {
+        return (vec![current.clone()], current);
+    }
+    (labels, current)
+}
+
+/// The resolutions the chosen monitor supports
+/// Also contains the current one, so a window size the player dragged to isn't lost
+fn available_resolutions(monitor: Option<&MonitorEntry>, current: (u32, u32)) -> Vec<(u32, u32)> {
+    let mut resolutions = monitor
+        .map(MonitorEntry::resolutions)
+        .filter(|resolutions| !resolutions.is_empty())
+        .unwrap_or_else(|| FALLBACK_RESOLUTIONS.to_vec());
+    if let Err(index) = resolutions.binary_search(&current) {
+        resolutions.insert(index, current);
+    }
+    resolutions
+}
+
+/// "Auto" and every refresh rate of the monitor at the given resolution
+fn available_refresh_rates(
+    monitor: Option<&MonitorEntry>,
+    resolution: (u32, u32),
+) -> Vec<Option<u32>> {
+    std::iter::once(None)
+        .chain(
+            monitor
+                .map(|monitor| monitor.refresh_rates(resolution))
+                .unwrap_or_default()
+                .into_iter()
+                .map(Some),
+        )
+        .collect()
+}
+
+// The options of the monitor, resolution and refresh rate depend on each other, so all widgets are refreshed together
+fn update_display_settings_widgets(
+    mut selector_queries: ParamSet<(
+        Query<&mut CycleSelector, With<MonitorButton>>,
+        Query<&mut CycleSelector, With<ResolutionButton>>,
+        Query<&mut CycleSelector, With<RefreshRateButton>>,
+        Query<&mut CycleSelector, With<FullscreenButton>>,
+        Query<&mut CycleSelector, With<ScalingButton>>,
     )>,
+    mut vsync_query: Query<&mut Toggle, With<VSyncButton>>,
+    mut ui_scale_query: Query<&mut Dropdown, With<UiScaleButton>>,
     display_settings: Res<NewDisplaySettings>,
+    monitors: Monitors,
     mut event_reader: EventReader<DisplaySettingsChanged>,
 ) {
-    if event_reader.read().next().is_some() {
-        println!("Display settings changed:");
-        println!(
-            "  Resolution: {}x{}",
-            display_settings.resolution.0, display_settings.resolution.1
-        );
-        println!("  Window mode: {:?}", display_settings.window_mode);
-        println!("  VSync: {}", display_settings.vsync);
-
-        // Update resolution text
-        if let Ok(mut text) = text_queries.p0().get_single_mut() {
-            text.0 = format!(
-                "{}x{}",
-                display_settings.resolution.0, display_settings.resolution.1
-            );
-            println!("  Updated resolution text");
-        } else {
-            println!("  Failed to find resolution text");
-        }
-
-        // Update fullscreen mode text
-        if let Ok(mut text) = text_queries.p1().get_single_mut() {
-            text.0 = match display_settings.window_mode {
-                WindowModeType::Windowed => "Windowed",
-                WindowModeType::Fullscreen => "Fullscreen",
-                WindowModeType::BorderlessFullscreen => "Borderless",
-            }
-            .to_string();
-            println!("  Updated fullscreen text");
-        } else {
-            println!("  Failed to find fullscreen text");
-        }
+    if event_reader.read().last().is_none() {
+        return;
+    }
+    let monitor = monitors.find(display_settings.monitor.as_deref());
 
-        // Update VSync text
-        if let Ok(mut text) = text_queries.p2().get_single_mut() {
-            text.0 = if display_settings.vsync { "On" } else { "Off" }.to_string();
-            println!("  Updated VSync text");
-        } else {
-            println!("  Failed to find VSync text");
-        }
+    let (labels, current) = monitor_labels(&monitors, &display_settings);
+    for mut selector in &mut selector_queries.p0() {
+        selector.set_if_neq(selector_for(&labels, &current, Clone::clone));
+    }
+    let resolutions = available_resolutions(monitor.as_ref(), display_settings.resolution);
+    for mut selector in &mut selector_queries.p1() {
+        selector.set_if_neq(selector_for(
+            &resolutions,
+            &display_settings.resolution,
+            |resolution| resolution_label(*resolution),
+        ));
+    }
+    let refresh_rates = available_refresh_rates(monitor.as_ref(), display_settings.resolution);
+    for mut selector in &mut selector_queries.p2() {
+        selector.set_if_neq(selector_for(
+            &refresh_rates,
+            &display_settings.refresh_rate_millihertz,
+            |refresh_rate| refresh_rate_label(*refresh_rate),
+        ));
+    }
+    for mut selector in &mut selector_queries.p3() {
+        selector.set_if_neq(window_mode_selector(display_settings.window_mode));
+    }
+    for mut selector in &mut selector_queries.p4() {
+        selector.set_if_neq(scaling_selector(display_settings.scaling));
+    }
+    for mut toggle in &mut vsync_query {
+        toggle.set_if_neq(Toggle {
+            on: display_settings.vsync,
+        });
+    }
+    for mut dropdown in &mut ui_scale_query {
+        dropdown.set_if_neq(ui_scale_dropdown(display_settings.ui_scale));
     }
 }
 
@@ -373,226 +523,198 @@ fn create_bottom_buttons(parent: &mut ChildBuilder) {
             ..default()
         })
         .with_children(|parent| {
-            // Apply button
-            parent
-                .spawn((
-                    Button,
-                    Node {
-                        width: Val::Px(BUTTON_WIDTH),
-                        height: Val::Px(BOTTOM_BUTTON_HEIGHT),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        ..Default::default()
-                    },
-                    BackgroundColor(ButtonColors::default().normal),
-                    ButtonColors::default(),
-                    ApplyButton,
-                ))
-                .with_child((
-                    Text::new("Apply"),
-                    TextFont {
-                        font_size: 24.0,
-                        ..default()
-                    },
-                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                ));
-
-            // Back button
-            parent
-                .spawn((
-                    Button,
-                    Node {
-                        width: Val::Px(BUTTON_WIDTH),
-                        height: Val::Px(BOTTOM_BUTTON_HEIGHT),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        ..Default::default()
-                    },
-                    BackgroundColor(ButtonColors::default().normal),
-                    ButtonColors::default(),
-                    DisplaySettingsBackButton,
-                ))
-                .with_child((
-                    Text::new("Back"),
-                    TextFont {
-                        font_size: 24.0,
-                        ..default()
-                    },
-                    TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                ));
+            button(parent, "Apply", ButtonStyle::MEDIUM).insert(ApplyButton);
+            button(parent, "Back", ButtonStyle::MEDIUM).insert(DisplaySettingsBackButton);
         });
 }
 
 #[derive(Component)]
 struct DisplaySettingsUI;
 
+#[derive(Component)]
+pub struct MonitorButton;
+
 #[derive(Component)]
 pub struct ResolutionButton;
 
+#[derive(Component)]
+pub struct RefreshRateButton;
+
 #[derive(Component)]
 pub struct FullscreenButton;
 
 #[derive(Component)]
 pub struct VSyncButton;
 
+#[derive(Component)]
+pub struct ScalingButton;
+
+#[derive(Component)]
+pub struct UiScaleButton;
+
 #[derive(Component)]
 struct ApplyButton;
 
 #[derive(Component)]
 struct DisplaySettingsBackButton;
 
+fn handle_monitor_button(
+    mut changed_events: EventReader<WidgetChanged>,
+    selector_query: Query<&CycleSelector, With<MonitorButton>>,
+    monitors: Monitors,
+    mut display_settings: ResMut<NewDisplaySettings>,
+    mut event_writer: EventWriter<DisplaySettingsChanged>,
+) {
+    for event in changed_events.read() {
+        let Ok(selector) = selector_query.get(event.entity) else {
+            continue;
+        };
+        let Some(monitor) = monitors.list().into_iter().nth(selector.selected) else {
+            continue;
+        };
+        display_settings.monitor = Some(monitor.label);
+        // The refresh rates differ between monitors
+        display_settings.refresh_rate_millihertz = None;
+        event_writer.send(DisplaySettingsChanged);
+    }
+}
+
 fn handle_resolution_button(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, &ButtonColors),
-        (With<ResolutionButton>, Changed<Interaction>),
-    >,
+    mut changed_events: EventReader<WidgetChanged>,
+    selector_query: Query<&CycleSelector, With<ResolutionButton>>,
+    monitors: Monitors,
     mut display_settings: ResMut<NewDisplaySettings>,
     mut event_writer: EventWriter<DisplaySettingsChanged>,
 ) {
-    for (interaction, mut color, button_colors) in &mut interaction_query {
-        match *interaction {
-            Interaction::Pressed => {
-                // Cycle through common resolutions
-                let new_res = match display_settings.resolution {
-                    (800, 600) => (1280, 720),
-                    (1280, 720) => (1366, 768),
-                    (1366, 768) => (1600, 900),
-                    (1600, 900) => (1920, 1080),
-                    (1920, 1080) => (2560, 1440),
-                    (2560, 1440) => (3840, 2160),
-                    (3840, 2160) => (800, 600),
-                    _ => (1920, 1080), // Default if somehow in an unknown state
-                };
-                display_settings.resolution = new_res;
-                event_writer.send(DisplaySettingsChanged);
-                *color = button_colors.active.into();
-            }
-            Interaction::Hovered => {
-                *color = button_colors.hovered.into();
-            }
-            Interaction::None => {
-                *color = button_colors.normal.into();
+    for event in changed_events.read() {
+        let Ok(selector) = selector_query.get(event.entity) else {
+            continue;
+        };
+        let monitor = monitors.find(display_settings.monitor.as_deref());
+        let resolutions = available_resolutions(monitor.as_ref(), display_settings.resolution);
+        let Some(resolution) = resolutions.get(selector.selected) else {
+            continue;
+        };
+        display_settings.resolution = *resolution;
+        // Keep the refresh rate only if the monitor supports it at the new resolution
+        if let Some(refresh_rate) = display_settings.refresh_rate_millihertz {
+            let supported = monitor.is_some_and(|monitor| {
+                monitor
+                    .refresh_rates(display_settings.resolution)
+                    .contains(&refresh_rate)
+            });
+            if !supported {
+                display_settings.refresh_rate_millihertz = None;
             }
         }
+        event_writer.send(DisplaySettingsChanged);
+    }
+}
+
+fn handle_refresh_rate_button(
+    mut changed_events: EventReader<WidgetChanged>,
+    selector_query: Query<&CycleSelector, With<RefreshRateButton>>,
+    monitors: Monitors,
+    mut display_settings: ResMut<NewDisplaySettings>,
+    mut event_writer: EventWriter<DisplaySettingsChanged>,
+) {
+    for event in changed_events.read() {
+        let Ok(selector) = selector_query.get(event.entity) else {
+            continue;
+        };
+        let monitor = monitors.find(display_settings.monitor.as_deref());
+        let refresh_rates = available_refresh_rates(monitor.as_ref(), display_settings.resolution);
+        if let Some(refresh_rate) = refresh_rates.get(selector.selected) {
+            display_settings.refresh_rate_millihertz = *refresh_rate;
+            event_writer.send(DisplaySettingsChanged);
+        }
     }
 }
 
 fn handle_fullscreen_button(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, &ButtonColors),
-        (With<FullscreenButton>, Changed<Interaction>),
-    >,
+    mut changed_events: EventReader<WidgetChanged>,
+    selector_query: Query<&CycleSelector, With<FullscreenButton>>,
     mut display_settings: ResMut<NewDisplaySettings>,
     mut event_writer: EventWriter<DisplaySettingsChanged>,
 ) {
-    for (interaction, mut color, button_colors) in &mut interaction_query {
-        match *interaction {
-            Interaction::Pressed => {
-                // Cycle through window modes
-                display_settings.window_mode = match display_settings.window_mode {
-                    WindowModeType::Windowed => WindowModeType::Fullscreen,
-                    WindowModeType::Fullscreen => WindowModeType::BorderlessFullscreen,
-                    WindowModeType::BorderlessFullscreen => WindowModeType::Windowed,
-                };
-                event_writer.send(DisplaySettingsChanged);
-                *color = button_colors.active.into();
-            }
-            Interaction::Hovered => {
-                *color = button_colors.hovered.into();
-            }
-            Interaction::None => {
-                *color = button_colors.normal.into();
-            }
+    for event in changed_events.read() {
+        if let Ok(selector) = selector_query.get(event.entity) {
+            display_settings.window_mode = WindowModeType::ALL[selector.selected];
+            event_writer.send(DisplaySettingsChanged);
         }
     }
 }
 
 fn handle_vsync_button(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, &ButtonColors),
-        (With<VSyncButton>, Changed<Interaction>),
-    >,
+    mut changed_events: EventReader<WidgetChanged>,
+    toggle_query: Query<&Toggle, With<VSyncButton>>,
     mut display_settings: ResMut<NewDisplaySettings>,
     mut event_writer: EventWriter<DisplaySettingsChanged>,
 ) {
-    for (interaction, mut color, button_colors) in &mut interaction_query {
-        match *interaction {
-            Interaction::Pressed => {
-                display_settings.vsync = !display_settings.vsync;
-                event_writer.send(DisplaySettingsChanged);
-                *color = button_colors.active.into();
-            }
-            Interaction::Hovered => {
-                *color = button_colors.hovered.into();
-            }
-            Interaction::None => {
-                *color = button_colors.normal.into();
-            }
+    for event in changed_events.read() {
+        if let Ok(toggle) = toggle_query.get(event.entity) {
+            display_settings.vsync = toggle.on;
+            event_writer.send(DisplaySettingsChanged);
+        }
+    }
+}
+
+fn handle_scaling_button(
+    mut changed_events: EventReader<WidgetChanged>,
+    selector_query: Query<&CycleSelector, With<ScalingButton>>,
+    mut display_settings: ResMut<NewDisplaySettings>,
+    mut event_writer: EventWriter<DisplaySettingsChanged>,
+) {
+    for event in changed_events.read() {
+        if let Ok(selector) = selector_query.get(event.entity) {
+            display_settings.scaling = RenderScaling::ALL[selector.selected];
+            event_writer.send(DisplaySettingsChanged);
+        }
+    }
+}
+
+fn handle_ui_scale_button(
+    mut changed_events: EventReader<WidgetChanged>,
+    dropdown_query: Query<&Dropdown, With<UiScaleButton>>,
+    mut display_settings: ResMut<NewDisplaySettings>,
+    mut event_writer: EventWriter<DisplaySettingsChanged>,
+) {
+    for event in changed_events.read() {
+        if let Ok(dropdown) = dropdown_query.get(event.entity) {
+            display_settings.ui_scale = UI_SCALES[dropdown.selected];
+            event_writer.send(DisplaySettingsChanged);
         }
     }
 }
 
 fn handle_apply_button(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, &ButtonColors),
-        (With<ApplyButton>, Changed<Interaction>),
-    >,
-    mut window: Query<&mut Window>,
+    mut commands: Commands,
+    interaction_query: Query<&Interaction, (With<ApplyButton>, Changed<Interaction>)>,
+    mut display_target: DisplayTarget,
     display_settings: Res<NewDisplaySettings>,
-    mut render_resolution: ResMut<GameRenderResolution>,
+    settings_file: Res<SettingsFile>,
+    mut confirmation: Option<ResMut<DisplayConfirmation>>,
 ) {
-    for (interaction, mut color, button_colors) in &mut interaction_query {
-        match *interaction {
-            Interaction::Pressed => {
-                // Apply changes to window
-                if let Ok(mut window) = window.get_single_mut() {
-                    // Update the render resolution
-                    render_resolution.width = display_settings.resolution.0 as f32;
-                    render_resolution.height = display_settings.resolution.1 as f32;
-
-                    // In fullscreen modes, keep the window at native resolution
-                    let (width, height) = match display_settings.window_mode {
-                        WindowModeType::Windowed => display_settings.resolution,
-                        _ => (
-                            window.resolution.physical_width(),
-                            window.resolution.physical_height(),
-                        ),
-                    };
-
-                    // Apply window mode first
-                    match display_settings.window_mode {
-                        WindowModeType::Windowed => {
-                            window.mode = WindowMode::Windowed;
-                            window.position = WindowPosition::Automatic;
-                        }
-                        WindowModeType::Fullscreen => {
-                            window.mode = WindowMode::Fullscreen(MonitorSelection::Current);
-                        }
-                        WindowModeType::BorderlessFullscreen => {
-                            window.mode =
-                                WindowMode::BorderlessFullscreen(MonitorSelection::Current);
-                        }
-                    }
-
-                    // Then set the window resolution
-                    window.resolution = WindowResolution::new(width as f32, height as f32);
-
-                    // Apply other settings
-                    window.present_mode = if display_settings.vsync {
-                        PresentMode::AutoVsync
-                    } else {
-                        PresentMode::AutoNoVsync
-                    };
-                }
-                *color = button_colors.active.into();
-            }
-            Interaction::Hovered => {
-                *color = button_colors.hovered.into();
-            }
-            Interaction::None => {
-                *color = button_colors.normal.into();
+    for interaction in &interaction_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // Applying again while asked to confirm still reverts to the settings from before
+        match confirmation.as_mut() {
+            Some(confirmation) => {
+                **confirmation = DisplayConfirmation::new(
+                    confirmation.previous.clone(),
+                    display_settings.clone(),
+                );
             }
+            None => commands.insert_resource(DisplayConfirmation::new(
+                settings_file.display.clone(),
+                display_settings.clone(),
+            )),
         }
+
+        // Apply changes to window
+        display_target.apply(&display_settings);
     }
 }
 
@@ -1,6 +1,8 @@
 pub mod audio_settings;
 pub mod controls_settings;
+pub mod display_confirmation;
 pub mod display_settings;
+pub mod monitors;
 pub mod ui_constants;
 
 pub use audio_settings::*;
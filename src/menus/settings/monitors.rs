@@ -0,0 +1,188 @@
+use crate::menus::settings::{NewDisplaySettings, WindowModeType};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::window::{Monitor, PrimaryMonitor, PrimaryWindow};
+use bevy::winit::WinitWindows;
+use winit::window::Fullscreen;
+
+/// Offered in the Display settings when no monitor reports its video modes, e.g. on the web
+pub const FALLBACK_RESOLUTIONS: [(u32, u32); 7] = [
+    (800, 600),
+    (1280, 720),
+    (1366, 768),
+    (1600, 900),
+    (1920, 1080),
+    (2560, 1440),
+    (3840, 2160),
+];
+
+pub struct MonitorsPlugin;
+
+/// This plugin switches the window to the exact video mode chosen in the Display settings
+/// Bevy only picks the video mode closest to the window size with the highest refresh rate,
+/// so a lower refresh rate is set on the `winit` window directly afterwards
+impl Plugin for MonitorsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PendingVideoMode>()
+            .add_systems(PreUpdate, apply_pending_video_mode);
+    }
+}
+
+/// All connected monitors, as reported by Bevy
+#[derive(SystemParam)]
+pub struct Monitors<'w, 's> {
+    query: Query<'w, 's, (Entity, &'static Monitor, Has<PrimaryMonitor>)>,
+}
+
+pub struct MonitorEntry<'a> {
+    pub entity: Entity,
+    /// Identifies the monitor in the settings file
+    pub label: String,
+    pub monitor: &'a Monitor,
+}
+
+impl Monitors<'_, '_> {
+    /// The primary monitor first, then from left to right
+    pub fn list(&self) -> Vec<MonitorEntry<'_>> {
+        let mut monitors: Vec<_> = self.query.iter().collect();
+        monitors.sort_by_key(|(_, monitor, primary)| {
+            (
+                !primary,
+                monitor.physical_position.x,
+                monitor.physical_position.y,
+            )
+        });
+        monitors
+            .into_iter()
+            .enumerate()
+            .map(|(index, (entity, monitor, _))| MonitorEntry {
+                entity,
+                label: monitor
+                    .name
+                    .clone()
+                    .unwrap_or_else(|| format!("Monitor {}", index + 1)),
+                monitor,
+            })
+            .collect()
+    }
+
+    /// The monitor with the given label, or the primary one if there is none
+    pub fn find(&self, label: Option<&str>) -> Option<MonitorEntry<'_>> {
+        let mut monitors = self.list();
+        let index = label
+            .and_then(|label| monitors.iter().position(|entry| entry.label == label))
+            .unwrap_or(0);
+        (index < monitors.len()).then(|| monitors.swap_remove(index))
+    }
+}
+
+impl MonitorEntry<'_> {
+    /// Every size any of the video modes has, from small to large
+    pub fn resolutions(&self) -> Vec<(u32, u32)> {
+        let mut resolutions: Vec<_> = self
+            .monitor
+            .video_modes
+            .iter()
+            .map(|mode| (mode.physical_size.x, mode.physical_size.y))
+            .collect();
+        resolutions.sort_unstable();
+        resolutions.dedup();
+        resolutions
+    }
+
+    /// The refresh rates in millihertz the monitor supports at the given size, from low to high
+    pub fn refresh_rates(&self, resolution: (u32, u32)) -> Vec<u32> {
+        let mut refresh_rates: Vec<_> = self
+            .monitor
+            .video_modes
+            .iter()
+            .filter(|mode| mode.physical_size == UVec2::new(resolution.0, resolution.1))
+            .map(|mode| mode.refresh_rate_millihertz)
+            .collect();
+        refresh_rates.sort_unstable();
+        refresh_rates.dedup();
+        refresh_rates
+    }
+}
+
+pub fn refresh_rate_label(refresh_rate_millihertz: Option<u32>) -> String {
+    match refresh_rate_millihertz {
+        None => "Auto".to_string(),
+        Some(millihertz) if millihertz % 1000 == 0 => format!("{} Hz", millihertz / 1000),
+        Some(millihertz) => format!("{:.2} Hz", millihertz as f32 / 1000.0),
+    }
+}
+
+/// Exclusive fullscreen video mode that still has to be set on the window
+#[derive(Resource, Default, Debug)]
+pub struct PendingVideoMode(pub Option<VideoModeRequest>);
+
+#[derive(Debug, PartialEq)]
+pub struct VideoModeRequest {
+    /// Label of the monitor, see [`MonitorEntry::label`]
+    pub monitor: String,
+    pub resolution: (u32, u32),
+    pub refresh_rate_millihertz: u32,
+}
+
+impl VideoModeRequest {
+    /// Only needed in exclusive fullscreen with a fixed refresh rate, Bevy handles everything else
+    pub fn from_settings(settings: &NewDisplaySettings, monitor: &MonitorEntry) -> Option<Self> {
+        if settings.window_mode != WindowModeType::Fullscreen {
+            return None;
+        }
+        Some(Self {
+            monitor: monitor.label.clone(),
+            resolution: settings.resolution,
+            refresh_rate_millihertz: settings.refresh_rate_millihertz?,
+        })
+    }
+}
+
+// Runs before anything in `Update` can change the window again, so Bevy has already switched
+// to fullscreen in `Last` of the frame the request was made in
+fn apply_pending_video_mode(
+    mut pending: ResMut<PendingVideoMode>,
+    winit_windows: Option<NonSend<WinitWindows>>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+) {
+    if pending.0.is_none() {
+        return;
+    }
+    let (Some(winit_windows), Ok(primary_window)) = (winit_windows, primary_window.get_single())
+    else {
+        return;
+    };
+    let Some(window) = winit_windows.get_window(primary_window) else {
+        return;
+    };
+    // Wait until Bevy picked the monitor, so the exact mode isn't replaced by its choice again
+    let Some(Fullscreen::Exclusive(current_mode)) = window.fullscreen() else {
+        return;
+    };
+    let Some(request) = pending.0.take() else {
+        return;
+    };
+
+    let monitor = current_mode.monitor();
+    let video_mode = monitor
+        .video_modes()
+        .filter(|mode| {
+            (mode.size().width, mode.size().height) == request.resolution
+                && mode.refresh_rate_millihertz() == request.refresh_rate_millihertz
+        })
+        .max_by_key(|mode| mode.bit_depth());
+    match video_mode {
+        Some(video_mode) if video_mode != current_mode => {
+            window.set_fullscreen(Some(Fullscreen::Exclusive(video_mode)));
+        }
+        Some(_) => {}
+        None => warn!(
+            "Monitor {} has no video mode with {}x{} at {}",
+            request.monitor,
+            request.resolution.0,
+            request.resolution.1,
+            refresh_rate_label(Some(request.refresh_rate_millihertz))
+        ),
+    }
+}
@@ -1,7 +1,12 @@
+use crate::actions::{KeyBindings, TouchControlSettings};
+use crate::audio::AudioSettings;
+use crate::menus::focus::FocusedEntity;
 use crate::menus::settings::*;
-use crate::menus::ui_components::ButtonColors;
+use crate::menus::ui_components::ActiveButton;
+use crate::menus::widgets::{button, menu_container, title, ButtonStyle};
 use crate::overlay_state::GameOverlayState;
 use crate::AppState;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 
 pub struct SettingsMenuPlugin;
@@ -27,14 +32,9 @@ impl Plugin for SettingsMenuPlugin {
         )
         .add_systems(
             Update,
-            (
-                handle_tab_button_hover,
-                handle_regular_button_hover,
-                handle_tab_buttons,
-            )
-                .run_if(
-                    in_state(AppState::SettingsMenu).or(in_state(GameOverlayState::SettingsMenu)),
-                ),
+            (handle_tab_buttons, handle_tab_shortcuts).run_if(
+                in_state(AppState::SettingsMenu).or(in_state(GameOverlayState::SettingsMenu)),
+            ),
         )
         .add_systems(
             OnExit(AppState::SettingsMenu),
@@ -60,122 +60,37 @@ struct TabContent {
     tab_index: usize,
 }
 
-#[derive(Component)]
-struct ActiveButton;
-
-fn setup_settings_menu(mut commands: Commands, display_settings: Res<NewDisplaySettings>) {
-    // Menu container
+fn setup_settings_menu(
+    mut commands: Commands,
+    display_settings: Res<NewDisplaySettings>,
+    audio_settings: Res<AudioSettings>,
+    key_bindings: Res<KeyBindings>,
+    touch_settings: Res<TouchControlSettings>,
+) {
     commands
-        .spawn((
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                flex_direction: FlexDirection::Column,
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },
-            SettingsMenu,
-        ))
+        .spawn((menu_container(), SettingsMenu))
         .with_children(|children| {
-            // Title
-            children.spawn((
-                Text::new("Settings"),
-                TextFont {
-                    font_size: 72.0,
-                    ..default()
-                },
-                TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                Node {
-                    margin: UiRect::bottom(Val::Px(50.0)),
-                    ..default()
-                },
-            ));
+            title(children, "Settings");
 
-            // Tab buttons container
             children
                 .spawn(Node {
                     flex_direction: FlexDirection::Row,
                     align_items: AlignItems::Center,
                     justify_content: JustifyContent::Center,
+                    column_gap: Val::Px(10.0),
                     margin: UiRect::bottom(Val::Px(20.0)),
                     ..default()
                 })
                 .with_children(|parent| {
-                    // Display Tab
-                    parent
-                        .spawn((
-                            Button,
-                            Node {
-                                width: Val::Px(150.0),
-                                height: Val::Px(50.0),
-                                justify_content: JustifyContent::Center,
-                                align_items: AlignItems::Center,
-                                margin: UiRect::right(Val::Px(10.0)),
-                                ..Default::default()
-                            },
-                            BackgroundColor(ButtonColors::default().normal),
-                            ButtonColors::default(),
-                            TabButton { tab_index: 0 },
-                            ActiveButton,
-                        ))
-                        .with_child((
-                            Text::new("Display"),
-                            TextFont {
-                                font_size: 24.0,
-                                ..default()
-                            },
-                            TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                        ));
-
-                    // Audio Tab
-                    parent
-                        .spawn((
-                            Button,
-                            Node {
-                                width: Val::Px(150.0),
-                                height: Val::Px(50.0),
-                                justify_content: JustifyContent::Center,
-                                align_items: AlignItems::Center,
-                                margin: UiRect::right(Val::Px(10.0)),
-                                ..Default::default()
-                            },
-                            BackgroundColor(ButtonColors::default().normal),
-                            ButtonColors::default(),
-                            TabButton { tab_index: 1 },
-                        ))
-                        .with_child((
-                            Text::new("Audio"),
-                            TextFont {
-                                font_size: 24.0,
-                                ..default()
-                            },
-                            TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                        ));
-
-                    // Controls Tab
-                    parent
-                        .spawn((
-                            Button,
-                            Node {
-                                width: Val::Px(150.0),
-                                height: Val::Px(50.0),
-                                justify_content: JustifyContent::Center,
-                                align_items: AlignItems::Center,
-                                ..Default::default()
-                            },
-                            BackgroundColor(ButtonColors::default().normal),
-                            ButtonColors::default(),
-                            TabButton { tab_index: 2 },
-                        ))
-                        .with_child((
-                            Text::new("Controls"),
-                            TextFont {
-                                font_size: 24.0,
-                                ..default()
-                            },
-                            TextColor(Color::linear_rgb(0.9, 0.9, 0.9)),
-                        ));
+                    for (tab_index, name) in
+                        ["Display", "Audio", "Controls"].into_iter().enumerate()
+                    {
+                        let mut tab = button(parent, name, ButtonStyle::MEDIUM);
+                        tab.insert(TabButton { tab_index });
+                        if tab_index == 0 {
+                            tab.insert(ActiveButton);
+                        }
+                    }
                 });
 
             // Tab content container
@@ -222,7 +137,7 @@ fn setup_settings_menu(mut commands: Commands, display_settings: Res<NewDisplayS
                             TabContent { tab_index: 1 },
                         ))
                         .with_children(|parent| {
-                            setup_audio_settings(parent);
+                            setup_audio_settings(parent, &audio_settings);
                         });
 
                     // Controls tab content
@@ -240,103 +155,95 @@ fn setup_settings_menu(mut commands: Commands, display_settings: Res<NewDisplayS
                             TabContent { tab_index: 2 },
                         ))
                         .with_children(|parent| {
-                            setup_controls_settings(parent);
+                            setup_controls_settings(parent, &key_bindings, &touch_settings);
                         });
                 });
         });
 }
 
-fn handle_tab_button_hover(
-    mut interaction_query: Query<
-        (
-            &Interaction,
-            &mut BackgroundColor,
-            &ButtonColors,
-            &TabButton,
-            Option<&ActiveButton>,
-        ),
-        Changed<Interaction>,
-    >,
+fn handle_tab_buttons(
+    interaction_query: Query<(&Interaction, &TabButton), Changed<Interaction>>,
+    mut tabs: Tabs,
 ) {
-    for (interaction, mut color, button_colors, _tab_button, is_active) in &mut interaction_query {
-        match *interaction {
-            Interaction::Hovered => {
-                if is_active.is_some() {
-                    *color = Color::linear_rgb(0.4, 0.4, 0.4).into();
-                } else {
-                    *color = button_colors.hovered.into();
-                }
-            }
-            Interaction::None => {
-                if is_active.is_some() {
-                    *color = button_colors.active.into();
-                } else {
-                    *color = button_colors.normal.into();
-                }
-            }
-            Interaction::Pressed => {}
+    for (interaction, pressed_tab) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            tabs.select(pressed_tab.tab_index);
         }
     }
 }
 
-fn handle_regular_button_hover(
-    mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, &ButtonColors),
-        (Changed<Interaction>, Without<TabButton>),
-    >,
+// Tab cycles forward and Shift+Tab backwards, same as the gamepad's bumpers
+fn handle_tab_shortcuts(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    active_tab_query: Query<&TabButton, With<ActiveButton>>,
+    mut tabs: Tabs,
 ) {
-    for (interaction, mut color, button_colors) in &mut interaction_query {
-        match *interaction {
-            Interaction::Hovered => {
-                *color = button_colors.hovered.into();
-            }
-            Interaction::None => {
-                *color = button_colors.normal.into();
-            }
-            Interaction::Pressed => {}
-        }
-    }
+    let shift = keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let tab = keyboard_input.just_pressed(KeyCode::Tab);
+    let step: isize = if (tab && shift)
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::LeftTrigger))
+    {
+        -1
+    } else if tab
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.just_pressed(GamepadButton::RightTrigger))
+    {
+        1
+    } else {
+        return;
+    };
+
+    let tab_count = tabs.tab_button_query.iter().count();
+    let Ok(active_tab) = active_tab_query.get_single() else {
+        return;
+    };
+    let next_tab = (active_tab.tab_index as isize + step).rem_euclid(tab_count as isize) as usize;
+    tabs.select(next_tab);
 }
 
-fn handle_tab_buttons(
-    interaction_query: Query<(&Interaction, &TabButton), Changed<Interaction>>,
-    mut tab_content_query: Query<(&mut Node, &TabContent)>,
-    mut commands: Commands,
-    mut tab_button_query: Query<
-        (Entity, &mut BackgroundColor, &ButtonColors, &TabButton),
-        With<Button>,
-    >,
-) {
-    for (interaction, pressed_tab) in &interaction_query {
-        if *interaction == Interaction::Pressed {
-            // Remove ActiveButton from all tab buttons
-            for (entity, _, _, _) in &tab_button_query {
-                commands.entity(entity).remove::<ActiveButton>();
-            }
+/// Everything switching between the tabs changes
+#[derive(SystemParam)]
+struct Tabs<'w, 's> {
+    commands: Commands<'w, 's>,
+    tab_content_query: Query<'w, 's, (&'static mut Node, &'static TabContent)>,
+    tab_button_query: Query<'w, 's, (Entity, &'static TabButton)>,
+    focused: ResMut<'w, FocusedEntity>,
+    parent_query: Query<'w, 's, &'static Parent>,
+}
 
-            // Add ActiveButton to the clicked tab
-            for (entity, _, _, button) in &tab_button_query {
-                if button.tab_index == pressed_tab.tab_index {
-                    commands.entity(entity).insert(ActiveButton);
-                }
+impl Tabs<'_, '_> {
+    fn select(&mut self, tab_index: usize) {
+        // Move ActiveButton to the selected tab
+        for (entity, button) in self.tab_button_query.iter() {
+            if button.tab_index == tab_index {
+                self.commands.entity(entity).insert(ActiveButton);
+            } else {
+                self.commands.entity(entity).remove::<ActiveButton>();
             }
+        }
 
-            // Update tab content visibility
-            for (mut node, tab_content) in &mut tab_content_query {
-                node.display = if tab_content.tab_index == pressed_tab.tab_index {
-                    Display::Flex
-                } else {
-                    Display::None
-                };
-            }
+        // Update tab content visibility
+        for (mut node, tab_content) in self.tab_content_query.iter_mut() {
+            node.display = if tab_content.tab_index == tab_index {
+                Display::Flex
+            } else {
+                Display::None
+            };
+        }
 
-            // Update colors for ALL tab buttons
-            for (_, mut color, button_colors, button) in &mut tab_button_query {
-                if button.tab_index == pressed_tab.tab_index {
-                    *color = button_colors.active.into();
-                } else {
-                    *color = button_colors.normal.into();
-                }
+        // Buttons of the hidden tabs can't be pressed through the focus anymore
+        if let Some(entity) = self.focused.0 {
+            let hidden = self.parent_query.iter_ancestors(entity).any(|ancestor| {
+                self.tab_content_query
+                    .get(ancestor)
+                    .is_ok_and(|(_, tab_content)| tab_content.tab_index != tab_index)
+            });
+            if hidden {
+                self.focused.0 = None;
             }
         }
     }
@@ -5,6 +5,8 @@ pub struct ButtonColors {
     pub normal: Color,
     pub hovered: Color,
     pub active: Color,
+    /// Hovered or focused while [`ActiveButton`]
+    pub active_hovered: Color,
 }
 
 impl Default for ButtonColors {
@@ -13,10 +15,30 @@ impl Default for ButtonColors {
             normal: Color::linear_rgb(0.15, 0.15, 0.15),
             hovered: Color::linear_rgb(0.25, 0.25, 0.25),
             active: Color::linear_rgb(0.35, 0.35, 0.35),
+            active_hovered: Color::linear_rgb(0.4, 0.4, 0.4),
         }
     }
 }
 
+impl ButtonColors {
+    /// Focused buttons look like hovered ones, and pressed ones like active ones
+    pub fn color(&self, interaction: Interaction, active: bool, focused: bool) -> Color {
+        match (interaction, active) {
+            (Interaction::Pressed, _) => self.active,
+            (Interaction::Hovered, true) => self.active_hovered,
+            (Interaction::Hovered, false) => self.hovered,
+            (Interaction::None, true) if focused => self.active_hovered,
+            (Interaction::None, false) if focused => self.hovered,
+            (Interaction::None, true) => self.active,
+            (Interaction::None, false) => self.normal,
+        }
+    }
+}
+
+/// Marks the selected button of a group, e.g. the tab that is currently shown
+#[derive(Component)]
+pub struct ActiveButton;
+
 // Common UI dimensions
 pub const BUTTON_WIDTH: f32 = 150.0;
 pub const BUTTON_HEIGHT: f32 = 40.0;
@@ -0,0 +1,129 @@
+use crate::menus::focus::FocusedEntity;
+use crate::menus::widgets::{button, ButtonLabels, ButtonStyle, WidgetChanged};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+
+/// A button showing the selected option, pressing it opens a list below it to pick another one from
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct Dropdown {
+    pub options: Vec<String>,
+    pub selected: usize,
+}
+
+impl Dropdown {
+    fn label(&self) -> &str {
+        self.options.get(self.selected).map_or("", String::as_str)
+    }
+}
+
+/// The options are made with the same style as the dropdown
+#[derive(Component)]
+pub(super) struct DropdownStyle(ButtonStyle);
+
+/// The open list of a dropdown, it is a child of the dropdown
+#[derive(Component)]
+pub(super) struct DropdownList;
+
+#[derive(Component)]
+pub(super) struct DropdownOption(usize);
+
+pub fn dropdown<'a>(
+    parent: &'a mut ChildBuilder,
+    dropdown: Dropdown,
+    style: ButtonStyle,
+) -> EntityCommands<'a> {
+    let mut button = button(parent, dropdown.label(), style);
+    button.insert((dropdown, DropdownStyle(style)));
+    button
+}
+
+// Only one list is open at a time, pressing the dropdown of the open list closes it again
+pub(super) fn press_dropdowns(
+    mut commands: Commands,
+    mut focused: ResMut<FocusedEntity>,
+    dropdown_query: Query<(Entity, &Interaction, &Dropdown, &DropdownStyle), Changed<Interaction>>,
+    list_query: Query<(Entity, &Parent), With<DropdownList>>,
+) {
+    for (entity, interaction, dropdown, DropdownStyle(style)) in &dropdown_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let was_open = list_query.iter().any(|(_, parent)| parent.get() == entity);
+        for (list, _) in &list_query {
+            commands.entity(list).despawn_recursive();
+        }
+        if was_open {
+            continue;
+        }
+
+        let option_style = ButtonStyle {
+            width: Val::Percent(100.0),
+            margin: UiRect::ZERO,
+            ..*style
+        };
+        commands.entity(entity).with_children(|parent| {
+            parent
+                .spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        top: Val::Percent(100.0),
+                        left: Val::Px(0.0),
+                        width: Val::Percent(100.0),
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    },
+                    // Drawn above the widgets following the dropdown
+                    GlobalZIndex(1),
+                    DropdownList,
+                ))
+                .with_children(|parent| {
+                    for (index, option) in dropdown.options.iter().enumerate() {
+                        let option = button(parent, option, option_style)
+                            .insert(DropdownOption(index))
+                            .id();
+                        if index == dropdown.selected {
+                            focused.0 = Some(option);
+                        }
+                    }
+                });
+        });
+    }
+}
+
+pub(super) fn pick_dropdown_options(
+    mut commands: Commands,
+    mut focused: ResMut<FocusedEntity>,
+    mut changed_events: EventWriter<WidgetChanged>,
+    option_query: Query<(&Interaction, &DropdownOption, &Parent), Changed<Interaction>>,
+    list_query: Query<&Parent, With<DropdownList>>,
+    mut dropdown_query: Query<&mut Dropdown>,
+) {
+    for (interaction, DropdownOption(index), list) in &option_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Ok(dropdown_entity) = list_query.get(list.get()).map(Parent::get) else {
+            continue;
+        };
+        let Ok(mut dropdown) = dropdown_query.get_mut(dropdown_entity) else {
+            continue;
+        };
+        if dropdown.selected != *index {
+            dropdown.selected = *index;
+            changed_events.send(WidgetChanged {
+                entity: dropdown_entity,
+            });
+        }
+        commands.entity(list.get()).despawn_recursive();
+        focused.0 = Some(dropdown_entity);
+    }
+}
+
+pub(super) fn update_dropdown_labels(
+    dropdown_query: Query<(Entity, &Dropdown), Changed<Dropdown>>,
+    mut labels: ButtonLabels,
+) {
+    for (entity, dropdown) in &dropdown_query {
+        labels.set(entity, dropdown.label());
+    }
+}
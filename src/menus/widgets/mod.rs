@@ -0,0 +1,380 @@
+use crate::menus::focus::{FocusPlugin, FocusSystem, Focusable, FocusedEntity};
+use crate::menus::settings::ui_constants::{
+    SETTINGS_CONTAINER_WIDTH, SETTING_ROW_HEIGHT, SETTING_ROW_MARGIN,
+};
+use crate::menus::ui_components::{
+    ActiveButton, ButtonColors, BOTTOM_BUTTON_HEIGHT, BUTTON_HEIGHT, BUTTON_WIDTH,
+};
+use bevy::ecs::system::{EntityCommands, SystemParam};
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+
+mod dropdown;
+mod scroll_list;
+mod selector;
+mod slider;
+
+pub use dropdown::{dropdown, Dropdown};
+pub use scroll_list::scroll_list;
+pub use selector::{cycle_selector, toggle, CycleSelector, Toggle};
+pub use slider::{slider, Slider};
+
+pub const TEXT_COLOR: Color = Color::linear_rgb(0.9, 0.9, 0.9);
+pub const DISABLED_TEXT_COLOR: Color = Color::linear_rgb(0.5, 0.5, 0.5);
+/// Background of the bars filled up to show a value, e.g. sliders and the loading progress
+pub const BAR_COLOR: Color = Color::linear_rgb(0.15, 0.15, 0.15);
+
+pub struct WidgetsPlugin;
+
+/// This plugin makes the widgets work that the menus are built from
+/// Widgets holding a value send a [`WidgetChanged`] when the player changed it
+impl Plugin for WidgetsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(FocusPlugin)
+            .add_event::<WidgetChanged>()
+            // Right after the presses are known, so menus see the new values in `Update`
+            .add_systems(
+                PreUpdate,
+                (
+                    selector::press_toggles,
+                    selector::press_cycle_selectors,
+                    slider::press_slider_steps,
+                    slider::drag_sliders,
+                    dropdown::press_dropdowns,
+                    dropdown::pick_dropdown_options,
+                )
+                    .chain()
+                    .in_set(WidgetSystem)
+                    .after(FocusSystem),
+            )
+            .add_systems(
+                Update,
+                (
+                    scroll_list::scroll_hovered_lists,
+                    scroll_list::scroll_to_focused.run_if(resource_changed::<FocusedEntity>),
+                ),
+            )
+            // Values may be changed by any system, the widgets show them before the layout is updated
+            .add_systems(
+                PostUpdate,
+                (
+                    update_button_colors,
+                    selector::update_toggle_labels,
+                    selector::update_cycle_selector_labels,
+                    slider::update_sliders,
+                    dropdown::update_dropdown_labels,
+                )
+                    .before(UiSystem::Prepare),
+            );
+    }
+}
+
+/// Systems that change the values of widgets pressed by the player
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct WidgetSystem;
+
+/// Sent when the player changed the value of a widget
+#[derive(Event, Clone, Copy, PartialEq, Debug)]
+pub struct WidgetChanged {
+    pub entity: Entity,
+}
+
+/// The text of a button made with [`button`]
+#[derive(Component)]
+pub struct WidgetLabel;
+
+/// Size of a button and its label
+#[derive(Clone, Copy, Debug)]
+pub struct ButtonStyle {
+    pub width: Val,
+    pub height: Val,
+    pub font_size: f32,
+    pub margin: UiRect,
+    /// Without a background until hovered or focused
+    pub transparent: bool,
+}
+
+impl ButtonStyle {
+    /// The main buttons of the full screen menus
+    pub const LARGE: Self = Self {
+        width: Val::Px(300.0),
+        height: Val::Px(50.0),
+        font_size: 40.0,
+        margin: UiRect::ZERO,
+        transparent: false,
+    };
+    /// Tabs, and buttons below the settings like Apply
+    pub const MEDIUM: Self = Self {
+        width: Val::Px(BUTTON_WIDTH),
+        height: Val::Px(BOTTOM_BUTTON_HEIGHT),
+        font_size: 24.0,
+        margin: UiRect::ZERO,
+        transparent: false,
+    };
+    /// The values of settings rows
+    pub const SMALL: Self = Self {
+        width: Val::Px(BUTTON_WIDTH),
+        height: Val::Px(BUTTON_HEIGHT),
+        font_size: 20.0,
+        margin: UiRect::ZERO,
+        transparent: false,
+    };
+    /// Square buttons with a single symbol
+    pub const ICON: Self = Self {
+        width: Val::Px(BUTTON_HEIGHT),
+        height: Val::Px(BUTTON_HEIGHT),
+        font_size: 24.0,
+        margin: UiRect::ZERO,
+        transparent: false,
+    };
+
+    /// Links at the edges of a menu, e.g. to the project's website
+    pub const LINK: Self = Self {
+        width: Val::Px(170.0),
+        height: Val::Px(50.0),
+        font_size: 15.0,
+        margin: UiRect::ZERO,
+        transparent: true,
+    };
+
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.width = Val::Px(width);
+        self
+    }
+
+    pub fn with_margin(mut self, margin: UiRect) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    fn colors(&self) -> ButtonColors {
+        if self.transparent {
+            ButtonColors {
+                normal: Color::NONE,
+                ..default()
+            }
+        } else {
+            ButtonColors::default()
+        }
+    }
+}
+
+/// Full screen column with a menu's widgets stacked in the middle
+pub fn menu_container() -> Node {
+    Node {
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::Center,
+        justify_content: JustifyContent::Center,
+        row_gap: Val::Px(10.0),
+        ..default()
+    }
+}
+
+/// The heading at the top of a menu
+pub fn title<'a>(parent: &'a mut ChildBuilder, text: impl Into<String>) -> EntityCommands<'a> {
+    let mut title = label(parent, text, 72.0);
+    title.insert(Node {
+        margin: UiRect::bottom(Val::Px(50.0)),
+        ..default()
+    });
+    title
+}
+
+pub fn label<'a>(
+    parent: &'a mut ChildBuilder,
+    text: impl Into<String>,
+    font_size: f32,
+) -> EntityCommands<'a> {
+    parent.spawn((
+        Text::new(text),
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextColor(TEXT_COLOR),
+    ))
+}
+
+/// A focusable button with a text label, menus add a marker component to react to its presses
+pub fn button<'a>(
+    parent: &'a mut ChildBuilder,
+    text: impl Into<String>,
+    style: ButtonStyle,
+) -> EntityCommands<'a> {
+    let mut button = parent.spawn((
+        Button,
+        Node {
+            width: style.width,
+            height: style.height,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            margin: style.margin,
+            ..default()
+        },
+        BackgroundColor(style.colors().normal),
+        style.colors(),
+        Focusable,
+    ));
+    button.with_child((
+        Text::new(text),
+        TextFont {
+            font_size: style.font_size,
+            ..default()
+        },
+        TextColor(TEXT_COLOR),
+        WidgetLabel,
+    ));
+    button
+}
+
+/// A wide button with its text on the left and smaller details on the right, e.g. a level and its best time
+/// Disabled rows look the same, with greyed out text, but can't be pressed or focused
+pub fn button_row<'a>(
+    parent: &'a mut ChildBuilder,
+    text: impl Into<String>,
+    details: impl Into<String>,
+    style: ButtonStyle,
+    enabled: bool,
+) -> EntityCommands<'a> {
+    let text_color = if enabled {
+        TEXT_COLOR
+    } else {
+        DISABLED_TEXT_COLOR
+    };
+    let mut row = parent.spawn((
+        Node {
+            width: style.width,
+            height: style.height,
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            padding: UiRect::horizontal(Val::Px(15.0)),
+            margin: style.margin,
+            ..default()
+        },
+        BackgroundColor(style.colors().normal),
+    ));
+    if enabled {
+        row.insert((Button, style.colors(), Focusable));
+    }
+    row.with_children(|parent| {
+        parent.spawn((
+            Text::new(text),
+            TextFont {
+                font_size: style.font_size,
+                ..default()
+            },
+            TextColor(text_color),
+            WidgetLabel,
+        ));
+        parent.spawn((
+            Text::new(details),
+            TextFont {
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(text_color),
+        ));
+    });
+    row
+}
+
+/// A setting's label on the left and its widgets on the right
+pub fn setting_row(
+    parent: &mut ChildBuilder,
+    text: impl Into<String>,
+    widgets: impl FnOnce(&mut ChildBuilder),
+) {
+    spawn_setting_row(
+        parent,
+        text,
+        Val::Px(SETTING_ROW_HEIGHT),
+        UiRect::all(Val::Px(SETTING_ROW_MARGIN)),
+        widgets,
+    );
+}
+
+/// A [`setting_row`] that is only as high as its buttons, so long lists of settings fit
+pub fn compact_setting_row(
+    parent: &mut ChildBuilder,
+    text: impl Into<String>,
+    widgets: impl FnOnce(&mut ChildBuilder),
+) {
+    spawn_setting_row(
+        parent,
+        text,
+        Val::Px(BUTTON_HEIGHT),
+        UiRect::vertical(Val::Px(SETTING_ROW_MARGIN / 2.0)),
+        widgets,
+    );
+}
+
+fn spawn_setting_row(
+    parent: &mut ChildBuilder,
+    text: impl Into<String>,
+    height: Val,
+    margin: UiRect,
+    widgets: impl FnOnce(&mut ChildBuilder),
+) {
+    parent
+        .spawn(Node {
+            width: Val::Percent(SETTINGS_CONTAINER_WIDTH),
+            height,
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::SpaceBetween,
+            margin,
+            ..default()
+        })
+        .with_children(|parent| {
+            label(parent, text, 24.0);
+            // Several widgets are kept together on the right
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(SETTING_ROW_MARGIN),
+                    ..default()
+                })
+                .with_children(widgets);
+        });
+}
+
+/// Changes what buttons made with [`button`] say
+#[derive(SystemParam)]
+pub struct ButtonLabels<'w, 's> {
+    children: Query<'w, 's, &'static Children>,
+    labels: Query<'w, 's, &'static mut Text, With<WidgetLabel>>,
+}
+
+impl ButtonLabels<'_, '_> {
+    pub fn set(&mut self, button: Entity, text: &str) {
+        let Ok(children) = self.children.get(button) else {
+            return;
+        };
+        for child in children {
+            if let Ok(mut label) = self.labels.get_mut(*child) {
+                if label.0 != text {
+                    label.0 = text.to_string();
+                }
+            }
+        }
+    }
+}
+
+fn update_button_colors(
+    focused: Res<FocusedEntity>,
+    mut button_query: Query<(
+        Entity,
+        &Interaction,
+        &ButtonColors,
+        Has<ActiveButton>,
+        &mut BackgroundColor,
+    )>,
+) {
+    for (entity, interaction, colors, active, mut background) in &mut button_query {
+        let color = colors.color(*interaction, active, focused.0 == Some(entity));
+        background.set_if_neq(color.into());
+    }
+}
@@ -0,0 +1,88 @@
+use crate::menus::focus::FocusedEntity;
+use bevy::ecs::system::EntityCommands;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+
+/// How far one line of a mouse wheel scrolls, in logical pixels
+const LINE_HEIGHT: f32 = 20.0;
+
+/// A column that is scrolled with the mouse wheel when it is taller than its height
+/// It also follows the focus, so widgets reached with the keyboard or a gamepad are always visible
+#[derive(Component, Default)]
+#[require(ScrollPosition, RelativeCursorPosition)]
+pub struct ScrollList;
+
+pub fn scroll_list<'a>(parent: &'a mut ChildBuilder, height: Val) -> EntityCommands<'a> {
+    parent.spawn((
+        Node {
+            width: Val::Percent(100.0),
+            height,
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            overflow: Overflow::scroll_y(),
+            ..default()
+        },
+        ScrollList,
+    ))
+}
+
+// The layout keeps the offsets within the content
+pub(super) fn scroll_hovered_lists(
+    mut wheel_events: EventReader<MouseWheel>,
+    mut list_query: Query<(&RelativeCursorPosition, &mut ScrollPosition), With<ScrollList>>,
+) {
+    let delta: f32 = wheel_events
+        .read()
+        .map(|event| match event.unit {
+            MouseScrollUnit::Line => event.y * LINE_HEIGHT,
+            MouseScrollUnit::Pixel => event.y,
+        })
+        .sum();
+    if delta == 0.0 {
+        return;
+    }
+    for (cursor, mut scroll_position) in &mut list_query {
+        if cursor.mouse_over() {
+            // Turning the wheel up scrolls towards the top
+            scroll_position.offset_y = (scroll_position.offset_y - delta).max(0.0);
+        }
+    }
+}
+
+pub(super) fn scroll_to_focused(
+    focused: Res<FocusedEntity>,
+    parent_query: Query<&Parent>,
+    node_query: Query<(&ComputedNode, &GlobalTransform)>,
+    mut list_query: Query<&mut ScrollPosition, With<ScrollList>>,
+) {
+    let Some(entity) = focused.0 else {
+        return;
+    };
+    let Some(list) = parent_query
+        .iter_ancestors(entity)
+        .find(|ancestor| list_query.contains(*ancestor))
+    else {
+        return;
+    };
+    let (Ok((item_node, item_transform)), Ok((list_node, list_transform))) =
+        (node_query.get(entity), node_query.get(list))
+    else {
+        return;
+    };
+    let Ok(mut scroll_position) = list_query.get_mut(list) else {
+        return;
+    };
+
+    // Positions and sizes are in physical pixels, the offset is in logical ones
+    let item_top = item_transform.translation().y - item_node.size().y / 2.0;
+    let item_bottom = item_top + item_node.size().y;
+    let list_top = list_transform.translation().y - list_node.size().y / 2.0;
+    let list_bottom = list_top + list_node.size().y;
+    let scale = list_node.inverse_scale_factor();
+    if item_top < list_top {
+        scroll_position.offset_y -= (list_top - item_top) * scale;
+    } else if item_bottom > list_bottom {
+        scroll_position.offset_y += (item_bottom - list_bottom) * scale;
+    }
+}
@@ -0,0 +1,99 @@
+use crate::menus::widgets::{button, ButtonLabels, ButtonStyle, WidgetChanged};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+
+/// A button switching a setting on and off
+#[derive(Component, Clone, Copy, PartialEq, Debug)]
+pub struct Toggle {
+    pub on: bool,
+}
+
+impl Toggle {
+    fn label(self) -> &'static str {
+        if self.on {
+            "On"
+        } else {
+            "Off"
+        }
+    }
+}
+
+/// A button moving on to the next of its options with every press, after the last one it starts over
+#[derive(Component, Clone, PartialEq, Debug)]
+pub struct CycleSelector {
+    pub options: Vec<String>,
+    pub selected: usize,
+}
+
+impl CycleSelector {
+    pub fn new(options: Vec<String>, selected: usize) -> Self {
+        Self { options, selected }
+    }
+
+    fn label(&self) -> &str {
+        self.options.get(self.selected).map_or("", String::as_str)
+    }
+}
+
+pub fn toggle<'a>(
+    parent: &'a mut ChildBuilder,
+    on: bool,
+    style: ButtonStyle,
+) -> EntityCommands<'a> {
+    let toggle = Toggle { on };
+    let mut button = button(parent, toggle.label(), style);
+    button.insert(toggle);
+    button
+}
+
+pub fn cycle_selector<'a>(
+    parent: &'a mut ChildBuilder,
+    selector: CycleSelector,
+    style: ButtonStyle,
+) -> EntityCommands<'a> {
+    let mut button = button(parent, selector.label(), style);
+    button.insert(selector);
+    button
+}
+
+pub(super) fn press_toggles(
+    mut changed_events: EventWriter<WidgetChanged>,
+    mut toggle_query: Query<(Entity, &Interaction, &mut Toggle), Changed<Interaction>>,
+) {
+    for (entity, interaction, mut toggle) in &mut toggle_query {
+        if *interaction == Interaction::Pressed {
+            toggle.on = !toggle.on;
+            changed_events.send(WidgetChanged { entity });
+        }
+    }
+}
+
+pub(super) fn press_cycle_selectors(
+    mut changed_events: EventWriter<WidgetChanged>,
+    mut selector_query: Query<(Entity, &Interaction, &mut CycleSelector), Changed<Interaction>>,
+) {
+    for (entity, interaction, mut selector) in &mut selector_query {
+        if *interaction == Interaction::Pressed && !selector.options.is_empty() {
+            selector.selected = (selector.selected + 1) % selector.options.len();
+            changed_events.send(WidgetChanged { entity });
+        }
+    }
+}
+
+pub(super) fn update_toggle_labels(
+    toggle_query: Query<(Entity, &Toggle), Changed<Toggle>>,
+    mut labels: ButtonLabels,
+) {
+    for (entity, toggle) in &toggle_query {
+        labels.set(entity, toggle.label());
+    }
+}
+
+pub(super) fn update_cycle_selector_labels(
+    selector_query: Query<(Entity, &CycleSelector), Changed<CycleSelector>>,
+    mut labels: ButtonLabels,
+) {
+    for (entity, selector) in &selector_query {
+        labels.set(entity, selector.label());
+    }
+}
@@ -0,0 +1,176 @@
+use crate::menus::ui_components::{ButtonColors, BUTTON_HEIGHT, BUTTON_WIDTH};
+use crate::menus::widgets::{
+    button, ButtonStyle, WidgetChanged, WidgetLabel, BAR_COLOR, TEXT_COLOR,
+};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use bevy::ui::{FocusPolicy, RelativeCursorPosition};
+
+const FILL_COLOR: Color = Color::linear_rgb(0.3, 0.45, 0.6);
+
+/// A value between `min` and `max`, dragged along the bar with the mouse or changed by a step with the buttons beside it
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Slider {
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    /// The value is always a whole number of steps away from `min`
+    pub step: f32,
+    /// Shows the value on the bar
+    pub format: fn(f32) -> String,
+}
+
+impl Slider {
+    fn fraction(&self) -> f32 {
+        ((self.value - self.min) / (self.max - self.min)).clamp(0.0, 1.0)
+    }
+
+    // Rounding to whole steps also keeps repeated steps from accumulating float errors
+    fn snap(&self, value: f32) -> f32 {
+        let steps = ((value - self.min) / self.step).round();
+        (self.min + steps * self.step).clamp(self.min, self.max)
+    }
+}
+
+/// The part of a slider that is dragged
+#[derive(Component)]
+pub(super) struct SliderBar;
+
+#[derive(Component)]
+pub(super) struct SliderFill;
+
+/// Moves the slider by this many steps
+#[derive(Component)]
+pub(super) struct SliderStep(f32);
+
+/// The bar can only be dragged with the mouse, the step buttons are there for the keyboard and gamepads
+pub fn slider<'a>(parent: &'a mut ChildBuilder, slider: Slider) -> EntityCommands<'a> {
+    let text = (slider.format)(slider.value);
+    let fraction = slider.fraction();
+    let mut root = parent.spawn((
+        Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        slider,
+    ));
+    root.with_children(|parent| {
+        button(parent, "-", ButtonStyle::ICON).insert(SliderStep(-1.0));
+        parent
+            .spawn((
+                Node {
+                    width: Val::Px(BUTTON_WIDTH),
+                    height: Val::Px(BUTTON_HEIGHT),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                Interaction::default(),
+                FocusPolicy::Block,
+                RelativeCursorPosition::default(),
+                BackgroundColor(BAR_COLOR),
+                ButtonColors {
+                    normal: BAR_COLOR,
+                    ..default()
+                },
+                SliderBar,
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(0.0),
+                        width: Val::Percent(fraction * 100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(FILL_COLOR),
+                    SliderFill,
+                ));
+                parent.spawn((
+                    Text::new(text),
+                    TextFont {
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(TEXT_COLOR),
+                    WidgetLabel,
+                ));
+            });
+        button(parent, "+", ButtonStyle::ICON).insert(SliderStep(1.0));
+    });
+    root
+}
+
+pub(super) fn press_slider_steps(
+    mut changed_events: EventWriter<WidgetChanged>,
+    step_query: Query<(&Interaction, &SliderStep, &Parent), Changed<Interaction>>,
+    mut slider_query: Query<&mut Slider>,
+) {
+    for (interaction, step, parent) in &step_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let Ok(mut slider) = slider_query.get_mut(parent.get()) else {
+            continue;
+        };
+        let value = slider.snap(slider.value + step.0 * slider.step);
+        if value != slider.value {
+            slider.value = value;
+            changed_events.send(WidgetChanged {
+                entity: parent.get(),
+            });
+        }
+    }
+}
+
+// A bar stays pressed while the mouse button is held, even once the cursor left it
+pub(super) fn drag_sliders(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    mut changed_events: EventWriter<WidgetChanged>,
+    bar_query: Query<(&Interaction, &RelativeCursorPosition, &Parent), With<SliderBar>>,
+    mut slider_query: Query<&mut Slider>,
+) {
+    if !mouse_input.pressed(MouseButton::Left) {
+        return;
+    }
+    for (interaction, cursor, parent) in &bar_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        let (Some(position), Ok(mut slider)) =
+            (cursor.normalized, slider_query.get_mut(parent.get()))
+        else {
+            continue;
+        };
+        let value =
+            slider.snap(slider.min + position.x.clamp(0.0, 1.0) * (slider.max - slider.min));
+        if value != slider.value {
+            slider.value = value;
+            changed_events.send(WidgetChanged {
+                entity: parent.get(),
+            });
+        }
+    }
+}
+
+pub(super) fn update_sliders(
+    slider_query: Query<(&Slider, &Children), Changed<Slider>>,
+    bar_query: Query<&Children, With<SliderBar>>,
+    mut fill_query: Query<&mut Node, With<SliderFill>>,
+    mut label_query: Query<&mut Text, With<WidgetLabel>>,
+) {
+    for (slider, children) in &slider_query {
+        for bar_children in bar_query.iter_many(children) {
+            for child in bar_children {
+                if let Ok(mut fill) = fill_query.get_mut(*child) {
+                    fill.width = Val::Percent(slider.fraction() * 100.0);
+                }
+                if let Ok(mut label) = label_query.get_mut(*child) {
+                    label.0 = (slider.format)(slider.value);
+                }
+            }
+        }
+    }
+}
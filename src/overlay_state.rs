@@ -7,6 +7,8 @@ pub enum GameOverlayState {
     None,
     GameMenu,
     SettingsMenu,
+    SaveMenu,
+    LoadMenu,
 }
 
 #[derive(States, Default, Clone, Eq, PartialEq, Debug, Hash)]
@@ -0,0 +1,58 @@
+use crate::overlay_state::GameOverlayState;
+use crate::AppState;
+use bevy::prelude::*;
+use bevy::window::WindowFocused;
+
+pub struct PausePlugin;
+
+/// This plugin pauses the game while an overlay menu is open
+/// Gameplay systems should run with `in_state(GameplayState::Running)` instead of `in_state(AppState::InGame)`,
+/// so they stop as soon as the game is paused
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_computed_state::<GameplayState>()
+            .add_systems(OnEnter(GameplayState::Paused), pause_time)
+            .add_systems(OnExit(GameplayState::Paused), resume_time)
+            .add_systems(
+                Update,
+                pause_on_focus_lost.run_if(in_state(GameplayState::Running)),
+            );
+    }
+}
+
+/// Only exists during `AppState::InGame`
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub(crate) enum GameplayState {
+    Running,
+    Paused,
+}
+
+impl ComputedStates for GameplayState {
+    type SourceStates = (AppState, GameOverlayState);
+
+    fn compute((app_state, overlay_state): (AppState, GameOverlayState)) -> Option<Self> {
+        match (app_state, overlay_state) {
+            (AppState::InGame, GameOverlayState::None) => Some(GameplayState::Running),
+            (AppState::InGame, _) => Some(GameplayState::Paused),
+            _ => None,
+        }
+    }
+}
+
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+// Opening the game menu pauses everything else, and the player finds it open when coming back
+fn pause_on_focus_lost(
+    mut focus_events: EventReader<WindowFocused>,
+    mut next_overlay_state: ResMut<NextState<GameOverlayState>>,
+) {
+    if focus_events.read().any(|event| !event.focused) {
+        next_overlay_state.set(GameOverlayState::GameMenu);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::pause::GameplayState;
+use bevy::math::Vec3Swizzles;
+use bevy::prelude::*;
+
+/// How often overlaps are resolved per step, so bodies pushed into a second obstacle get out of it too
+const COLLISION_ITERATIONS: usize = 4;
+
+pub struct PhysicsPlugin;
+
+/// This plugin moves every entity with a [`Velocity`] in fixed time steps and keeps bodies with a [`Collider`]
+/// out of [`Obstacle`]s, so they slide along walls instead of passing through them
+/// Systems changing velocities should run in `FixedUpdate` before the [`PhysicsSystem`] set
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Collider>()
+            .register_type::<Obstacle>()
+            .add_systems(
+                FixedUpdate,
+                (move_bodies, resolve_collisions)
+                    .chain()
+                    .in_set(PhysicsSystem)
+                    .run_if(in_state(GameplayState::Running)),
+            );
+    }
+}
+
+/// Integrates velocities and resolves collisions
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PhysicsSystem;
+
+/// Movement in world units per second
+#[derive(Component, Default, Clone, Copy, PartialEq, Debug)]
+pub struct Velocity(pub Vec2);
+
+/// The shape of an entity, centered on its translation
+/// Rotation and scale are ignored, so boxes always stay axis aligned
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Debug)]
+#[reflect(Component, Default)]
+pub enum Collider {
+    Aabb { half_size: Vec2 },
+    Circle { radius: f32 },
+}
+
+impl Default for Collider {
+    fn default() -> Self {
+        Collider::Circle { radius: 16.0 }
+    }
+}
+
+impl Collider {
+    /// How far the shape at `position` has to move to stop overlapping `other` at `other_position`
+    pub fn penetration(
+        self,
+        position: Vec2,
+        other: Collider,
+        other_position: Vec2,
+    ) -> Option<Vec2> {
+        match (self, other) {
+            (
+                Collider::Circle { radius },
+                Collider::Circle {
+                    radius: other_radius,
+                },
+            ) => {
+                let distance = position - other_position;
+                let depth = radius + other_radius - distance.length();
+                (depth > 0.0).then(|| distance.try_normalize().unwrap_or(Vec2::Y) * depth)
+            }
+            (Collider::Circle { radius }, Collider::Aabb { half_size }) => {
+                circle_out_of_box(position, radius, other_position, half_size)
+            }
+            (Collider::Aabb { half_size }, Collider::Circle { radius }) => {
+                circle_out_of_box(other_position, radius, position, half_size).map(|push| -push)
+            }
+            (
+                Collider::Aabb { half_size },
+                Collider::Aabb {
+                    half_size: other_half_size,
+                },
+            ) => {
+                let distance = position - other_position;
+                let overlap = half_size + other_half_size - distance.abs();
+                if overlap.x <= 0.0 || overlap.y <= 0.0 {
+                    return None;
+                }
+                // Out along the axis that needs the shorter push
+                Some(if overlap.x < overlap.y {
+                    Vec2::new(overlap.x.copysign(distance.x), 0.0)
+                } else {
+                    Vec2::new(0.0, overlap.y.copysign(distance.y))
+                })
+            }
+        }
+    }
+}
+
+/// How far a circle has to move to stop overlapping a box
+fn circle_out_of_box(center: Vec2, radius: f32, box_center: Vec2, half_size: Vec2) -> Option<Vec2> {
+    let local = center - box_center;
+    let closest = local.clamp(-half_size, half_size);
+    if closest != local {
+        let distance = local - closest;
+        let depth = radius - distance.length();
+        return (depth > 0.0).then(|| distance.normalize() * depth);
+    }
+    // The center is inside the box, leave through the closest side
+    let to_side = half_size - local.abs();
+    Some(if to_side.x < to_side.y {
+        Vec2::new((to_side.x + radius).copysign(local.x), 0.0)
+    } else {
+        Vec2::new(0.0, (to_side.y + radius).copysign(local.y))
+    })
+}
+
+/// Static collider that bodies with a [`Velocity`] can't move into
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+#[require(Collider)]
+pub struct Obstacle;
+
+fn move_bodies(time: Res<Time>, mut bodies: Query<(&mut Transform, &Velocity)>) {
+    for (mut transform, velocity) in &mut bodies {
+        transform.translation += (velocity.0 * time.delta_secs()).extend(0.0);
+    }
+}
+
+fn resolve_collisions(
+    obstacles: Query<(&Transform, &Collider), (With<Obstacle>, Without<Velocity>)>,
+    mut bodies: Query<(&mut Transform, &mut Velocity, &Collider), Without<Obstacle>>,
+) {
+    for (mut transform, mut velocity, collider) in &mut bodies {
+        for _ in 0..COLLISION_ITERATIONS {
+            let mut resolved = true;
+            for (obstacle_transform, obstacle_collider) in &obstacles {
+                let Some(push) = collider.penetration(
+                    transform.translation.xy(),
+                    *obstacle_collider,
+                    obstacle_transform.translation.xy(),
+                ) else {
+                    continue;
+                };
+                transform.translation += push.extend(0.0);
+                // Keep only the movement along the obstacle, so the body slides instead of sticking to it
+                if let Some(normal) = push.try_normalize() {
+                    let into_obstacle = velocity.0.dot(normal);
+                    if into_obstacle < 0.0 {
+                        velocity.0 -= normal * into_obstacle;
+                    }
+                }
+                resolved = false;
+            }
+            if resolved {
+                break;
+            }
+        }
+    }
+}
@@ -1,47 +1,209 @@
-use crate::actions::Actions;
+use crate::actions::{Action, ActionState};
+use crate::animation::{SpriteAnimationSystem, SpriteAnimator};
+use crate::camera::{CameraShake, CameraTarget};
 use crate::gameplay::GameplayEntity;
-use crate::loading::TextureAssets;
+use crate::health::{Damaged, Died, Health, HealthSystem, Team};
+use crate::level::{LevelSpawnSystem, PlayerStart};
+use crate::loading::{AnimationAssets, TextureAssets};
+use crate::pause::GameplayState;
+use crate::physics::{Collider, PhysicsSystem, Velocity};
 use crate::AppState;
 use bevy::prelude::*;
+use serde::Deserialize;
+
+/// How much the camera shakes when the player gets hurt
+const HIT_TRAUMA: f32 = 0.5;
 
 pub struct PlayerPlugin;
 
-#[derive(Component)]
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+#[require(CameraTarget, Velocity, MovementStats, Collider, Health, Team(|| Team::Player))]
 pub struct Player;
 
-/// This plugin handles player related stuff like movement
-/// Player logic is only active during the State `AppState::InGame`
+/// How quickly the player, or an enemy, gets going and comes to a stop again
+#[derive(Component, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct MovementStats {
+    /// In world units per second
+    pub max_speed: f32,
+    /// Speed gained per second while moving
+    pub acceleration: f32,
+    /// Speed lost per second without movement input
+    pub deceleration: f32,
+}
+
+impl Default for MovementStats {
+    fn default() -> Self {
+        Self {
+            max_speed: 150.0,
+            acceleration: 1200.0,
+            deceleration: 900.0,
+        }
+    }
+}
+
+/// Which of the player's clips is playing
+#[derive(Component, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlayerAnimationState {
+    #[default]
+    Idle,
+    Move,
+    /// Played once when the player changes its horizontal direction
+    Turn,
+}
+
+impl PlayerAnimationState {
+    /// Name of the clip in `animations/player.anim.ron`
+    pub fn clip(self) -> &'static str {
+        match self {
+            PlayerAnimationState::Idle => "idle",
+            PlayerAnimationState::Move => "move",
+            PlayerAnimationState::Turn => "turn",
+        }
+    }
+}
+
+/// This plugin handles player related stuff like movement and animation
+/// Movement input only changes the player's velocity, the `PhysicsPlugin` moves it
+/// Player logic is only active during the State `AppState::InGame` and stops while the game is paused
+/// Getting hurt shakes the camera, the game is over once the player died, continuing in `AppState::GameOver`
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::InGame), spawn_player)
-            .add_systems(Update, move_player.run_if(in_state(AppState::InGame)));
+        app.register_type::<Player>()
+            .add_systems(
+                OnEnter(AppState::InGame),
+                spawn_player.after(LevelSpawnSystem),
+            )
+            .add_systems(Update, add_player_sprite.run_if(in_state(AppState::InGame)))
+            .add_systems(
+                Update,
+                (shake_camera_on_hit, end_game_on_death)
+                    .after(HealthSystem)
+                    .run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(
+                Update,
+                animate_player
+                    .before(SpriteAnimationSystem)
+                    .run_if(in_state(GameplayState::Running)),
+            )
+            .add_systems(
+                FixedUpdate,
+                accelerate_player
+                    .before(PhysicsSystem)
+                    .run_if(in_state(GameplayState::Running)),
+            );
     }
 }
 
-fn spawn_player(mut commands: Commands, textures: Res<TextureAssets>) {
+fn spawn_player(mut commands: Commands, start_query: Query<&Transform, With<PlayerStart>>) {
+    let start = start_query
+        .iter()
+        .next()
+        .map_or(Vec2::ZERO, |transform| transform.translation.truncate());
     commands.spawn((
-        Sprite::from_image(textures.bevy.clone()),
-        Transform::from_translation(Vec3::new(0., 0., 1.)),
+        Transform::from_translation(start.extend(1.)),
         Player,
         GameplayEntity,
     ));
 }
 
-fn move_player(
+// Sprites are not part of save games, so this also covers players restored from a save
+fn add_player_sprite(
+    mut commands: Commands,
+    textures: Res<TextureAssets>,
+    animations: Res<AnimationAssets>,
+    player_query: Query<Entity, (With<Player>, Without<Sprite>)>,
+) {
+    for entity in &player_query {
+        let state = PlayerAnimationState::default();
+        // A save loaded in the same frame may have replaced the player already
+        commands.entity(entity).try_insert((
+            Sprite::from_atlas_image(
+                textures.player.clone(),
+                TextureAtlas {
+                    layout: textures.player_layout.clone(),
+                    index: 0,
+                },
+            ),
+            SpriteAnimator::new(animations.player.clone(), state.clip()),
+            state,
+        ));
+    }
+}
+
+// The sprite sheet faces right, so facing left flips the sprite
+fn animate_player(
+    action_state: Res<ActionState>,
+    mut player_query: Query<
+        (&mut PlayerAnimationState, &mut SpriteAnimator, &mut Sprite),
+        With<Player>,
+    >,
+) {
+    let direction = action_state.axis(Action::Move);
+    for (mut state, mut animator, mut sprite) in &mut player_query {
+        let facing_left = if direction.x == 0.0 {
+            sprite.flip_x
+        } else {
+            direction.x < 0.0
+        };
+        let next_state = if facing_left != sprite.flip_x {
+            sprite.flip_x = facing_left;
+            // Turning back mid-turn starts the turn over
+            animator.restart(PlayerAnimationState::Turn.clip());
+            PlayerAnimationState::Turn
+        } else if *state == PlayerAnimationState::Turn && !animator.is_finished() {
+            PlayerAnimationState::Turn
+        } else if direction == Vec2::ZERO {
+            PlayerAnimationState::Idle
+        } else {
+            PlayerAnimationState::Move
+        };
+        state.set_if_neq(next_state);
+        animator.play(next_state.clip());
+    }
+}
+
+fn accelerate_player(
     time: Res<Time>,
-    actions: Res<Actions>,
-    mut player_query: Query<&mut Transform, With<Player>>,
+    action_state: Res<ActionState>,
+    mut player_query: Query<(&mut Velocity, &MovementStats), With<Player>>,
+) {
+    let direction = action_state.axis(Action::Move);
+    for (mut velocity, stats) in &mut player_query {
+        // Partial stick deflections result in a lower top speed
+        let (target, rate) = if direction == Vec2::ZERO {
+            (Vec2::ZERO, stats.deceleration)
+        } else {
+            (direction * stats.max_speed, stats.acceleration)
+        };
+        velocity.0 = velocity.0.move_towards(target, rate * time.delta_secs());
+    }
+}
+
+fn shake_camera_on_hit(
+    mut damaged_events: EventReader<Damaged>,
+    mut shake_events: EventWriter<CameraShake>,
+    player_query: Query<(), With<Player>>,
+) {
+    if damaged_events
+        .read()
+        .any(|damaged| player_query.contains(damaged.entity))
+    {
+        shake_events.send(CameraShake { trauma: HIT_TRAUMA });
+    }
+}
+
+fn end_game_on_death(
+    mut died_events: EventReader<Died>,
+    mut next_state: ResMut<NextState<AppState>>,
+    player_query: Query<(), With<Player>>,
 ) {
-    if actions.player_movement.is_none() {
-        return;
-    }
-    let speed = 150.;
-    let movement = Vec3::new(
-        actions.player_movement.unwrap().x * speed * time.delta_secs(),
-        actions.player_movement.unwrap().y * speed * time.delta_secs(),
-        0.,
-    );
-    for mut player_transform in &mut player_query {
-        player_transform.translation += movement;
+    if died_events
+        .read()
+        .any(|died| player_query.contains(died.entity))
+    {
+        next_state.set(AppState::GameOver);
     }
 }
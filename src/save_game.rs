@@ -0,0 +1,324 @@
+use crate::enemies::{Enemy, EnemySpawner, SpawnedBy};
+use crate::gameplay::GameplayEntity;
+use crate::health::Health;
+use crate::level::{CurrentLevel, LevelEntity, LevelSpawnSystem};
+use crate::physics::{Collider, Obstacle};
+use crate::player::Player;
+use crate::score::{Pickup, Score};
+use crate::storage;
+use crate::AppState;
+use bevy::ecs::entity::EntityHashMap;
+use bevy::prelude::*;
+use bevy::reflect::TypeRegistry;
+use bevy::scene::serde::SceneDeserializer;
+use bevy::scene::SceneFilter;
+use serde::de::DeserializeSeed;
+use serde::{Deserialize, Serialize};
+use std::{fmt, io};
+
+pub const SAVE_SLOT_COUNT: usize = 3;
+
+/// Bump this whenever a saved component changes its layout
+/// Saves don't get migrated, so saves from other versions are reported as incompatible
+pub const SAVE_VERSION: u32 = 2;
+
+pub struct SaveGamePlugin;
+
+/// This plugin stores the running game session in numbered slots and restores it again
+/// Saving is requested with a [`SaveGameRequest`], loading by inserting a [`PendingLoad`]
+/// (the game is entered first, if the load was started from the main menu)
+impl Plugin for SaveGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SaveGameRequest>()
+            .init_resource::<SaveGameStatus>()
+            .add_systems(
+                Update,
+                apply_pending_load
+                    .before(LevelSpawnSystem)
+                    .run_if(resource_exists::<PendingLoad>.and(in_state(AppState::InGame))),
+            )
+            // Saving after `Update` stores the state of the frame in which the save was requested
+            .add_systems(
+                PostUpdate,
+                save_requested_slots.run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(OnExit(AppState::InGame), clear_save_game_status);
+    }
+}
+
+/// The components of [`GameplayEntity`]s that end up in a save game
+/// Everything else (e.g. sprites) has to be added back by the owning plugin after loading
+fn saved_components() -> SceneFilter {
+    SceneFilter::deny_all()
+        .allow::<GameplayEntity>()
+        .allow::<Player>()
+        .allow::<Enemy>()
+        .allow::<EnemySpawner>()
+        .allow::<SpawnedBy>()
+        .allow::<Health>()
+        .allow::<Pickup>()
+        .allow::<Obstacle>()
+        .allow::<Collider>()
+        .allow::<Transform>()
+}
+
+/// The entities that end up in a save game, and are replaced when loading one
+/// The level is spawned from its map again and UI like the HUD is rebuilt by its plugin
+type SavedEntities = (With<GameplayEntity>, Without<LevelEntity>, Without<Node>);
+
+/// The resources that end up in a save game, they replace the running ones when loading
+fn saved_resources() -> SceneFilter {
+    SceneFilter::deny_all()
+        .allow::<CurrentLevel>()
+        .allow::<Score>()
+}
+
+#[derive(Event)]
+pub struct SaveGameRequest {
+    pub slot: usize,
+}
+
+/// A save game that was read successfully and replaces the running session in the next frame
+#[derive(Resource)]
+pub struct PendingLoad(pub DynamicScene);
+
+/// The outcome of the last save or load, for the menus to show
+#[derive(Resource, Default)]
+pub struct SaveGameStatus(pub Option<String>);
+
+#[derive(Serialize, Deserialize)]
+struct SaveFile {
+    version: u32,
+    // Increases with every save, so the most recent slot can be found without a clock
+    sequence: u64,
+    checksum: u64,
+    scene: String,
+}
+
+#[derive(Debug)]
+pub enum SaveGameError {
+    Empty,
+    Storage(io::Error),
+    Parse(ron::error::SpannedError),
+    UnsupportedVersion(u32),
+    ChecksumMismatch,
+    Scene(String),
+}
+
+impl fmt::Display for SaveGameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveGameError::Empty => write!(f, "the slot is empty"),
+            SaveGameError::Storage(error) => write!(f, "storage error: {error}"),
+            SaveGameError::Parse(error) => write!(f, "invalid save file: {error}"),
+            SaveGameError::UnsupportedVersion(version) => write!(
+                f,
+                "save file version {version} is not compatible with version {SAVE_VERSION}"
+            ),
+            SaveGameError::ChecksumMismatch => write!(f, "save file is corrupt"),
+            SaveGameError::Scene(error) => write!(f, "invalid save game content: {error}"),
+        }
+    }
+}
+
+impl From<ron::error::SpannedError> for SaveGameError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        SaveGameError::Parse(error)
+    }
+}
+
+impl From<io::Error> for SaveGameError {
+    fn from(error: io::Error) -> Self {
+        SaveGameError::Storage(error)
+    }
+}
+
+pub enum SlotSummary {
+    Empty,
+    Saved { sequence: u64 },
+    // The reason is reported when trying to load the slot
+    Unreadable,
+}
+
+impl SlotSummary {
+    pub fn label(&self) -> &'static str {
+        match self {
+            SlotSummary::Empty => "Empty",
+            SlotSummary::Saved { .. } => "Saved",
+            SlotSummary::Unreadable => "Unreadable",
+        }
+    }
+}
+
+pub(crate) fn slot_key(slot: usize) -> String {
+    format!("save_slot_{}.ron", slot + 1)
+}
+
+// FNV-1a, only meant to detect damaged or hand edited files
+fn checksum(contents: &str) -> u64 {
+    contents.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn read_save_file(slot: usize) -> Result<SaveFile, SaveGameError> {
+    let contents = storage::read(&slot_key(slot)).ok_or(SaveGameError::Empty)?;
+    let version = storage::ron_version(&contents)?;
+    if version != SAVE_VERSION {
+        return Err(SaveGameError::UnsupportedVersion(version));
+    }
+    let save_file: SaveFile = ron::from_str(&contents)?;
+    if checksum(&save_file.scene) != save_file.checksum {
+        return Err(SaveGameError::ChecksumMismatch);
+    }
+    Ok(save_file)
+}
+
+pub fn slot_summary(slot: usize) -> SlotSummary {
+    match read_save_file(slot) {
+        Ok(save_file) => SlotSummary::Saved {
+            sequence: save_file.sequence,
+        },
+        Err(SaveGameError::Empty) => SlotSummary::Empty,
+        Err(_) => SlotSummary::Unreadable,
+    }
+}
+
+/// The slot that was saved to last, if there is any readable save
+pub fn latest_slot() -> Option<usize> {
+    (0..SAVE_SLOT_COUNT)
+        .filter_map(|slot| match slot_summary(slot) {
+            SlotSummary::Saved { sequence } => Some((slot, sequence)),
+            _ => None,
+        })
+        .max_by_key(|(_, sequence)| *sequence)
+        .map(|(slot, _)| slot)
+}
+
+pub fn read_slot(slot: usize, type_registry: &TypeRegistry) -> Result<DynamicScene, SaveGameError> {
+    let save_file = read_save_file(slot)?;
+    let mut deserializer = ron::de::Deserializer::from_str(&save_file.scene)?;
+    SceneDeserializer { type_registry }
+        .deserialize(&mut deserializer)
+        .map_err(|error| SaveGameError::Scene(error.to_string()))
+}
+
+fn write_slot(world: &mut World, slot: usize) -> Result<(), SaveGameError> {
+    // The level is spawned from its map again, only which one it was is saved
+    let entities: Vec<Entity> = world
+        .query_filtered::<Entity, SavedEntities>()
+        .iter(world)
+        .collect();
+    let scene = DynamicSceneBuilder::from_world(world)
+        .with_component_filter(saved_components())
+        .with_resource_filter(saved_resources())
+        .extract_entities(entities.into_iter())
+        .extract_resources()
+        .build();
+    let scene = scene
+        .serialize(&world.resource::<AppTypeRegistry>().read())
+        .map_err(|error| SaveGameError::Scene(error.to_string()))?;
+
+    let sequence = (0..SAVE_SLOT_COUNT)
+        .filter_map(|slot| match slot_summary(slot) {
+            SlotSummary::Saved { sequence } => Some(sequence),
+            _ => None,
+        })
+        .max()
+        .map_or(0, |sequence| sequence + 1);
+    let save_file = SaveFile {
+        version: SAVE_VERSION,
+        sequence,
+        checksum: checksum(&scene),
+        scene,
+    };
+    let contents = ron::ser::to_string_pretty(&save_file, ron::ser::PrettyConfig::default())
+        .map_err(|error| SaveGameError::Scene(error.to_string()))?;
+    storage::write(&slot_key(slot), &contents)?;
+    Ok(())
+}
+
+fn save_requested_slots(world: &mut World) {
+    let requests: Vec<SaveGameRequest> = world
+        .resource_mut::<Events<SaveGameRequest>>()
+        .drain()
+        .collect();
+    for request in requests {
+        let message = match write_slot(world, request.slot) {
+            Ok(()) => format!("Saved to slot {}", request.slot + 1),
+            Err(error) => {
+                warn!("Failed to save to slot {}: {error}", request.slot + 1);
+                format!("Could not save to slot {}: {error}", request.slot + 1)
+            }
+        };
+        world.resource_mut::<SaveGameStatus>().0 = Some(message);
+    }
+}
+
+fn apply_pending_load(world: &mut World) {
+    let Some(PendingLoad(scene)) = world.remove_resource::<PendingLoad>() else {
+        return;
+    };
+    // Checked before anything is despawned, so a save that can't be restored leaves the session running
+    let checked = check_scene(&scene, &world.resource::<AppTypeRegistry>().read());
+    if let Err(error) = checked {
+        warn!("Failed to restore save game: {error}");
+        world.resource_mut::<SaveGameStatus>().0 =
+            Some(format!("Could not restore save game: {error}"));
+        return;
+    }
+    // The level isn't part of the save, it is respawned if the save was made in another one
+    let entities: Vec<Entity> = world
+        .query_filtered::<Entity, SavedEntities>()
+        .iter(world)
+        .collect();
+    // Children are often saved entities too and may already be gone with their parent
+    for entity in entities {
+        if let Ok(entity) = world.get_entity_mut(entity) {
+            entity.despawn_recursive();
+        }
+    }
+    if let Err(error) = scene.write_to_world(world, &mut EntityHashMap::default()) {
+        warn!("Failed to restore save game: {error}");
+        world.resource_mut::<SaveGameStatus>().0 =
+            Some(format!("Could not restore save game: {error}"));
+    }
+}
+
+/// Finds the components and resources of a scene that `DynamicScene::write_to_world` would fail on
+fn check_scene(scene: &DynamicScene, type_registry: &TypeRegistry) -> Result<(), SaveGameError> {
+    let components = scene.entities.iter().flat_map(|entity| &entity.components);
+    for component in components {
+        let registration = component
+            .get_represented_type_info()
+            .and_then(|type_info| type_registry.get(type_info.type_id()));
+        if registration
+            .and_then(|registration| registration.data::<ReflectComponent>())
+            .is_none()
+        {
+            return Err(SaveGameError::Scene(format!(
+                "unknown component `{}`",
+                component.reflect_type_path()
+            )));
+        }
+    }
+    for resource in &scene.resources {
+        let registration = resource
+            .get_represented_type_info()
+            .and_then(|type_info| type_registry.get(type_info.type_id()));
+        if registration
+            .and_then(|registration| registration.data::<ReflectResource>())
+            .is_none()
+        {
+            return Err(SaveGameError::Scene(format!(
+                "unknown resource `{}`",
+                resource.reflect_type_path()
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn clear_save_game_status(mut status: ResMut<SaveGameStatus>) {
+    status.0 = None;
+}
@@ -0,0 +1,61 @@
+use crate::storage;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+const HIGH_SCORES_KEY: &str = "high_scores.ron";
+/// Only the best runs are kept
+const MAX_HIGH_SCORES: usize = 10;
+
+/// The best runs, stored on disk across sessions
+#[derive(Resource, Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct HighScores {
+    /// Best first
+    pub entries: Vec<HighScore>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct HighScore {
+    pub name: String,
+    pub points: u32,
+    /// Id of the level the run was played in
+    pub level: String,
+}
+
+/// A finished run that made it into the [`HighScores`] and waits for the player's name
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct PendingHighScore {
+    pub points: u32,
+    pub level: String,
+}
+
+impl HighScores {
+    /// Loads the stored high scores, starting over if there are none or they can't be read
+    pub fn load() -> Self {
+        storage::load_ron(HIGH_SCORES_KEY, "high scores")
+    }
+
+    pub fn save(&self) {
+        storage::save_ron(HIGH_SCORES_KEY, "high scores", self);
+    }
+
+    /// Whether a run with these points gets onto the table
+    pub fn qualifies(&self, points: u32) -> bool {
+        points > 0
+            && (self.entries.len() < MAX_HIGH_SCORES
+                || self.entries.last().is_some_and(|last| points > last.points))
+    }
+
+    /// Returns the entry's rank, starting at one
+    /// Ties are ranked below the entries that were there first
+    pub fn insert(&mut self, entry: HighScore) -> usize {
+        let index = self
+            .entries
+            .iter()
+            .position(|existing| existing.points < entry.points)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(index, entry);
+        self.entries.truncate(MAX_HIGH_SCORES);
+        index + 1
+    }
+}
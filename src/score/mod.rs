@@ -0,0 +1,126 @@
+use crate::level::CurrentLevel;
+use crate::pause::GameplayState;
+use crate::AppState;
+use bevy::prelude::*;
+
+mod high_scores;
+mod pickup;
+
+pub use high_scores::{HighScore, HighScores, PendingHighScore};
+pub use pickup::{spawn_pickup, Pickup};
+
+/// Pickups collected within this many seconds of each other build up a combo
+const COMBO_SECONDS: f32 = 2.0;
+/// The combo stops raising the multiplier at this point
+const MAX_MULTIPLIER: u32 = 5;
+
+pub struct ScorePlugin;
+
+/// This plugin keeps the [`Score`] of a run, awarded for collecting [`Pickup`]s
+/// A run scoring high enough for the [`HighScores`] leaves a [`PendingHighScore`] for the menus
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Score>()
+            .register_type::<Pickup>()
+            .init_resource::<Score>()
+            .insert_resource(HighScores::load())
+            .add_systems(OnEnter(AppState::InGame), start_run)
+            .add_systems(
+                Update,
+                (
+                    pickup::add_pickup_sprites.run_if(in_state(AppState::InGame)),
+                    (tick_combo, pickup::collect_pickups)
+                        .chain()
+                        .run_if(in_state(GameplayState::Running)),
+                ),
+            )
+            .add_systems(
+                OnEnter(AppState::LevelComplete),
+                end_run.in_set(RunEndSystem),
+            )
+            .add_systems(OnEnter(AppState::GameOver), end_run.in_set(RunEndSystem));
+    }
+}
+
+/// Decides whether the run made it into the [`HighScores`], menus asking for a name should run after it
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct RunEndSystem;
+
+/// Points of the running game, they are part of save games
+#[derive(Resource, Reflect, Default, Clone, PartialEq, Debug)]
+#[reflect(Resource, Default)]
+pub struct Score {
+    pub points: u32,
+    /// Pickups collected in quick succession
+    pub combo: u32,
+    /// Seconds left to keep the combo going
+    pub combo_time: f32,
+}
+
+impl Score {
+    /// Multiplies the points of the next pickup, if it keeps the combo going
+    pub fn multiplier(&self) -> u32 {
+        self.combo.clamp(1, MAX_MULTIPLIER)
+    }
+
+    /// Adds the points of a pickup and returns how many were awarded with the combo
+    pub fn award(&mut self, points: u32) -> u32 {
+        self.combo = if self.combo_time > 0.0 {
+            self.combo + 1
+        } else {
+            1
+        };
+        self.combo_time = COMBO_SECONDS;
+        let awarded = points.saturating_mul(self.multiplier());
+        self.points = self.points.saturating_add(awarded);
+        awarded
+    }
+}
+
+// A save loaded after entering the game brings back the score it was made with
+fn start_run(mut commands: Commands) {
+    commands.insert_resource(Score::default());
+    commands.remove_resource::<PendingHighScore>();
+}
+
+fn tick_combo(time: Res<Time>, mut score: ResMut<Score>) {
+    if score.combo_time <= 0.0 {
+        return;
+    }
+    score.combo_time -= time.delta_secs();
+    if score.combo_time <= 0.0 {
+        score.combo_time = 0.0;
+        score.combo = 0;
+    }
+}
+
+fn end_run(
+    mut commands: Commands,
+    score: Res<Score>,
+    current_level: Res<CurrentLevel>,
+    high_scores: Res<HighScores>,
+) {
+    if high_scores.qualifies(score.points) {
+        commands.insert_resource(PendingHighScore {
+            points: score.points,
+            level: current_level.id.clone(),
+        });
+    } else {
+        commands.remove_resource::<PendingHighScore>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn huge_pickups_saturate_the_score() {
+        let mut score = Score::default();
+        assert_eq!(score.award(u32::MAX), u32::MAX);
+        // The second pickup keeps the combo going and doubles the points
+        assert_eq!(score.award(u32::MAX), u32::MAX);
+        assert_eq!(score.points, u32::MAX);
+        assert_eq!(score.combo, 2);
+    }
+}
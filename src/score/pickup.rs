@@ -0,0 +1,103 @@
+use crate::audio::PlaySfx;
+use crate::gameplay::GameplayEntity;
+use crate::loading::AudioAssets;
+use crate::physics::Collider;
+use crate::player::Player;
+use crate::score::Score;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Pickups worth at least this much are shown as gems instead of coins
+const GEM_POINTS: u32 = 50;
+
+/// Awards its points to the player touching its [`Collider`], and is gone afterwards
+#[derive(Component, Reflect, Clone, Copy, PartialEq, Debug)]
+#[reflect(Component, Default)]
+#[require(Collider)]
+pub struct Pickup {
+    pub points: u32,
+}
+
+impl Default for Pickup {
+    fn default() -> Self {
+        Self { points: 10 }
+    }
+}
+
+impl Pickup {
+    /// Reads the points from a level object's `points` property
+    pub fn from_properties(properties: &HashMap<String, String>) -> Self {
+        match properties.get("points").map(|points| points.parse()) {
+            Some(Ok(points)) => Self { points },
+            Some(Err(_)) => {
+                warn!("Invalid pickup points: {:?}", properties["points"]);
+                Self::default()
+            }
+            None => Self::default(),
+        }
+    }
+}
+
+/// Spawns a pickup covering the area
+/// Pickups are [`GameplayEntity`]s of their own instead of level entities, so save games remember which were collected
+pub fn spawn_pickup(commands: &mut Commands, name: &str, pickup: Pickup, area: Rect) -> Entity {
+    commands
+        .spawn((
+            Name::new(name.to_string()),
+            pickup,
+            Collider::Aabb {
+                half_size: area.half_size(),
+            },
+            Transform::from_translation(area.center().extend(0.5)),
+            GameplayEntity,
+        ))
+        .id()
+}
+
+// Sprites are not part of save games, so this also covers pickups restored from a save
+pub(super) fn add_pickup_sprites(
+    mut commands: Commands,
+    pickup_query: Query<(Entity, &Pickup, &Collider), Without<Sprite>>,
+) {
+    for (entity, pickup, collider) in &pickup_query {
+        let size = match *collider {
+            Collider::Aabb { half_size } => half_size * 2.0,
+            Collider::Circle { radius } => Vec2::splat(radius * 2.0),
+        };
+        let color = if pickup.points >= GEM_POINTS {
+            Color::srgb(0.3, 0.85, 0.95)
+        } else {
+            Color::srgb(0.95, 0.8, 0.2)
+        };
+        commands
+            .entity(entity)
+            .try_insert(Sprite::from_color(color, size));
+    }
+}
+
+pub(super) fn collect_pickups(
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    audio_assets: Res<AudioAssets>,
+    mut sfx_events: EventWriter<PlaySfx>,
+    player_query: Query<(&Transform, &Collider), With<Player>>,
+    pickup_query: Query<(Entity, &Pickup, &Transform, &Collider)>,
+) {
+    for (player, player_collider) in &player_query {
+        for (entity, pickup, transform, collider) in &pickup_query {
+            let touching = player_collider
+                .penetration(
+                    player.translation.truncate(),
+                    *collider,
+                    transform.translation.truncate(),
+                )
+                .is_some();
+            if !touching {
+                continue;
+            }
+            score.award(pickup.points);
+            sfx_events.send(PlaySfx(audio_assets.pickup.clone()));
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use crate::actions::{GamepadInputSettings, KeyBindings, TouchControlSettings};
+use crate::audio::AudioSettings;
+use crate::menus::settings::NewDisplaySettings;
+use crate::storage;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const SETTINGS_KEY: &str = "settings.ron";
+
+/// Bump this whenever the meaning of a stored field changes and add a step to [`SettingsFile::migrate`]
+/// New fields don't need a bump, since missing fields fall back to their defaults
+pub const SETTINGS_VERSION: u32 = 1;
+
+pub struct SettingsFilePlugin;
+
+/// This plugin makes the settings stored on disk available as the [`SettingsFile`] resource
+/// The game binary loads the file itself before creating the primary window and inserts it,
+/// in all other cases (e.g. mobile) it's loaded here
+impl Plugin for SettingsFilePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world().contains_resource::<SettingsFile>() {
+            app.insert_resource(SettingsFile::load());
+        }
+    }
+}
+
+/// The settings that were last applied by the player
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct SettingsFile {
+    pub version: u32,
+    pub display: NewDisplaySettings,
+    pub audio: AudioSettings,
+    pub controls: KeyBindings,
+    pub gamepad: GamepadInputSettings,
+    pub touch: TouchControlSettings,
+    /// Whether these were read from disk, rather than being the defaults of a first launch
+    #[serde(skip)]
+    pub loaded: bool,
+}
+
+impl Default for SettingsFile {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            display: NewDisplaySettings::default(),
+            audio: AudioSettings::default(),
+            controls: KeyBindings::default(),
+            gamepad: GamepadInputSettings::default(),
+            touch: TouchControlSettings::default(),
+            loaded: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SettingsFileError {
+    Parse(ron::error::SpannedError),
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SettingsFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsFileError::Parse(error) => write!(f, "invalid settings file: {error}"),
+            SettingsFileError::UnsupportedVersion(version) => write!(
+                f,
+                "settings file version {version} is newer than the supported version {SETTINGS_VERSION}"
+            ),
+        }
+    }
+}
+
+impl From<ron::error::SpannedError> for SettingsFileError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        SettingsFileError::Parse(error)
+    }
+}
+
+impl SettingsFile {
+    /// Loads the stored settings, falling back to the defaults if there are none or they can't be read
+    /// Unreadable files are kept as a copy, so the next save doesn't destroy them
+    pub fn load() -> Self {
+        storage::load_with(SETTINGS_KEY, "settings", Self::parse)
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, SettingsFileError> {
+        let version = storage::ron_version(contents)?;
+        if version > SETTINGS_VERSION {
+            return Err(SettingsFileError::UnsupportedVersion(version));
+        }
+        let mut settings: SettingsFile = ron::from_str(contents)?;
+        settings.loaded = true;
+        Ok(settings.migrate(version))
+    }
+
+    /// Upgrades settings written with an older schema to [`SETTINGS_VERSION`]
+    fn migrate(mut self, from_version: u32) -> Self {
+        if from_version < SETTINGS_VERSION {
+            info!("Migrating settings from version {from_version} to {SETTINGS_VERSION}");
+        }
+        self.version = SETTINGS_VERSION;
+        self
+    }
+
+    pub fn save(&self) {
+        storage::save_ron(SETTINGS_KEY, "settings", self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_settings_fall_back_to_the_defaults() {
+        let settings = SettingsFile::load();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.audio, AudioSettings::default());
+        assert!(!settings.loaded);
+        assert_eq!(storage::read(&storage::corrupt_key(SETTINGS_KEY)), None);
+    }
+
+    #[test]
+    fn settings_of_an_older_version_are_migrated() {
+        // Files written before the version was stored count as version 0
+        storage::write(SETTINGS_KEY, "(audio: (master: 0.5))").unwrap();
+        let settings = SettingsFile::load();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.audio.master, 0.5);
+        assert!(settings.loaded);
+        assert_eq!(settings.audio.music, AudioSettings::default().music);
+    }
+
+    #[test]
+    fn corrupt_settings_are_kept_as_a_copy() {
+        let contents = "(version: 1, audio: (master: ";
+        storage::write(SETTINGS_KEY, contents).unwrap();
+        let settings = SettingsFile::load();
+        assert_eq!(settings.audio, AudioSettings::default());
+        assert!(!settings.loaded);
+        assert_eq!(
+            storage::read(&storage::corrupt_key(SETTINGS_KEY)).as_deref(),
+            Some(contents)
+        );
+    }
+
+    #[test]
+    fn settings_of_a_newer_version_are_rejected() {
+        let contents = format!("(version: {})", SETTINGS_VERSION + 1);
+        assert!(matches!(
+            SettingsFile::parse(&contents),
+            Err(SettingsFileError::UnsupportedVersion(version)) if version == SETTINGS_VERSION + 1
+        ));
+    }
+}
@@ -0,0 +1,160 @@
+use bevy::log::warn;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+
+// ToDo: use your game's name, this is the directory/key prefix the player's data is stored under
+#[cfg_attr(test, allow(dead_code))]
+const APP_NAME: &str = "bevy_game";
+
+/// Reads the stored contents for `key`
+/// Returns `None` if nothing was stored yet or the storage can not be accessed
+pub fn read(key: &str) -> Option<String> {
+    backend::read(key)
+}
+
+/// Stores `contents` under `key`, replacing anything stored before
+pub fn write(key: &str, contents: &str) -> io::Result<()> {
+    backend::write(key, contents)
+}
+
+/// Reads the RON stored under `key`, starting over with the default if there is none or it can't be read
+/// `what` names the contents in the warning, e.g. "high scores"
+pub fn load_ron<T: DeserializeOwned + Default>(key: &str, what: &str) -> T {
+    load_with(key, what, |contents| ron::from_str(contents))
+}
+
+/// Reads the contents stored under `key` with `parse`, starting over with the default if there are none or they can't be parsed
+/// Contents that can't be parsed are kept under [`corrupt_key`], so the next save doesn't destroy them
+pub fn load_with<T: Default, E: fmt::Display>(
+    key: &str,
+    what: &str,
+    parse: impl FnOnce(&str) -> Result<T, E>,
+) -> T {
+    let Some(contents) = read(key) else {
+        return T::default();
+    };
+    parse(&contents).unwrap_or_else(|error| {
+        warn!("Failed to load the {what}, starting over: {error}");
+        if let Err(error) = write(&corrupt_key(key), &contents) {
+            warn!("Failed to back up the unreadable {what}: {error}");
+        }
+        T::default()
+    })
+}
+
+/// The key unreadable contents of `key` are kept under, e.g. `settings.corrupt.ron` for `settings.ron`
+pub fn corrupt_key(key: &str) -> String {
+    match key.rsplit_once('.') {
+        Some((stem, extension)) => format!("{stem}.corrupt.{extension}"),
+        None => format!("{key}.corrupt"),
+    }
+}
+
+/// Stores `value` as RON under `key`, failures are only logged
+pub fn save_ron<T: Serialize>(key: &str, what: &str, value: &T) {
+    let contents = match ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default()) {
+        Ok(contents) => contents,
+        Err(error) => {
+            warn!("Failed to serialize the {what}: {error}");
+            return;
+        }
+    };
+    if let Err(error) = write(key, &contents) {
+        warn!("Failed to save the {what}: {error}");
+    }
+}
+
+// Only used to find out which schema a file was written with before parsing the rest of it
+#[derive(Deserialize)]
+struct VersionHeader {
+    #[serde(default)]
+    version: u32,
+}
+
+/// The `version` field of a versioned RON file, files written before it was added count as version `0`
+pub fn ron_version(contents: &str) -> Result<u32, ron::error::SpannedError> {
+    ron::from_str::<VersionHeader>(contents).map(|header| header.version)
+}
+
+// Native builds keep one file per key in the platform config directory
+// (e.g. `~/.config/bevy_game` on Linux or `%APPDATA%\bevy_game` on Windows)
+#[cfg(all(not(target_arch = "wasm32"), not(test)))]
+mod backend {
+    use super::APP_NAME;
+    use std::fs;
+    use std::io;
+    use std::path::PathBuf;
+
+    fn path(key: &str) -> io::Result<PathBuf> {
+        dirs::config_dir()
+            .map(|dir| dir.join(APP_NAME).join(key))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))
+    }
+
+    pub fn read(key: &str) -> Option<String> {
+        fs::read_to_string(path(key).ok()?).ok()
+    }
+
+    pub fn write(key: &str, contents: &str) -> io::Result<()> {
+        let path = path(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write next to the target first, so a crash mid-write can't leave a truncated file behind
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, contents)?;
+        fs::rename(temp_path, path)
+    }
+}
+
+// The browser has no file system we could write to, so keys are stored in `localStorage`
+#[cfg(all(target_arch = "wasm32", not(test)))]
+mod backend {
+    use super::APP_NAME;
+    use std::io;
+
+    fn local_storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    pub fn read(key: &str) -> Option<String> {
+        local_storage()?
+            .get_item(&format!("{APP_NAME}/{key}"))
+            .ok()?
+    }
+
+    pub fn write(key: &str, contents: &str) -> io::Result<()> {
+        local_storage()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no local storage"))?
+            .set_item(&format!("{APP_NAME}/{key}"), contents)
+            .map_err(|error| io::Error::other(format!("{error:?}")))
+    }
+}
+
+// Tests must not touch the player's data, so they get an in-memory store
+// Every test runs on its own thread, which keeps their stores apart
+#[cfg(test)]
+mod backend {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::io;
+
+    thread_local! {
+        static STORE: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+    }
+
+    pub fn read(key: &str) -> Option<String> {
+        STORE.with(|store| store.borrow().get(key).cloned())
+    }
+
+    pub fn write(key: &str, contents: &str) -> io::Result<()> {
+        STORE.with(|store| {
+            store
+                .borrow_mut()
+                .insert(key.to_string(), contents.to_string())
+        });
+        Ok(())
+    }
+}
@@ -0,0 +1,303 @@
+// Not every test uses every helper
+#![allow(dead_code)]
+
+use crate::camera::CameraPlugin;
+use crate::loading::LoadingScreenSettings;
+use crate::settings_file::SettingsFile;
+use crate::{AppState, GamePlugin};
+use bevy::ecs::schedule::ExecutorKind;
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey, NativeKeyCode};
+use bevy::input::mouse::{MouseButtonInput, MouseScrollUnit, MouseWheel};
+use bevy::input::touch::TouchPhase;
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::prelude::*;
+use bevy::render::texture::ImagePlugin;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy::window::{
+    ExitCondition, Monitor, PrimaryMonitor, PrimaryWindow, VideoMode, WindowFocused, WindowResized,
+};
+use std::time::Duration;
+
+/// Loading happens on background threads, so give it plenty of frames before giving up
+const MAX_LOADING_FRAMES: usize = 1000;
+
+/// The game without a window, renderer or audio device
+/// Input is injected as events, so it goes through the same input systems as real input
+pub struct TestApp {
+    pub app: App,
+}
+
+impl TestApp {
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            ImagePlugin::default(),
+            InputPlugin,
+            StatesPlugin,
+            TransformPlugin,
+            WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            },
+        ))
+        // Registered by the `SpritePlugin`, which needs the renderer
+        .init_asset::<TextureAtlasLayout>()
+        // Keep the player's settings on disk out of the tests
+        .insert_resource(SettingsFile::default())
+        // Tests don't need to see the loading screen
+        .insert_resource(LoadingScreenSettings {
+            min_display_time: Duration::ZERO,
+        })
+        // Added by the game binary, next to the `GamePlugin`
+        .add_plugins((GamePlugin, CameraPlugin));
+        // Run every system on the test's own thread, which keeps the in-memory storage of tests apart
+        // and makes system order within a frame the same for every run
+        for (_, schedule) in app.world_mut().resource_mut::<Schedules>().iter_mut() {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        }
+        app.finish();
+        app.cleanup();
+        Self { app }
+    }
+
+    /// Starts a new app and waits until all assets are loaded and the main menu is shown
+    pub fn in_main_menu() -> Self {
+        let mut test_app = Self::new();
+        test_app.run_until_state(AppState::MainMenu);
+        test_app
+    }
+
+    /// Starts a new app, presses "Play" in the main menu and picks the first level
+    pub fn in_game() -> Self {
+        let mut test_app = Self::in_main_menu();
+        test_app.click_button("Play");
+        test_app.click_button("Meadow");
+        assert_eq!(test_app.state::<AppState>(), AppState::InGame);
+        test_app
+    }
+
+    pub fn update(&mut self) {
+        self.app.update();
+    }
+
+    pub fn advance(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+
+    /// Makes every frame advance time by exactly the given duration, instead of the real time passed
+    pub fn set_frame_time(&mut self, duration: Duration) {
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(duration));
+    }
+
+    /// Runs frames until the given state is entered
+    /// Panics if that doesn't happen within [`MAX_LOADING_FRAMES`]
+    pub fn run_until_state<S: States>(&mut self, state: S) {
+        for _ in 0..MAX_LOADING_FRAMES {
+            if self.state::<S>() == state {
+                return;
+            }
+            self.app.update();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        panic!(
+            "State {state:?} not reached, still in {:?}",
+            self.state::<S>()
+        );
+    }
+
+    pub fn state<S: States>(&self) -> S {
+        self.app.world().resource::<State<S>>().get().clone()
+    }
+
+    pub fn resource<R: Resource>(&self) -> &R {
+        self.app.world().resource::<R>()
+    }
+
+    pub fn count<C: Component>(&mut self) -> usize {
+        self.app
+            .world_mut()
+            .query_filtered::<(), With<C>>()
+            .iter(self.app.world())
+            .count()
+    }
+
+    pub fn press_key(&mut self, key_code: KeyCode) {
+        self.send_key(key_code, ButtonState::Pressed);
+    }
+
+    pub fn release_key(&mut self, key_code: KeyCode) {
+        self.send_key(key_code, ButtonState::Released);
+    }
+
+    /// Presses and releases a key, running one frame in between
+    pub fn tap_key(&mut self, key_code: KeyCode) {
+        self.press_key(key_code);
+        self.update();
+        self.release_key(key_code);
+        self.update();
+    }
+
+    /// Types the text one character per frame, like a keyboard layout producing these characters
+    pub fn type_text(&mut self, text: &str) {
+        for character in text.chars() {
+            for state in [ButtonState::Pressed, ButtonState::Released] {
+                self.app.world_mut().send_event(KeyboardInput {
+                    key_code: KeyCode::Unidentified(NativeKeyCode::Unidentified),
+                    logical_key: Key::Character(character.to_string().into()),
+                    state,
+                    repeat: false,
+                    window: Entity::PLACEHOLDER,
+                });
+                self.update();
+            }
+        }
+    }
+
+    fn send_key(&mut self, key_code: KeyCode, state: ButtonState) {
+        self.app.world_mut().send_event(KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    pub fn press_mouse(&mut self, button: MouseButton) {
+        self.send_mouse(button, ButtonState::Pressed);
+    }
+
+    pub fn release_mouse(&mut self, button: MouseButton) {
+        self.send_mouse(button, ButtonState::Released);
+    }
+
+    fn send_mouse(&mut self, button: MouseButton, state: ButtonState) {
+        self.app.world_mut().send_event(MouseButtonInput {
+            button,
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    /// Scrolls the mouse wheel by the given number of lines, positive values scroll up
+    pub fn scroll_mouse(&mut self, lines: f32) {
+        self.app.world_mut().send_event(MouseWheel {
+            unit: MouseScrollUnit::Line,
+            x: 0.0,
+            y: lines,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    /// Sends a touch event for the finger with the given id, positions are in window coordinates
+    pub fn touch(&mut self, id: u64, phase: TouchPhase, position: Vec2) {
+        self.app.world_mut().send_event(TouchInput {
+            phase,
+            position,
+            window: Entity::PLACEHOLDER,
+            force: None,
+            id,
+        });
+    }
+
+    pub fn set_window_focus(&mut self, focused: bool) {
+        self.app.world_mut().send_event(WindowFocused {
+            window: Entity::PLACEHOLDER,
+            focused,
+        });
+    }
+
+    /// Spawns the primary window, which the headless app doesn't have by default
+    pub fn spawn_primary_window(&mut self) -> Entity {
+        self.app
+            .world_mut()
+            .spawn((Window::default(), PrimaryWindow))
+            .id()
+    }
+
+    /// Sends a resize event like the one for a window dragged by the player
+    pub fn resize_window(&mut self, window: Entity, width: f32, height: f32) {
+        if let Some(mut window) = self.app.world_mut().get_mut::<Window>(window) {
+            window.resolution.set(width, height);
+        }
+        self.app.world_mut().send_event(WindowResized {
+            window,
+            width,
+            height,
+        });
+    }
+
+    /// Connects a monitor supporting the given video modes, as `(width, height, refresh rate in millihertz)`
+    pub fn add_monitor(&mut self, name: &str, primary: bool, modes: &[(u32, u32, u32)]) -> Entity {
+        let monitor_count = self.count::<Monitor>() as i32;
+        let video_modes = modes
+            .iter()
+            .map(|&(width, height, refresh_rate_millihertz)| VideoMode {
+                physical_size: UVec2::new(width, height),
+                bit_depth: 32,
+                refresh_rate_millihertz,
+            })
+            .collect();
+        let mut monitor = self.app.world_mut().spawn(Monitor {
+            name: Some(name.to_string()),
+            physical_height: 1080,
+            physical_width: 1920,
+            physical_position: IVec2::new(monitor_count * 1920, 0),
+            refresh_rate_millihertz: None,
+            scale_factor: 1.0,
+            video_modes,
+        });
+        if primary {
+            monitor.insert(PrimaryMonitor);
+        }
+        monitor.id()
+    }
+
+    /// Whether any text on screen reads exactly like the given text
+    pub fn has_text(&mut self, text: &str) -> bool {
+        let world = self.app.world_mut();
+        world.query::<&Text>().iter(world).any(|t| t.0 == text)
+    }
+
+    /// Finds the button showing the given text
+    pub fn find_button(&mut self, label: &str) -> Option<Entity> {
+        let world = self.app.world_mut();
+        let labels: Vec<(Entity, String)> = world
+            .query::<(&Parent, &Text)>()
+            .iter(world)
+            .map(|(parent, text)| (parent.get(), text.0.clone()))
+            .collect();
+        let mut buttons = world.query_filtered::<Entity, With<Button>>();
+        labels
+            .into_iter()
+            .filter(|(_, text)| text == label)
+            .map(|(parent, _)| parent)
+            .find(|parent| buttons.get(world, *parent).is_ok())
+    }
+
+    /// Presses the button showing the given text, like a mouse click would
+    /// Runs two frames, so that state changes caused by the button have been applied afterwards
+    pub fn click_button(&mut self, label: &str) {
+        let Some(button) = self.find_button(label) else {
+            panic!("No button labelled {label:?}");
+        };
+        self.set_interaction(button, Interaction::Pressed);
+        self.update();
+        self.set_interaction(button, Interaction::None);
+        self.update();
+    }
+
+    fn set_interaction(&mut self, entity: Entity, interaction: Interaction) {
+        // The button might have been despawned by its own handler
+        if let Some(mut current) = self.app.world_mut().get_mut::<Interaction>(entity) {
+            *current = interaction;
+        }
+    }
+}
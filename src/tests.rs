@@ -0,0 +1,1993 @@
+use crate::actions::{
+    Action, ActionMap, ActionState, InputSource, TouchControlSettings, TouchControls, TouchMovement,
+};
+use crate::animation::SpriteAnimationEvent;
+use crate::audio::{AudioSettings, FlyingAudio, PlaySfx};
+use crate::camera::{
+    CameraController, CameraShake, CameraSmoothing, CameraTarget, GameCamera, GameRenderResolution,
+    GameViewport, RenderScaling,
+};
+use crate::enemies::{Enemy, EnemySpawner, SpawnRules, SpawnedBy};
+use crate::gameplay::GameplayEntity;
+use crate::health::{Damage, Health, Invulnerable};
+use crate::hud::{HudAnchor, HudAppExt, HudRoot, MinimapDot};
+use crate::level::{
+    CurrentLevel, LevelEntity, LevelMap, LevelProgress, LevelRoot, LevelTrigger, PlayerStart,
+};
+use crate::loading::{
+    AudioAssets, LevelAssets, LoadingError, LoadingProgress, LoadingScreenSettings,
+};
+use crate::menus::focus::FocusedEntity;
+use crate::menus::settings::display_confirmation::DisplayConfirmation;
+use crate::menus::settings::monitors::PendingVideoMode;
+use crate::menus::settings::{ControlsSettings, NewDisplaySettings, WindowModeType};
+use crate::overlay_state::GameOverlayState;
+use crate::pause::GameplayState;
+use crate::physics::{Collider, Obstacle, Velocity};
+use crate::player::{MovementStats, Player, PlayerAnimationState};
+use crate::save_game::{
+    read_slot, slot_key, PendingLoad, SaveGameRequest, SaveGameStatus, SAVE_VERSION,
+};
+use crate::score::{HighScores, Pickup, Score};
+use crate::settings_file::SettingsFile;
+use crate::storage;
+use crate::test_support::TestApp;
+use crate::AppState;
+use bevy::input::gamepad::{
+    GamepadConnection, GamepadConnectionEvent, RawGamepadButtonChangedEvent, RawGamepadEvent,
+};
+use bevy::input::touch::TouchPhase;
+use bevy::prelude::*;
+use bevy::reflect::GetField;
+use bevy::render::camera::RenderTarget;
+use bevy::scene::DynamicEntity;
+use bevy::ui::RelativeCursorPosition;
+use bevy::window::{MonitorSelection, WindowMode};
+use std::time::{Duration, Instant};
+
+#[test]
+fn loading_continues_to_main_menu() {
+    let mut app = TestApp::in_main_menu();
+
+    assert!(app.find_button("Play").is_some());
+    assert!(app.find_button("High Scores").is_some());
+    assert!(app.find_button("Settings").is_some());
+    assert!(app.find_button("Quit").is_some());
+}
+
+#[test]
+fn main_menu_links_have_no_background() {
+    let mut app = TestApp::in_main_menu();
+    for link in ["Made with Bevy", "Open source"] {
+        let button = app.find_button(link).unwrap();
+        let background = app.app.world().get::<BackgroundColor>(button).unwrap();
+        assert_eq!(background.0, Color::NONE);
+    }
+}
+
+#[test]
+fn play_spawns_the_player() {
+    let mut app = TestApp::in_game();
+
+    assert_eq!(app.count::<Player>(), 1);
+    assert!(app.find_button("Play").is_none());
+}
+
+#[test]
+fn main_menu_settings_and_back() {
+    let mut app = TestApp::in_main_menu();
+
+    app.click_button("Settings");
+    assert_eq!(app.state::<AppState>(), AppState::SettingsMenu);
+    assert!(app.find_button("Display").is_some());
+
+    app.click_button("Back");
+    assert_eq!(app.state::<AppState>(), AppState::MainMenu);
+    assert!(app.find_button("Display").is_none());
+}
+
+#[test]
+fn escape_toggles_the_game_menu() {
+    let mut app = TestApp::in_game();
+
+    app.tap_key(KeyCode::Escape);
+    assert_eq!(app.state::<GameOverlayState>(), GameOverlayState::GameMenu);
+    assert!(app.find_button("Resume").is_some());
+
+    app.tap_key(KeyCode::Escape);
+    assert_eq!(app.state::<GameOverlayState>(), GameOverlayState::None);
+    assert!(app.find_button("Resume").is_none());
+}
+
+#[test]
+fn settings_overlay_returns_to_game_menu() {
+    let mut app = TestApp::in_game();
+    app.tap_key(KeyCode::Escape);
+
+    app.click_button("Settings");
+    assert_eq!(
+        app.state::<GameOverlayState>(),
+        GameOverlayState::SettingsMenu
+    );
+    assert_eq!(app.state::<AppState>(), AppState::InGame);
+    assert!(app.find_button("Display").is_some());
+
+    app.tap_key(KeyCode::Escape);
+    assert_eq!(app.state::<GameOverlayState>(), GameOverlayState::GameMenu);
+    assert!(app.find_button("Display").is_none());
+
+    app.click_button("Settings");
+    app.click_button("Back");
+    assert_eq!(app.state::<GameOverlayState>(), GameOverlayState::GameMenu);
+
+    app.click_button("Resume");
+    assert_eq!(app.state::<GameOverlayState>(), GameOverlayState::None);
+    assert_eq!(app.count::<Player>(), 1);
+}
+
+#[test]
+fn quit_to_menu_cleans_up_the_game() {
+    let mut app = TestApp::in_game();
+    app.tap_key(KeyCode::Escape);
+
+    app.click_button("Quit to Menu");
+    assert_eq!(app.state::<AppState>(), AppState::MainMenu);
+    assert_eq!(app.state::<GameOverlayState>(), GameOverlayState::None);
+    assert_eq!(app.count::<GameplayEntity>(), 0);
+    assert!(app.find_button("Play").is_some());
+}
+
+#[test]
+fn movement_keys_set_player_movement() {
+    let mut app = TestApp::in_game();
+
+    app.press_key(KeyCode::KeyD);
+    app.update();
+    assert_eq!(app.resource::<ActionState>().axis(Action::Move), Vec2::X);
+
+    app.press_key(KeyCode::KeyW);
+    app.update();
+    let movement = app.resource::<ActionState>().axis(Action::Move);
+    assert!((movement - Vec2::new(1.0, 1.0).normalize()).length() < 1e-5);
+
+    app.release_key(KeyCode::KeyD);
+    app.release_key(KeyCode::KeyW);
+    app.update();
+    assert_eq!(app.resource::<ActionState>().axis(Action::Move), Vec2::ZERO);
+}
+
+#[test]
+fn button_actions_report_presses_and_releases() {
+    let mut app = TestApp::in_game();
+
+    app.press_key(KeyCode::Escape);
+    app.update();
+    let actions = app.resource::<ActionState>();
+    assert!(actions.just_pressed(Action::Menu));
+    assert!(actions.pressed(Action::Menu));
+    assert!(!actions.pressed(Action::Move));
+
+    app.update();
+    let actions = app.resource::<ActionState>();
+    assert!(!actions.just_pressed(Action::Menu));
+    assert!(actions.pressed(Action::Menu));
+
+    app.release_key(KeyCode::Escape);
+    app.update();
+    let actions = app.resource::<ActionState>();
+    assert!(actions.just_released(Action::Menu));
+    assert!(!actions.pressed(Action::Menu));
+}
+
+#[test]
+fn action_map_can_be_changed() {
+    let mut app = TestApp::in_game();
+    app.app
+        .world_mut()
+        .resource_mut::<ActionMap>()
+        .0
+        .insert(Action::Menu, vec![InputSource::Key(KeyCode::KeyM)]);
+
+    app.press_key(KeyCode::Escape);
+    app.update();
+    assert!(!app.resource::<ActionState>().pressed(Action::Menu));
+
+    app.press_key(KeyCode::KeyM);
+    app.update();
+    assert!(app.resource::<ActionState>().pressed(Action::Menu));
+
+    app.release_key(KeyCode::KeyM);
+    app.update();
+    assert!(app.resource::<ActionState>().just_released(Action::Menu));
+}
+
+fn player_translation(app: &mut TestApp) -> Vec3 {
+    let world = app.app.world_mut();
+    world
+        .query_filtered::<&Transform, With<Player>>()
+        .single(world)
+        .translation
+}
+
+fn set_player_translation(app: &mut TestApp, translation: Vec3) {
+    let world = app.app.world_mut();
+    world
+        .query_filtered::<&mut Transform, With<Player>>()
+        .single_mut(world)
+        .translation = translation;
+}
+
+#[test]
+fn saved_game_can_be_loaded_again() {
+    let mut app = TestApp::in_game();
+    let saved = Vec3::new(40.0, -25.0, 1.0);
+    set_player_translation(&mut app, saved);
+
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Save");
+    assert_eq!(app.state::<GameOverlayState>(), GameOverlayState::SaveMenu);
+    app.click_button("Slot 2: Empty");
+    assert!(app.find_button("Slot 2: Saved").is_some());
+    assert_eq!(
+        app.resource::<SaveGameStatus>().0.as_deref(),
+        Some("Saved to slot 2")
+    );
+    app.click_button("Back");
+    app.click_button("Resume");
+
+    set_player_translation(&mut app, Vec3::new(-100.0, 100.0, 1.0));
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Load");
+    app.click_button("Slot 2: Saved");
+    assert_eq!(app.state::<GameOverlayState>(), GameOverlayState::None);
+    assert_eq!(app.count::<Player>(), 1);
+    assert_eq!(player_translation(&mut app), saved);
+}
+
+#[test]
+fn continue_resumes_the_latest_save() {
+    let mut app = TestApp::in_main_menu();
+    assert!(app.find_button("Continue").is_none());
+
+    app.click_button("Play");
+    app.click_button("Meadow");
+    let saved = Vec3::new(12.0, 34.0, 1.0);
+    set_player_translation(&mut app, saved);
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Save");
+    app.click_button("Slot 1: Empty");
+    app.click_button("Back");
+    app.click_button("Quit to Menu");
+
+    app.click_button("Continue");
+    assert_eq!(app.state::<AppState>(), AppState::InGame);
+    assert_eq!(app.count::<Player>(), 1);
+    assert_eq!(player_translation(&mut app), saved);
+}
+
+#[test]
+fn corrupt_saves_are_reported() {
+    let mut app = TestApp::in_game();
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Save");
+    app.click_button("Slot 1: Empty");
+
+    // Tamper with the stored position, so the checksum no longer matches
+    let contents = storage::read(&slot_key(0)).unwrap();
+    storage::write(&slot_key(0), &contents.replacen("1.0", "2.0", 1)).unwrap();
+    storage::write(&slot_key(1), "not a save game").unwrap();
+    storage::write(
+        &slot_key(2),
+        &contents.replacen(&format!("version: {SAVE_VERSION}"), "version: 99", 1),
+    )
+    .unwrap();
+    app.click_button("Back");
+
+    app.click_button("Load");
+    app.click_button("Slot 1: Unreadable");
+    assert_eq!(app.state::<GameOverlayState>(), GameOverlayState::LoadMenu);
+    assert_eq!(
+        app.resource::<SaveGameStatus>().0.as_deref(),
+        Some("Could not load slot 1: save file is corrupt")
+    );
+
+    app.click_button("Slot 2: Unreadable");
+    assert!(app
+        .resource::<SaveGameStatus>()
+        .0
+        .as_deref()
+        .unwrap()
+        .starts_with("Could not load slot 2: invalid save file"));
+
+    app.click_button("Slot 3: Unreadable");
+    assert_eq!(
+        app.resource::<SaveGameStatus>().0.as_deref(),
+        Some(
+            format!(
+                "Could not load slot 3: save file version 99 is not compatible with version {SAVE_VERSION}"
+            )
+            .as_str()
+        )
+    );
+    assert_eq!(app.count::<Player>(), 1);
+}
+
+#[test]
+fn saves_that_cant_be_restored_keep_the_session() {
+    let mut app = TestApp::in_game();
+    // Vec3 is registered for reflection, but isn't a component
+    let scene = DynamicScene {
+        resources: Vec::new(),
+        entities: vec![DynamicEntity {
+            entity: Entity::from_raw(1000),
+            components: vec![Box::new(Vec3::ONE)],
+        }],
+    };
+    app.app.world_mut().insert_resource(PendingLoad(scene));
+    app.update();
+
+    assert_eq!(app.count::<Player>(), 1);
+    let status = app.resource::<SaveGameStatus>().0.clone().unwrap();
+    assert!(status.contains("unknown component"), "{status}");
+}
+
+#[test]
+fn saved_children_are_despawned_with_their_parents() {
+    let mut app = TestApp::in_game();
+    let parent = app
+        .app
+        .world_mut()
+        .spawn(GameplayEntity)
+        .with_child(GameplayEntity)
+        .id();
+    app.app
+        .world_mut()
+        .insert_resource(PendingLoad(DynamicScene::default()));
+    app.update();
+
+    assert!(app.app.world().get_entity(parent).is_err());
+    assert_eq!(app.count::<Player>(), 0);
+}
+
+fn save_and_load(app: &mut TestApp) {
+    app.app.world_mut().send_event(SaveGameRequest { slot: 0 });
+    app.update();
+    let scene = read_slot(0, &app.resource::<AppTypeRegistry>().read()).unwrap();
+    app.app.world_mut().insert_resource(PendingLoad(scene));
+    app.update();
+}
+
+#[test]
+fn spawners_continue_after_loading_a_save() {
+    let mut app = TestApp::in_game();
+    app.set_frame_time(Duration::from_millis(100));
+    let world = app.app.world_mut();
+    let level_spawners: Vec<Entity> = world
+        .query_filtered::<Entity, With<EnemySpawner>>()
+        .iter(world)
+        .collect();
+    for spawner in level_spawners {
+        world.entity_mut(spawner).despawn_recursive();
+    }
+    let home = Vec2::new(-600.0, 400.0);
+    world.spawn((
+        EnemySpawner::new(SpawnRules {
+            archetype: "slime".to_string(),
+            interval: 0.1,
+            wave_size: 2,
+            wave_delay: 0.1,
+            waves: Some(1),
+            max_alive: 1,
+            radius: 0.0,
+        }),
+        Transform::from_translation(home.extend(0.0)),
+        GameplayEntity,
+    ));
+    app.advance(5);
+    assert_eq!(app.count::<Enemy>(), 1);
+    let world = app.app.world_mut();
+    world
+        .query_filtered::<&mut Transform, With<Enemy>>()
+        .single_mut(world)
+        .translation = Vec3::new(-500.0, 300.0, 0.5);
+
+    save_and_load(&mut app);
+    // The restored enemy still counts towards the spawner's cap and stays around its home
+    app.advance(5);
+    assert_eq!(app.count::<Enemy>(), 1);
+    let world = app.app.world_mut();
+    let spawner = world
+        .query_filtered::<Entity, With<EnemySpawner>>()
+        .single(world);
+    let (enemy, enemy_entity, spawned_by) =
+        world.query::<(&Enemy, Entity, &SpawnedBy)>().single(world);
+    assert_eq!(*spawned_by, SpawnedBy(spawner));
+    assert_eq!(enemy.home, home);
+
+    // Only the second enemy of the wave is left
+    world.entity_mut(enemy_entity).despawn_recursive();
+    app.advance(5);
+    assert_eq!(app.count::<Enemy>(), 1);
+    assert!(app
+        .app
+        .world()
+        .get::<EnemySpawner>(spawner)
+        .unwrap()
+        .is_done());
+}
+
+#[test]
+fn overlay_menus_pause_the_game() {
+    let mut app = TestApp::in_game();
+    assert_eq!(app.state::<GameplayState>(), GameplayState::Running);
+
+    app.press_key(KeyCode::KeyD);
+    app.update();
+    app.tap_key(KeyCode::Escape);
+    assert_eq!(app.state::<GameplayState>(), GameplayState::Paused);
+    assert!(app.resource::<Time<Virtual>>().is_paused());
+
+    // Holding a movement key in the menu doesn't move the player
+    let paused_at = player_translation(&mut app);
+    app.advance(5);
+    assert_eq!(player_translation(&mut app), paused_at);
+
+    app.click_button("Settings");
+    assert_eq!(app.state::<GameplayState>(), GameplayState::Paused);
+
+    app.tap_key(KeyCode::Escape);
+    app.tap_key(KeyCode::Escape);
+    assert_eq!(app.state::<GameplayState>(), GameplayState::Running);
+    assert!(!app.resource::<Time<Virtual>>().is_paused());
+}
+
+#[test]
+fn losing_focus_opens_the_game_menu() {
+    let mut app = TestApp::in_game();
+
+    app.set_window_focus(false);
+    app.update();
+    app.update();
+    assert_eq!(app.state::<GameOverlayState>(), GameOverlayState::GameMenu);
+    assert_eq!(app.state::<GameplayState>(), GameplayState::Paused);
+
+    // Coming back leaves the menu open until the player resumes
+    app.set_window_focus(true);
+    app.update();
+    assert_eq!(app.state::<GameOverlayState>(), GameOverlayState::GameMenu);
+}
+
+#[test]
+fn quitting_while_paused_resumes_time() {
+    let mut app = TestApp::in_game();
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Quit to Menu");
+
+    assert!(!app.resource::<Time<Virtual>>().is_paused());
+    assert!(app
+        .app
+        .world()
+        .get_resource::<State<GameplayState>>()
+        .is_none());
+}
+
+#[test]
+fn loading_screen_shows_progress() {
+    let mut app = TestApp::new();
+    app.update();
+    assert_eq!(app.state::<AppState>(), AppState::Loading);
+    assert!(app.has_text("Loading"));
+    assert_eq!(app.resource::<LoadingProgress>().total, 9);
+
+    app.run_until_state(AppState::MainMenu);
+    let progress = app.resource::<LoadingProgress>();
+    assert_eq!(progress.loaded, progress.total);
+    assert!(!app.has_text("Loading"));
+}
+
+#[test]
+fn loading_screen_stays_for_the_minimum_time() {
+    let mut app = TestApp::new();
+    app.app.insert_resource(LoadingScreenSettings {
+        min_display_time: Duration::from_millis(300),
+    });
+    let started = Instant::now();
+
+    app.run_until_state(AppState::MainMenu);
+    assert!(started.elapsed() >= Duration::from_millis(300));
+}
+
+#[test]
+fn failing_assets_show_the_error_screen() {
+    let mut app = TestApp::new();
+    app.update();
+    let missing = app
+        .resource::<AssetServer>()
+        .load::<Image>("textures/missing.png")
+        .untyped();
+    app.app
+        .world_mut()
+        .resource_mut::<LoadingProgress>()
+        .track(vec![missing], |_| true);
+
+    app.run_until_state(AppState::LoadingFailed);
+    let error = app.resource::<LoadingError>();
+    assert_eq!(error.path, "textures/missing.png");
+    assert!(app.has_text("Failed to load the game"));
+    assert!(app.has_text("textures/missing.png"));
+    assert!(app.find_button("Quit").is_some());
+
+    // It stays there instead of continuing without the asset
+    app.advance(10);
+    assert_eq!(app.state::<AppState>(), AppState::LoadingFailed);
+}
+
+#[test]
+fn display_settings_offer_the_monitor_video_modes() {
+    let mut app = TestApp::new();
+    app.add_monitor(
+        "Left",
+        true,
+        &[
+            (1280, 720, 60000),
+            (1920, 1080, 60000),
+            (1920, 1080, 144000),
+        ],
+    );
+    app.add_monitor("Right", false, &[(1280, 720, 59940), (2560, 1440, 75000)]);
+    app.run_until_state(AppState::MainMenu);
+    app.click_button("Settings");
+    assert!(app.has_text("Left"));
+    assert!(app.has_text("Auto"));
+
+    app.click_button("1280x720");
+    assert!(app.has_text("1920x1080"));
+    app.click_button("Auto");
+    assert!(app.has_text("60 Hz"));
+    app.click_button("60 Hz");
+    assert!(app.has_text("144 Hz"));
+    app.click_button("144 Hz");
+    assert!(app.has_text("Auto"));
+
+    // Every monitor has its own video modes
+    app.click_button("Left");
+    assert!(app.has_text("Right"));
+    app.click_button("1920x1080");
+    assert!(app.has_text("2560x1440"));
+    app.click_button("2560x1440");
+    app.click_button("Auto");
+    assert!(app.has_text("59.94 Hz"));
+    let display = app.resource::<NewDisplaySettings>();
+    assert_eq!(display.monitor.as_deref(), Some("Right"));
+    assert_eq!(display.resolution, (1280, 720));
+    assert_eq!(display.refresh_rate_millihertz, Some(59940));
+}
+
+#[test]
+fn fullscreen_uses_the_chosen_video_mode() {
+    let mut app = TestApp::new();
+    app.add_monitor("Left", true, &[(1920, 1080, 60000)]);
+    let right = app.add_monitor("Right", false, &[(1280, 720, 60000), (1280, 720, 120000)]);
+    let window = app.spawn_primary_window();
+    app.run_until_state(AppState::MainMenu);
+    app.click_button("Settings");
+
+    app.click_button("Left");
+    app.click_button("Windowed");
+    app.click_button("Auto");
+    assert!(app.has_text("60 Hz"));
+    app.click_button("Apply");
+
+    let window = app.app.world().get::<Window>(window).unwrap();
+    assert_eq!(
+        window.mode,
+        WindowMode::SizedFullscreen(MonitorSelection::Entity(right))
+    );
+    assert_eq!(window.resolution.physical_size(), UVec2::new(1280, 720));
+    let request = app.resource::<PendingVideoMode>().0.as_ref().unwrap();
+    assert_eq!(request.monitor, "Right");
+    assert_eq!(request.refresh_rate_millihertz, 60000);
+    app.click_button("Keep");
+    let display = &app.resource::<SettingsFile>().display;
+    assert_eq!(display.window_mode, WindowModeType::Fullscreen);
+    assert_eq!(display.monitor.as_deref(), Some("Right"));
+}
+
+#[test]
+fn dragged_window_sizes_are_kept() {
+    let mut app = TestApp::in_main_menu();
+    let window = app.spawn_primary_window();
+    app.update();
+
+    app.resize_window(window, 1000.0, 700.0);
+    app.update();
+    assert_eq!(
+        app.resource::<SettingsFile>().display.resolution,
+        (1000, 700)
+    );
+
+    // The dragged size is one of the choices, instead of being replaced by a default
+    app.click_button("Settings");
+    assert!(app.has_text("1000x700"));
+    app.click_button("1000x700");
+    assert!(app.has_text("1280x720"));
+}
+
+#[test]
+fn applied_display_settings_have_to_be_confirmed() {
+    let mut app = TestApp::in_main_menu();
+    let window = app.spawn_primary_window();
+    app.click_button("Settings");
+
+    app.click_button("Windowed");
+    app.click_button("Apply");
+    assert!(app.has_text("Keep these settings?"));
+    assert!(app.has_text("Reverting in 15 seconds"));
+    assert!(matches!(
+        app.app.world().get::<Window>(window).unwrap().mode,
+        WindowMode::SizedFullscreen(_)
+    ));
+    // Nothing is saved before the player confirmed
+    assert_eq!(
+        app.resource::<SettingsFile>().display.window_mode,
+        WindowModeType::Windowed
+    );
+
+    app.click_button("Keep");
+    assert!(!app.has_text("Keep these settings?"));
+    assert_eq!(
+        app.resource::<SettingsFile>().display.window_mode,
+        WindowModeType::Fullscreen
+    );
+}
+
+#[test]
+fn unconfirmed_display_settings_are_reverted() {
+    let mut app = TestApp::in_main_menu();
+    let window = app.spawn_primary_window();
+    app.click_button("Settings");
+
+    app.click_button("Windowed");
+    app.click_button("Apply");
+    app.click_button("Revert");
+    assert!(!app.has_text("Keep these settings?"));
+    assert_eq!(
+        app.app.world().get::<Window>(window).unwrap().mode,
+        WindowMode::Windowed
+    );
+    assert!(app.has_text("Windowed"));
+
+    // Running out of time reverts as well
+    app.click_button("Windowed");
+    app.click_button("Apply");
+    app.app
+        .world_mut()
+        .resource_mut::<DisplayConfirmation>()
+        .timer
+        .tick(Duration::from_secs(15));
+    app.update();
+    app.update();
+    assert!(!app.has_text("Keep these settings?"));
+    assert_eq!(
+        app.app.world().get::<Window>(window).unwrap().mode,
+        WindowMode::Windowed
+    );
+    assert_eq!(
+        app.resource::<NewDisplaySettings>().window_mode,
+        WindowModeType::Windowed
+    );
+    assert_eq!(
+        app.resource::<SettingsFile>().display.window_mode,
+        WindowModeType::Windowed
+    );
+}
+
+fn set_render_scaling(app: &mut TestApp, width: f32, height: f32, scaling: RenderScaling) {
+    let mut render_resolution = app.app.world_mut().resource_mut::<GameRenderResolution>();
+    render_resolution.width = width;
+    render_resolution.height = height;
+    render_resolution.scaling = scaling;
+    app.update();
+}
+
+fn game_render_target_size(app: &mut TestApp) -> Option<UVec2> {
+    let world = app.app.world_mut();
+    let camera = world
+        .query_filtered::<&Camera, With<GameCamera>>()
+        .single(world);
+    match &camera.target {
+        RenderTarget::Image(handle) => Some(world.resource::<Assets<Image>>().get(handle)?.size()),
+        _ => None,
+    }
+}
+
+#[test]
+fn render_scaling_fits_the_game_into_the_window() {
+    let mut app = TestApp::in_main_menu();
+    // 1280x720 by default
+    app.spawn_primary_window();
+
+    set_render_scaling(&mut app, 640.0, 480.0, RenderScaling::Letterbox);
+    assert_eq!(
+        game_render_target_size(&mut app),
+        Some(UVec2::new(960, 720))
+    );
+    assert_eq!(app.resource::<GameViewport>().offset, Vec2::new(160.0, 0.0));
+
+    set_render_scaling(&mut app, 640.0, 480.0, RenderScaling::PixelPerfect);
+    assert_eq!(
+        game_render_target_size(&mut app),
+        Some(UVec2::new(640, 480))
+    );
+    assert_eq!(
+        app.resource::<GameViewport>().offset,
+        Vec2::new(320.0, 120.0)
+    );
+    assert_eq!(app.resource::<GameViewport>().scale, Vec2::ONE);
+
+    set_render_scaling(&mut app, 640.0, 480.0, RenderScaling::Upscale);
+    assert_eq!(
+        game_render_target_size(&mut app),
+        Some(UVec2::new(640, 480))
+    );
+    let viewport = *app.resource::<GameViewport>();
+    assert_eq!(viewport.offset, Vec2::new(160.0, 0.0));
+    assert!((viewport.scale - Vec2::splat(2.0 / 3.0)).length() < 1e-5);
+
+    set_render_scaling(&mut app, 640.0, 480.0, RenderScaling::AutoMin);
+    assert_eq!(game_render_target_size(&mut app), None);
+    assert_eq!(*app.resource::<GameViewport>(), GameViewport::default());
+}
+
+#[test]
+fn first_launch_keeps_the_default_render_resolution() {
+    let app = TestApp::in_main_menu();
+    assert!(!app.resource::<SettingsFile>().loaded);
+    let render_resolution = app.resource::<GameRenderResolution>();
+    assert_eq!(
+        (render_resolution.width, render_resolution.height),
+        (1920.0, 1080.0)
+    );
+}
+
+#[test]
+fn scaling_can_be_chosen_in_the_display_settings() {
+    let mut app = TestApp::in_main_menu();
+    app.spawn_primary_window();
+    app.click_button("Settings");
+
+    app.click_button("Expand");
+    assert!(app.has_text("Letterbox"));
+    app.click_button("Letterbox");
+    assert!(app.has_text("Pixel Perfect"));
+    app.click_button("Apply");
+    app.click_button("Keep");
+
+    assert_eq!(
+        app.resource::<GameRenderResolution>().scaling,
+        RenderScaling::PixelPerfect
+    );
+    assert_eq!(
+        app.resource::<SettingsFile>().display.scaling,
+        RenderScaling::PixelPerfect
+    );
+    assert_eq!(
+        game_render_target_size(&mut app),
+        Some(UVec2::new(1280, 720))
+    );
+}
+
+fn game_camera(app: &mut TestApp) -> (Transform, CameraController, Rect) {
+    let world = app.app.world_mut();
+    let (transform, controller, projection) = world
+        .query::<(&Transform, &CameraController, &OrthographicProjection)>()
+        .single(world);
+    (*transform, controller.clone(), projection.area)
+}
+
+fn configure_camera(app: &mut TestApp, configure: impl FnOnce(&mut CameraController)) {
+    let world = app.app.world_mut();
+    configure(&mut world.query::<&mut CameraController>().single_mut(world));
+}
+
+#[test]
+fn camera_follows_the_player() {
+    let mut app = TestApp::in_game();
+    app.set_frame_time(Duration::from_secs_f32(1.0 / 60.0));
+    set_player_translation(&mut app, Vec3::new(500.0, -300.0, 1.0));
+
+    // Smoothed, so the camera is still on its way after one frame
+    app.update();
+    let (camera, _, _) = game_camera(&mut app);
+    assert!(camera.translation.x < 400.0);
+
+    app.advance(300);
+    let (camera, controller, _) = game_camera(&mut app);
+    let distance = (Vec2::new(500.0, -300.0) - camera.translation.truncate()).abs();
+    assert!(camera.translation.x > 400.0);
+    assert!(distance.cmple(controller.dead_zone + 1.0).all());
+
+    // A new game starts at the origin again
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Quit to Menu");
+    let (camera, _, _) = game_camera(&mut app);
+    assert_eq!(camera.translation.truncate(), Vec2::ZERO);
+}
+
+#[test]
+fn camera_stays_within_world_bounds() {
+    let mut app = TestApp::in_game();
+    let bounds = Rect::new(-100.0, -100.0, 100.0, 100.0);
+    configure_camera(&mut app, |controller| {
+        controller.smoothing = CameraSmoothing::None;
+        controller.bounds = Some(bounds);
+    });
+    set_player_translation(&mut app, Vec3::new(1000.0, -1000.0, 1.0));
+    app.advance(5);
+
+    let (camera, _, view) = game_camera(&mut app);
+    let visible = Rect::from_center_half_size(camera.translation.truncate(), view.half_size());
+    assert_eq!(visible.max.x, bounds.max.x);
+    assert_eq!(visible.min.y, bounds.min.y);
+}
+
+#[test]
+fn camera_shake_wears_off() {
+    let mut app = TestApp::in_game();
+    app.set_frame_time(Duration::from_secs_f32(1.0 / 60.0));
+    app.update();
+    app.app.world_mut().send_event(CameraShake { trauma: 1.0 });
+    app.update();
+
+    let (camera, controller, _) = game_camera(&mut app);
+    assert!(controller.trauma > 0.9);
+    assert_ne!(camera.translation.truncate(), controller.position());
+
+    // Trauma is lost at one per second
+    app.advance(70);
+    let (camera, controller, _) = game_camera(&mut app);
+    assert_eq!(controller.trauma, 0.0);
+    assert_eq!(camera.translation.truncate(), controller.position());
+    assert_eq!(camera.rotation, Quat::IDENTITY);
+}
+
+#[test]
+fn getting_hurt_shakes_the_camera() {
+    let mut app = TestApp::in_game();
+    let position = player_translation(&mut app);
+    let (_, controller, _) = game_camera(&mut app);
+    assert_eq!(controller.trauma, 0.0);
+
+    app.app
+        .world_mut()
+        .spawn((Damage::default(), Transform::from_translation(position)));
+    app.update();
+    let (_, controller, _) = game_camera(&mut app);
+    assert!(controller.trauma > 0.0);
+}
+
+fn game_camera_scale(app: &mut TestApp) -> f32 {
+    let world = app.app.world_mut();
+    world
+        .query_filtered::<&OrthographicProjection, With<GameCamera>>()
+        .single(world)
+        .scale
+}
+
+#[test]
+fn mouse_wheel_and_pinching_zoom_the_camera() {
+    let mut app = TestApp::in_game();
+    app.set_frame_time(Duration::from_secs_f32(1.0 / 60.0));
+
+    app.scroll_mouse(3.0);
+    app.advance(120);
+    let (_, controller, _) = game_camera(&mut app);
+    assert!((controller.zoom - 1.1f32.powi(3)).abs() < 1e-4);
+    assert_eq!(controller.current_zoom(), controller.zoom);
+    assert!((game_camera_scale(&mut app) - 1.0 / controller.zoom).abs() < 1e-4);
+
+    // Pixel perfect scaling only zooms by whole numbers
+    set_render_scaling(&mut app, 1920.0, 1080.0, RenderScaling::PixelPerfect);
+    assert_eq!(game_camera_scale(&mut app), 1.0);
+    set_render_scaling(&mut app, 1920.0, 1080.0, RenderScaling::AutoMin);
+
+    // Limited to the controller's zoom range
+    app.scroll_mouse(-50.0);
+    app.update();
+    let (_, controller, _) = game_camera(&mut app);
+    assert_eq!(controller.zoom, controller.min_zoom);
+
+    // Spreading two fingers to twice their distance zooms in twice as much
+    let player = player_translation(&mut app);
+    app.touch(0, TouchPhase::Started, Vec2::new(100.0, 100.0));
+    app.touch(1, TouchPhase::Started, Vec2::new(200.0, 100.0));
+    app.update();
+    app.touch(1, TouchPhase::Moved, Vec2::new(300.0, 100.0));
+    app.update();
+    let (_, controller, _) = game_camera(&mut app);
+    assert!((controller.zoom - 2.0 * controller.min_zoom).abs() < 1e-4);
+    // Pinching doesn't move the player towards the fingers
+    assert_eq!(player_translation(&mut app), player);
+}
+
+#[test]
+fn framing_keeps_every_target_in_view() {
+    let mut app = TestApp::in_game();
+    app.set_frame_time(Duration::from_secs_f32(1.0 / 60.0));
+    configure_camera(&mut app, |controller| controller.frame_targets = true);
+    app.app
+        .world_mut()
+        .spawn((Transform::from_xyz(2000.0, 0.0, 1.0), CameraTarget));
+    app.advance(300);
+
+    let (camera, controller, _) = game_camera(&mut app);
+    assert!((camera.translation.truncate() - Vec2::new(1000.0, 0.0)).length() < 1.0);
+    // The 2000 units between the targets and the margins fit into the width of the render resolution
+    let width = app.resource::<GameRenderResolution>().width;
+    let expected_zoom = width / (2000.0 + 2.0 * controller.framing_margin);
+    assert!((controller.current_zoom() - expected_zoom).abs() < 1e-3);
+    // Not zoomed out further than allowed
+    configure_camera(&mut app, |controller| {
+        controller.min_zoom = expected_zoom + 0.1
+    });
+    app.update();
+    let (_, controller, _) = game_camera(&mut app);
+    assert_eq!(controller.zoom, controller.min_zoom);
+}
+
+fn player_velocity(app: &mut TestApp) -> Vec2 {
+    let world = app.app.world_mut();
+    world
+        .query_filtered::<&Velocity, With<Player>>()
+        .single(world)
+        .0
+}
+
+#[test]
+fn player_speeds_up_and_slows_down() {
+    let mut app = TestApp::in_game();
+    app.set_frame_time(Duration::from_secs_f32(1.0 / 64.0));
+    let stats = MovementStats::default();
+
+    app.press_key(KeyCode::KeyD);
+    app.advance(2);
+    let velocity = player_velocity(&mut app);
+    assert!(velocity.x > 0.0 && velocity.x < stats.max_speed);
+
+    app.advance(60);
+    assert_eq!(player_velocity(&mut app), Vec2::new(stats.max_speed, 0.0));
+    assert!(player_translation(&mut app).x > 100.0);
+
+    app.release_key(KeyCode::KeyD);
+    app.advance(2);
+    let velocity = player_velocity(&mut app);
+    assert!(velocity.x > 0.0 && velocity.x < stats.max_speed);
+    app.advance(60);
+    assert_eq!(player_velocity(&mut app), Vec2::ZERO);
+}
+
+#[test]
+fn player_slides_along_obstacles() {
+    let mut app = TestApp::in_game();
+    app.set_frame_time(Duration::from_secs_f32(1.0 / 64.0));
+    // A wall to the right of the player
+    app.app.world_mut().spawn((
+        Transform::from_xyz(100.0, 0.0, 0.0),
+        Obstacle,
+        Collider::Aabb {
+            half_size: Vec2::new(20.0, 1000.0),
+        },
+        GameplayEntity,
+    ));
+
+    app.press_key(KeyCode::KeyD);
+    app.press_key(KeyCode::KeyW);
+    app.advance(120);
+
+    let Collider::Circle { radius } = Collider::default() else {
+        unreachable!()
+    };
+    let translation = player_translation(&mut app);
+    assert!((translation.x - (100.0 - 20.0 - radius)).abs() < 0.01);
+    // Only the movement into the wall is stopped
+    assert!(translation.y > 100.0);
+    assert_eq!(player_velocity(&mut app).x, 0.0);
+    assert!(player_velocity(&mut app).y > 0.0);
+}
+
+#[test]
+fn colliders_push_each_other_apart() {
+    let circle = Collider::Circle { radius: 10.0 };
+    let square = Collider::Aabb {
+        half_size: Vec2::splat(10.0),
+    };
+    assert_eq!(
+        circle.penetration(Vec2::new(15.0, 0.0), circle, Vec2::ZERO),
+        Some(Vec2::new(5.0, 0.0))
+    );
+    assert_eq!(
+        circle.penetration(Vec2::new(0.0, -18.0), square, Vec2::ZERO),
+        Some(Vec2::new(0.0, -2.0))
+    );
+    assert_eq!(
+        square.penetration(Vec2::new(-16.0, 3.0), square, Vec2::ZERO),
+        Some(Vec2::new(-4.0, 0.0))
+    );
+    assert_eq!(
+        square.penetration(Vec2::new(30.0, 0.0), circle, Vec2::ZERO),
+        None
+    );
+}
+
+#[test]
+fn touch_joystick_and_buttons_work_together() {
+    let mut app = TestApp::in_game();
+    // 1280x720
+    app.spawn_primary_window();
+    app.app
+        .world_mut()
+        .resource_mut::<TouchControlSettings>()
+        .movement = TouchMovement::FloatingJoystick;
+    let radius = TouchControlSettings::default().joystick_radius;
+
+    // The joystick appears under the finger and is pushed to the right
+    app.touch(0, TouchPhase::Started, Vec2::new(300.0, 400.0));
+    app.update();
+    assert!(app.resource::<TouchControls>().shown);
+    assert_eq!(app.resource::<ActionState>().axis(Action::Move), Vec2::ZERO);
+    app.touch(0, TouchPhase::Moved, Vec2::new(300.0 + radius, 400.0));
+    app.update();
+    let movement = app.resource::<ActionState>().axis(Action::Move);
+    assert!((movement - Vec2::X).length() < 1e-4);
+
+    // A second finger presses the menu button in the top right corner without stopping the movement
+    app.touch(1, TouchPhase::Started, Vec2::new(1220.0, 60.0));
+    app.update();
+    assert!(app.resource::<ActionState>().pressed(Action::Menu));
+    assert_eq!(app.resource::<ActionState>().axis(Action::Move), movement);
+    app.update();
+    assert_eq!(app.state::<GameOverlayState>(), GameOverlayState::GameMenu);
+}
+
+#[test]
+fn touch_movement_can_be_chosen_in_the_controls_settings() {
+    let mut app = TestApp::in_main_menu();
+    app.click_button("Settings");
+    app.click_button("Controls");
+
+    app.click_button("Follow Finger");
+    assert!(app.has_text("Fixed Joystick"));
+    assert_eq!(
+        app.resource::<TouchControlSettings>().movement,
+        TouchMovement::FixedJoystick
+    );
+    assert_eq!(
+        app.resource::<SettingsFile>().touch.movement,
+        TouchMovement::FixedJoystick
+    );
+}
+
+fn player_animation(app: &mut TestApp) -> (PlayerAnimationState, bool) {
+    let world = app.app.world_mut();
+    let (state, sprite) = world
+        .query_filtered::<(&PlayerAnimationState, &Sprite), With<Player>>()
+        .single(world);
+    (*state, sprite.flip_x)
+}
+
+#[test]
+fn player_animation_follows_the_movement() {
+    let mut app = TestApp::in_game();
+    app.set_frame_time(Duration::from_secs_f32(1.0 / 64.0));
+    app.update();
+    assert_eq!(
+        player_animation(&mut app),
+        (PlayerAnimationState::Idle, false)
+    );
+
+    app.press_key(KeyCode::KeyD);
+    app.update();
+    assert_eq!(
+        player_animation(&mut app),
+        (PlayerAnimationState::Move, false)
+    );
+    // The first frame of the move clip is a step
+    let steps = app
+        .resource::<Events<SpriteAnimationEvent>>()
+        .iter_current_update_events()
+        .filter(|event| event.name == "step")
+        .count();
+    assert_eq!(steps, 1);
+
+    // Changing direction turns the player around before it moves on
+    app.release_key(KeyCode::KeyD);
+    app.press_key(KeyCode::KeyA);
+    app.update();
+    assert_eq!(
+        player_animation(&mut app),
+        (PlayerAnimationState::Turn, true)
+    );
+    app.advance(20);
+    assert_eq!(
+        player_animation(&mut app),
+        (PlayerAnimationState::Move, true)
+    );
+
+    // Standing still keeps facing the same way
+    app.release_key(KeyCode::KeyA);
+    app.update();
+    assert_eq!(
+        player_animation(&mut app),
+        (PlayerAnimationState::Idle, true)
+    );
+    let world = app.app.world_mut();
+    let sprite = world
+        .query_filtered::<&Sprite, With<Player>>()
+        .single(world);
+    let index = sprite.texture_atlas.as_ref().map(|atlas| atlas.index);
+    assert!(matches!(index, Some(0..=3)));
+}
+
+#[test]
+fn the_level_is_spawned_from_its_map() {
+    let mut app = TestApp::in_game();
+    assert_eq!(app.count::<LevelRoot>(), 1);
+    // Every tile of the ground layer, and the walls on top of it
+    assert!(app.count::<LevelEntity>() > 80 * 48);
+    let obstacles = app.count::<Obstacle>();
+    assert!(obstacles >= 4);
+
+    let world = app.app.world_mut();
+    let start = world
+        .query_filtered::<&Transform, With<PlayerStart>>()
+        .single(world)
+        .translation;
+    let triggers: Vec<String> = world
+        .query_filtered::<&Name, With<LevelTrigger>>()
+        .iter(world)
+        .map(|name| name.to_string())
+        .collect();
+    assert_eq!(triggers, ["exit"]);
+    assert_eq!(player_translation(&mut app).truncate(), start.truncate());
+
+    // Loading a save keeps the level instead of restoring it from the save
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Save");
+    app.click_button("Slot 1: Empty");
+    app.click_button("Back");
+    app.click_button("Load");
+    app.click_button("Slot 1: Saved");
+    assert_eq!(app.count::<LevelRoot>(), 1);
+    assert_eq!(app.count::<Obstacle>(), obstacles);
+
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Quit to Menu");
+    assert_eq!(app.count::<LevelEntity>(), 0);
+}
+
+#[test]
+fn the_camera_is_kept_within_the_map() {
+    let mut app = TestApp::in_game();
+    let handle = app.resource::<LevelAssets>().maps.get("meadow").unwrap();
+    let map_size = app.resource::<Assets<LevelMap>>().get(handle).unwrap().size;
+    let (_, controller, _) = game_camera(&mut app);
+    assert_eq!(
+        controller.bounds,
+        Some(Rect::from_center_size(Vec2::ZERO, map_size))
+    );
+
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Quit to Menu");
+    let (_, controller, _) = game_camera(&mut app);
+    assert_eq!(controller.bounds, None);
+}
+
+fn level_root(app: &mut TestApp) -> String {
+    let world = app.app.world_mut();
+    world.query::<&LevelRoot>().single(world).level.clone()
+}
+
+#[test]
+fn completing_a_level_unlocks_the_next_one() {
+    let mut app = TestApp::in_main_menu();
+    app.click_button("Play");
+    assert_eq!(app.state::<AppState>(), AppState::LevelSelect);
+    assert!(app.has_text("Not completed"));
+    assert!(app.has_text("Locked"));
+    assert!(app.find_button("Ruins").is_none());
+
+    // Reaching the exit completes the meadow
+    app.click_button("Meadow");
+    assert_eq!(level_root(&mut app), "meadow");
+    let world = app.app.world_mut();
+    let exit = world
+        .query_filtered::<&Transform, With<LevelTrigger>>()
+        .single(world)
+        .translation;
+    set_player_translation(&mut app, exit);
+    app.update();
+    app.update();
+    assert_eq!(app.state::<AppState>(), AppState::LevelComplete);
+    assert!(app.has_text("Level Complete"));
+    assert!(app.has_text("New best time!"));
+    assert_eq!(app.count::<LevelEntity>(), 0);
+    assert!(app
+        .resource::<LevelProgress>()
+        .best_time("meadow")
+        .is_some());
+
+    // Surviving long enough completes the ruins, the last level
+    app.click_button("Next Level");
+    assert_eq!(app.state::<AppState>(), AppState::InGame);
+    assert_eq!(level_root(&mut app), "ruins");
+    app.app.world_mut().resource_mut::<CurrentLevel>().time = 60.0;
+    app.update();
+    app.update();
+    assert_eq!(app.state::<AppState>(), AppState::LevelComplete);
+    assert!(app.has_text("All levels complete!"));
+    assert!(app.find_button("Next Level").is_none());
+
+    app.click_button("Level Select");
+    assert!(!app.has_text("Locked"));
+    assert!(app.find_button("Ruins").is_some());
+
+    // The progress is kept for the next session
+    let mut app = TestApp::in_main_menu();
+    app.click_button("Play");
+    assert!(app.find_button("Ruins").is_some());
+    assert!(!app.has_text("Not completed"));
+}
+
+#[test]
+fn saves_remember_their_level() {
+    let mut app = TestApp::in_game();
+    // Switching the level respawns it, like loading a save made in another level
+    app.app
+        .world_mut()
+        .insert_resource(CurrentLevel::start("ruins"));
+    app.update();
+    assert_eq!(level_root(&mut app), "ruins");
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Save");
+    app.click_button("Slot 1: Empty");
+    app.click_button("Back");
+    app.click_button("Quit to Menu");
+
+    app.click_button("Play");
+    app.click_button("Meadow");
+    assert_eq!(level_root(&mut app), "meadow");
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Load");
+    app.click_button("Slot 1: Saved");
+    assert_eq!(app.resource::<CurrentLevel>().id, "ruins");
+    assert_eq!(level_root(&mut app), "ruins");
+    assert_eq!(app.count::<LevelRoot>(), 1);
+}
+
+fn player_health(app: &mut TestApp) -> Health {
+    let world = app.app.world_mut();
+    *world
+        .query_filtered::<&Health, With<Player>>()
+        .single(world)
+}
+
+#[test]
+fn hazards_hurt_the_player_until_the_game_is_over() {
+    let mut app = TestApp::in_game();
+    app.set_frame_time(Duration::from_millis(50));
+    let start = player_translation(&mut app);
+    assert_eq!(player_health(&mut app), Health::new(3));
+
+    let world = app.app.world_mut();
+    let spikes = world
+        .query_filtered::<&Transform, (With<Damage>, With<LevelEntity>)>()
+        .single(world)
+        .translation;
+    set_player_translation(&mut app, spikes.truncate().extend(1.0));
+    app.update();
+    assert_eq!(player_health(&mut app).current, 2);
+
+    // Invulnerable for a moment after the hit, which makes the sprite flash
+    let mut hidden = false;
+    for _ in 0..10 {
+        app.update();
+        let world = app.app.world_mut();
+        let (visibility, invulnerable) = world
+            .query_filtered::<(&Visibility, Has<Invulnerable>), With<Player>>()
+            .single(world);
+        assert!(invulnerable);
+        hidden |= *visibility == Visibility::Hidden;
+    }
+    assert!(hidden);
+    assert_eq!(player_health(&mut app).current, 2);
+
+    // Staying on the spikes hurts again once the invulnerability wore off
+    app.advance(60);
+    assert_eq!(app.state::<AppState>(), AppState::GameOver);
+    assert!(app.has_text("Game Over"));
+    assert_eq!(app.count::<Player>(), 0);
+    assert_eq!(app.count::<GameplayEntity>(), 0);
+
+    // Retrying starts the level over
+    app.click_button("Retry");
+    assert_eq!(app.state::<AppState>(), AppState::InGame);
+    assert_eq!(app.count::<Player>(), 1);
+    assert_eq!(app.count::<LevelRoot>(), 1);
+    assert_eq!(player_health(&mut app), Health::new(3));
+    assert_eq!(player_translation(&mut app), start);
+    assert!(app.resource::<CurrentLevel>().time < 0.5);
+
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Quit to Menu");
+    assert_eq!(app.state::<AppState>(), AppState::MainMenu);
+}
+
+#[test]
+fn pausing_shows_a_flashing_player() {
+    let mut app = TestApp::in_game();
+    // Short frames, so the player is still in the first hidden part of the flash when the menu opens
+    app.set_frame_time(Duration::from_millis(20));
+    let position = player_translation(&mut app);
+    app.app
+        .world_mut()
+        .spawn((Damage::default(), Transform::from_translation(position)));
+    app.update();
+    app.update();
+
+    let player_visibility = |app: &mut TestApp| {
+        let world = app.app.world_mut();
+        *world
+            .query_filtered::<&Visibility, With<Player>>()
+            .single(world)
+    };
+    assert_eq!(player_visibility(&mut app), Visibility::Hidden);
+    app.tap_key(KeyCode::Escape);
+    assert_eq!(app.state::<GameplayState>(), GameplayState::Paused);
+    assert_eq!(player_visibility(&mut app), Visibility::Inherited);
+}
+
+#[test]
+fn enemies_are_hurt_by_hazards_but_not_by_each_other() {
+    let mut app = TestApp::in_game();
+    despawn_level_spawners(&mut app);
+    let position = Vec3::new(-600.0, 400.0, 0.5);
+    for _ in 0..2 {
+        app.app.world_mut().spawn((
+            Enemy {
+                archetype: "slime".to_string(),
+                home: position.truncate(),
+            },
+            Health::new(1),
+            Transform::from_translation(position),
+            GameplayEntity,
+        ));
+    }
+    app.advance(5);
+    assert_eq!(app.count::<Enemy>(), 2);
+
+    app.app
+        .world_mut()
+        .spawn((Damage::default(), Transform::from_translation(position)));
+    app.update();
+    app.update();
+    assert_eq!(app.count::<Enemy>(), 0);
+}
+
+fn enemy_translation(app: &mut TestApp, enemy: Entity) -> Vec3 {
+    app.app.world().get::<Transform>(enemy).unwrap().translation
+}
+
+// The levels' own spawners would get in the way of counting enemies
+fn despawn_level_spawners(app: &mut TestApp) {
+    let world = app.app.world_mut();
+    let spawners: Vec<Entity> = world
+        .query_filtered::<Entity, With<EnemySpawner>>()
+        .iter(world)
+        .collect();
+    for spawner in spawners {
+        world.entity_mut(spawner).despawn_recursive();
+    }
+}
+
+#[test]
+fn spawners_follow_their_waves_and_caps() {
+    let mut app = TestApp::in_game();
+    despawn_level_spawners(&mut app);
+    app.set_frame_time(Duration::from_millis(100));
+    let spawner = app
+        .app
+        .world_mut()
+        .spawn((
+            EnemySpawner::new(SpawnRules {
+                archetype: "slime".to_string(),
+                interval: 0.1,
+                wave_size: 2,
+                wave_delay: 0.5,
+                waves: Some(2),
+                max_alive: 3,
+                radius: 16.0,
+            }),
+            Transform::from_xyz(-600.0, 400.0, 0.0),
+        ))
+        .id();
+
+    // The cap holds back the last enemy of the second wave
+    app.advance(20);
+    assert_eq!(app.count::<Enemy>(), 3);
+    let world = app.app.world_mut();
+    let enemies: Vec<Entity> = world
+        .query_filtered::<Entity, (With<Enemy>, With<Sprite>, With<GameplayEntity>)>()
+        .iter(world)
+        .collect();
+    assert_eq!(enemies.len(), 3);
+    for enemy in enemies {
+        world.entity_mut(enemy).despawn_recursive();
+    }
+    app.advance(20);
+    assert_eq!(app.count::<Enemy>(), 1);
+    assert!(app
+        .app
+        .world()
+        .get::<EnemySpawner>(spawner)
+        .unwrap()
+        .is_done());
+
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Quit to Menu");
+    assert_eq!(app.count::<Enemy>(), 0);
+}
+
+#[test]
+fn spawners_of_unknown_archetypes_stop_without_changing_their_rules() {
+    let mut app = TestApp::in_game();
+    despawn_level_spawners(&mut app);
+    app.set_frame_time(Duration::from_millis(100));
+    let rules = SpawnRules {
+        archetype: "dragon".to_string(),
+        interval: 0.1,
+        ..default()
+    };
+    let spawner = app
+        .app
+        .world_mut()
+        .spawn(EnemySpawner::new(rules.clone()))
+        .id();
+
+    app.advance(5);
+    assert_eq!(app.count::<Enemy>(), 0);
+    let spawner = app.app.world().get::<EnemySpawner>(spawner).unwrap();
+    assert_eq!(spawner.rules, rules);
+    assert!(!spawner.is_done());
+}
+
+#[test]
+fn enemies_move_by_their_behaviours() {
+    let mut app = TestApp::in_game();
+    despawn_level_spawners(&mut app);
+    app.set_frame_time(Duration::from_secs_f32(1.0 / 60.0));
+    let player = player_translation(&mut app).truncate();
+    let mut spawn = |archetype: &str, position: Vec2| {
+        app.app
+            .world_mut()
+            .spawn((
+                Enemy {
+                    archetype: archetype.to_string(),
+                    home: position,
+                },
+                Transform::from_translation(position.extend(0.5)),
+                GameplayEntity,
+            ))
+            .id()
+    };
+    let bat = spawn("bat", player + Vec2::new(150.0, 0.0));
+    let wisp = spawn("wisp", player + Vec2::new(-100.0, 0.0));
+    let guard_home = Vec2::new(-800.0, 500.0);
+    let guard = spawn("guard", guard_home);
+
+    app.advance(30);
+    let distance =
+        |app: &mut TestApp, enemy| enemy_translation(app, enemy).truncate().distance(player);
+    assert!(distance(&mut app, bat) < 140.0);
+    assert!(distance(&mut app, wisp) > 110.0);
+    // The guard walks to the second point of its patrol, to the right
+    let guard_position = enemy_translation(&mut app, guard).truncate();
+    assert!(guard_position.x > guard_home.x + 10.0);
+    assert!((guard_position.y - guard_home.y).abs() < 1.0);
+}
+
+fn award_pickup(app: &mut TestApp, points: u32) {
+    let position = player_translation(app);
+    app.app
+        .world_mut()
+        .spawn((Pickup { points }, Transform::from_translation(position)));
+    app.update();
+}
+
+#[test]
+fn pickups_award_points_with_combos() {
+    let mut app = TestApp::in_game();
+    app.set_frame_time(Duration::from_millis(100));
+    let pickups = app.count::<Pickup>();
+    assert_eq!(app.resource::<Score>().points, 0);
+
+    // Collecting pickups in quick succession multiplies their points
+    award_pickup(&mut app, 10);
+    assert_eq!(app.resource::<Score>().points, 10);
+    award_pickup(&mut app, 10);
+    award_pickup(&mut app, 10);
+    assert_eq!(app.resource::<Score>().points, 10 + 20 + 30);
+    assert_eq!(app.count::<Pickup>(), pickups);
+
+    // Waiting too long ends the combo
+    app.advance(25);
+    assert_eq!(app.resource::<Score>().combo, 0);
+    award_pickup(&mut app, 10);
+    assert_eq!(app.resource::<Score>().points, 70);
+}
+
+#[test]
+fn collected_pickups_stay_collected_in_saves() {
+    let mut app = TestApp::in_game();
+    let pickups = app.count::<Pickup>();
+    assert!(pickups > 0);
+    app.update();
+    let world = app.app.world_mut();
+    let (pickup, transform) = world
+        .query_filtered::<(&Pickup, &Transform), With<Sprite>>()
+        .iter(world)
+        .next()
+        .map(|(pickup, transform)| (*pickup, *transform))
+        .unwrap();
+    set_player_translation(&mut app, transform.translation.truncate().extend(1.0));
+    app.update();
+    assert_eq!(app.count::<Pickup>(), pickups - 1);
+    assert_eq!(app.resource::<Score>().points, pickup.points);
+
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Save");
+    app.click_button("Slot 1: Empty");
+    app.click_button("Back");
+    app.click_button("Quit to Menu");
+
+    // A new game has every pickup again, the save doesn't
+    app.click_button("Play");
+    app.click_button("Meadow");
+    assert_eq!(app.count::<Pickup>(), pickups);
+    assert_eq!(app.resource::<Score>().points, 0);
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Load");
+    app.click_button("Slot 1: Saved");
+    assert_eq!(app.count::<Pickup>(), pickups - 1);
+    assert_eq!(app.resource::<Score>().points, pickup.points);
+}
+
+#[test]
+fn collected_pickups_play_their_sound() {
+    let mut app = TestApp::in_game();
+    let world = app.app.world_mut();
+    let position = world
+        .query_filtered::<&Transform, With<Player>>()
+        .single(world)
+        .translation;
+    world.spawn((Pickup::default(), Transform::from_translation(position)));
+    app.update();
+
+    // Sound effects are never started paused, only the flying loop waits for the player to move
+    let pickup_sound = app.resource::<AudioAssets>().pickup.clone();
+    let sounds: Vec<PlaySfx> = app
+        .resource::<Events<PlaySfx>>()
+        .iter_current_update_events()
+        .cloned()
+        .collect();
+    assert_eq!(sounds, vec![PlaySfx(pickup_sound)]);
+}
+
+#[test]
+fn high_scores_are_entered_after_a_run() {
+    let mut app = TestApp::in_main_menu();
+    app.click_button("High Scores");
+    assert!(app.has_text("No high scores yet"));
+    app.click_button("Back");
+
+    // A run without points doesn't make it into the table
+    app.click_button("Play");
+    app.click_button("Meadow");
+    let world = app.app.world_mut();
+    let exit = world
+        .query_filtered::<&Transform, With<LevelTrigger>>()
+        .single(world)
+        .translation;
+    set_player_translation(&mut app, exit);
+    app.update();
+    app.update();
+    assert_eq!(app.state::<AppState>(), AppState::LevelComplete);
+    assert!(app.has_text("Score 0"));
+    assert!(app.find_button("Save Score").is_none());
+
+    app.click_button("Level Select");
+    app.click_button("Meadow");
+    app.app.world_mut().resource_mut::<Score>().points = 120;
+    set_player_translation(&mut app, exit);
+    app.update();
+    app.update();
+    assert!(app.has_text("Score 120"));
+    assert!(app.has_text("New high score: 120! Enter your name"));
+
+    // Enter saves the name instead of pressing a button
+    app.type_text("Adaa");
+    app.tap_key(KeyCode::Backspace);
+    assert!(app.has_text("Ada_"));
+    app.tap_key(KeyCode::Enter);
+    assert_eq!(app.state::<AppState>(), AppState::LevelComplete);
+    assert!(app.has_text("Saved as #1 of the high scores"));
+    assert!(app.find_button("Save Score").is_none());
+    let entries = &app.resource::<HighScores>().entries;
+    assert_eq!(entries.len(), 1);
+    assert_eq!((entries[0].name.as_str(), entries[0].points), ("Ada", 120));
+
+    // The table is kept for the next session
+    let mut app = TestApp::in_main_menu();
+    app.click_button("High Scores");
+    assert_eq!(app.state::<AppState>(), AppState::HighScores);
+    assert!(app.has_text("1. Ada"));
+    assert!(app.has_text("Meadow  120"));
+    app.click_button("Back");
+    assert_eq!(app.state::<AppState>(), AppState::MainMenu);
+}
+
+fn hud_visibility(app: &mut TestApp) -> Visibility {
+    let world = app.app.world_mut();
+    *world
+        .query_filtered::<&Visibility, With<HudRoot>>()
+        .single(world)
+}
+
+#[test]
+fn hud_shows_the_running_game() {
+    let mut app = TestApp::in_game();
+    app.update();
+    assert_eq!(app.count::<HudRoot>(), 1);
+    assert!(app.has_text("Score 0"));
+    assert!(app.has_text("0:00.0"));
+    let tracked = app.count::<Player>() + app.count::<Enemy>() + app.count::<Pickup>();
+    assert_eq!(app.count::<MinimapDot>(), tracked);
+
+    award_pickup(&mut app, 10);
+    award_pickup(&mut app, 10);
+    app.update();
+    assert!(app.has_text("Score 30"));
+    assert!(app.has_text("x2"));
+
+    // Hidden behind the overlay menus
+    app.tap_key(KeyCode::Escape);
+    app.update();
+    assert_eq!(hud_visibility(&mut app), Visibility::Hidden);
+    app.tap_key(KeyCode::Escape);
+    app.update();
+    assert_eq!(hud_visibility(&mut app), Visibility::Inherited);
+
+    // Save games neither store nor duplicate it
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Save");
+    app.click_button("Slot 1: Empty");
+    app.click_button("Back");
+    app.click_button("Load");
+    app.click_button("Slot 1: Saved");
+    app.update();
+    assert_eq!(app.count::<HudRoot>(), 1);
+    assert!(app.has_text("Score 30"));
+
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Quit to Menu");
+    assert_eq!(app.count::<HudRoot>(), 0);
+    assert_eq!(app.count::<MinimapDot>(), 0);
+}
+
+fn spawn_test_widget(parent: &mut ChildBuilder) {
+    parent.spawn(Text::new("Test Widget"));
+}
+
+#[test]
+fn plugins_can_add_hud_widgets() {
+    let mut app = TestApp::in_main_menu();
+    app.app
+        .add_hud_widget(HudAnchor::BottomRight, spawn_test_widget);
+    app.click_button("Play");
+    app.click_button("Meadow");
+
+    let world = app.app.world_mut();
+    let parent = world
+        .query::<(&Text, &Parent)>()
+        .iter(world)
+        .find(|(text, _)| text.0 == "Test Widget")
+        .map(|(_, parent)| parent.get())
+        .unwrap();
+    assert_eq!(
+        world.get::<HudAnchor>(parent),
+        Some(&HudAnchor::BottomRight)
+    );
+}
+
+#[test]
+fn ui_scale_can_be_chosen_in_the_display_settings() {
+    let mut app = TestApp::in_main_menu();
+    app.spawn_primary_window();
+    app.click_button("Settings");
+
+    // Opens the list of scales, picking one closes it again
+    app.click_button("100%");
+    assert!(app.has_text("150%"));
+    app.click_button("125%");
+    assert!(!app.has_text("150%"));
+    app.click_button("Apply");
+    app.click_button("Keep");
+
+    assert_eq!(app.resource::<UiScale>().0, 1.25);
+    assert_eq!(app.resource::<SettingsFile>().display.ui_scale, 1.25);
+}
+
+#[test]
+fn volume_sliders_step_and_save_right_away() {
+    let mut app = TestApp::in_main_menu();
+    app.click_button("Settings");
+    app.click_button("Audio");
+
+    // The first slider is the master volume
+    app.click_button("-");
+    app.click_button("-");
+    assert!((app.resource::<AudioSettings>().master - 0.8).abs() < 1e-6);
+    assert!((app.resource::<SettingsFile>().audio.master - 0.8).abs() < 1e-6);
+
+    for _ in 0..3 {
+        app.click_button("+");
+    }
+    assert_eq!(app.resource::<AudioSettings>().master, 1.0);
+    assert!(app.has_text("100%"));
+}
+
+#[test]
+fn the_flying_loop_follows_the_sfx_volume() {
+    let mut app = TestApp::in_main_menu();
+    app.app.world_mut().resource_mut::<AudioSettings>().sfx = 0.0;
+    app.click_button("Play");
+    app.click_button("Meadow");
+    assert_eq!(app.resource::<FlyingAudio>().volume, 0.0);
+
+    // Unmuting keeps the loop quieter than the other sound effects
+    app.app.world_mut().resource_mut::<AudioSettings>().sfx = 1.0;
+    app.update();
+    let volume = app.resource::<FlyingAudio>().volume;
+    assert!(volume > 0.0 && volume < app.resource::<AudioSettings>().sfx_volume());
+}
+
+#[test]
+fn key_repeats_of_a_captured_key_are_ignored_until_it_is_released() {
+    let mut app = TestApp::in_game();
+    app.tap_key(KeyCode::Escape);
+    app.click_button("Settings");
+    app.click_button("Controls");
+    app.click_button("W");
+
+    // Escape cancels the capture instead of leaving the settings
+    app.press_key(KeyCode::Escape);
+    app.update();
+    assert!(app.has_text("Click a key to change it"));
+    // Holding the key makes the keyboard repeat the press
+    app.press_key(KeyCode::Escape);
+    app.update();
+    app.update();
+    assert_eq!(
+        app.state::<GameOverlayState>(),
+        GameOverlayState::SettingsMenu
+    );
+
+    app.release_key(KeyCode::Escape);
+    app.update();
+    app.tap_key(KeyCode::Escape);
+    app.update();
+    assert_eq!(app.state::<GameOverlayState>(), GameOverlayState::GameMenu);
+}
+
+#[test]
+fn settings_lists_scroll_with_the_mouse_wheel() {
+    let mut app = TestApp::in_main_menu();
+    app.click_button("Settings");
+    app.click_button("Controls");
+
+    // Without a layout the cursor position is set by hand
+    let world = app.app.world_mut();
+    let list = world
+        .query_filtered::<Entity, With<ControlsSettings>>()
+        .single(world);
+    world.entity_mut(list).insert(RelativeCursorPosition {
+        normalized_visible_node_rect: Rect::new(0.0, 0.0, 1.0, 1.0),
+        normalized: Some(Vec2::splat(0.5)),
+    });
+
+    app.scroll_mouse(-2.0);
+    app.update();
+    let offset = |app: &TestApp| {
+        app.app
+            .world()
+            .get::<ScrollPosition>(list)
+            .unwrap()
+            .offset_y
+    };
+    assert_eq!(offset(&app), 40.0);
+
+    app.scroll_mouse(5.0);
+    app.update();
+    assert_eq!(offset(&app), 0.0);
+}
+
+// Without a layout every button has no size and is skipped like a hidden one,
+// so the buttons a test navigates between get their position and size by hand
+fn place_button(app: &mut TestApp, label: &str, x: f32, y: f32) -> Entity {
+    let button = app.find_button(label).unwrap();
+    let mut entity = app.app.world_mut().entity_mut(button);
+    *entity
+        .get_mut::<ComputedNode>()
+        .unwrap()
+        .get_field_mut::<Vec2>("size")
+        .unwrap() = Vec2::new(100.0, 40.0);
+    entity.insert((
+        Transform::from_xyz(x, y, 0.0),
+        GlobalTransform::from_xyz(x, y, 0.0),
+    ));
+    button
+}
+
+fn focused(app: &TestApp) -> Option<Entity> {
+    app.resource::<FocusedEntity>().0
+}
+
+fn set_focused(app: &mut TestApp, entity: Entity) {
+    app.app.world_mut().resource_mut::<FocusedEntity>().0 = Some(entity);
+}
+
+#[test]
+fn the_first_press_focuses_the_top_left_button() {
+    let mut app = TestApp::in_main_menu();
+    place_button(&mut app, "Quit", 300.0, 100.0);
+    let top_left = place_button(&mut app, "Settings", 100.0, 100.0);
+    place_button(&mut app, "Play", 100.0, 200.0);
+    assert_eq!(focused(&app), None);
+
+    app.tap_key(KeyCode::ArrowDown);
+    assert_eq!(focused(&app), Some(top_left));
+}
+
+#[test]
+fn focus_navigation_follows_the_layout() {
+    let mut app = TestApp::in_main_menu();
+    // Two columns, the right one a bit lower
+    let left_top = place_button(&mut app, "Play", 100.0, 100.0);
+    let left_bottom = place_button(&mut app, "High Scores", 100.0, 200.0);
+    let right_top = place_button(&mut app, "Settings", 300.0, 130.0);
+    let right_bottom = place_button(&mut app, "Quit", 300.0, 230.0);
+    set_focused(&mut app, left_top);
+
+    app.tap_key(KeyCode::ArrowDown);
+    assert_eq!(focused(&app), Some(left_bottom));
+    // The button straight to the right wins over the closer one that is higher up
+    app.tap_key(KeyCode::ArrowRight);
+    assert_eq!(focused(&app), Some(right_bottom));
+    app.tap_key(KeyCode::ArrowUp);
+    assert_eq!(focused(&app), Some(right_top));
+    app.tap_key(KeyCode::ArrowLeft);
+    assert_eq!(focused(&app), Some(left_top));
+}
+
+#[test]
+fn focus_navigation_stops_at_the_edges() {
+    let mut app = TestApp::in_main_menu();
+    let top = place_button(&mut app, "Play", 100.0, 100.0);
+    let bottom = place_button(&mut app, "Settings", 100.0, 200.0);
+    set_focused(&mut app, top);
+
+    // Nothing is above or beside the top button, so the focus doesn't wrap around
+    for key in [KeyCode::ArrowUp, KeyCode::ArrowLeft, KeyCode::ArrowRight] {
+        app.tap_key(key);
+        assert_eq!(focused(&app), Some(top));
+    }
+    app.tap_key(KeyCode::ArrowDown);
+    app.tap_key(KeyCode::ArrowDown);
+    assert_eq!(focused(&app), Some(bottom));
+}
+
+#[test]
+fn hidden_buttons_are_skipped_by_the_focus() {
+    let mut app = TestApp::in_main_menu();
+    let top = place_button(&mut app, "Play", 100.0, 100.0);
+    let hidden = place_button(&mut app, "High Scores", 100.0, 200.0);
+    let bottom = place_button(&mut app, "Settings", 100.0, 300.0);
+    app.app
+        .world_mut()
+        .entity_mut(hidden)
+        .insert(ComputedNode::default());
+    set_focused(&mut app, top);
+
+    app.tap_key(KeyCode::ArrowDown);
+    assert_eq!(focused(&app), Some(bottom));
+}
+
+#[test]
+fn enter_presses_the_focused_button() {
+    let mut app = TestApp::in_main_menu();
+
+    // Space is left free for the game's own bindings, so holding it while a menu opens doesn't press anything
+    let settings = place_button(&mut app, "Settings", 100.0, 100.0);
+    set_focused(&mut app, settings);
+    app.tap_key(KeyCode::Space);
+    assert_eq!(app.state::<AppState>(), AppState::MainMenu);
+
+    for key in [KeyCode::Enter, KeyCode::NumpadEnter] {
+        let settings = place_button(&mut app, "Settings", 100.0, 100.0);
+        set_focused(&mut app, settings);
+        app.press_key(key);
+        app.update();
+        assert_eq!(
+            app.app.world().get::<Interaction>(settings),
+            Some(&Interaction::Pressed)
+        );
+        app.release_key(key);
+        app.update();
+        assert_eq!(app.state::<AppState>(), AppState::SettingsMenu);
+
+        app.click_button("Back");
+        assert_eq!(app.state::<AppState>(), AppState::MainMenu);
+    }
+}
+
+#[test]
+fn the_gamepad_south_button_presses_the_focused_button() {
+    let mut app = TestApp::in_main_menu();
+    let settings = place_button(&mut app, "Settings", 100.0, 100.0);
+    set_focused(&mut app, settings);
+    let gamepad = app.app.world_mut().spawn_empty().id();
+    app.app.world_mut().send_event(GamepadConnectionEvent::new(
+        gamepad,
+        GamepadConnection::Connected {
+            name: "Test Gamepad".to_string(),
+            vendor_id: None,
+            product_id: None,
+        },
+    ));
+    app.update();
+
+    app.app
+        .world_mut()
+        .send_event(RawGamepadEvent::Button(RawGamepadButtonChangedEvent::new(
+            gamepad,
+            GamepadButton::South,
+            1.0,
+        )));
+    app.update();
+    app.update();
+    assert_eq!(app.state::<AppState>(), AppState::SettingsMenu);
+}
+
+#[test]
+fn buttons_of_hidden_tabs_are_not_pressed() {
+    let mut app = TestApp::in_main_menu();
+    app.click_button("Settings");
+    let apply = place_button(&mut app, "Apply", 100.0, 100.0);
+    set_focused(&mut app, apply);
+
+    // Switching the tab hides the focused button
+    app.tap_key(KeyCode::Tab);
+    assert_eq!(focused(&app), None);
+
+    // Buttons in hidden containers have no size and ignore the focus
+    app.app
+        .world_mut()
+        .entity_mut(apply)
+        .insert(ComputedNode::default());
+    set_focused(&mut app, apply);
+    app.press_key(KeyCode::Enter);
+    app.update();
+    assert_eq!(
+        app.app.world().get::<Interaction>(apply),
+        Some(&Interaction::None)
+    );
+}